@@ -0,0 +1,62 @@
+//! Embeds the bundled registry snapshot
+//!
+//! `data/registry.json` (a snapshot of packages/core/src/prompts/registry.ts)
+//! is validated, minified and gzip-compressed into `$OUT_DIR/registry.json.gz`.
+//! The SHA-256 of the minified prompts, bundles and workflows goes to
+//! `$OUT_DIR/registry.sha256` so a running jfp can tell whether its database
+//! already holds these snapshots.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use sha2::{Digest, Sha256};
+
+const SNAPSHOT: &str = "data/registry.json";
+/// Embedded as-is by `registry`, but part of the snapshot hash
+const COMPANIONS: [&str; 2] = ["data/bundles.json", "data/workflows.json"];
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SNAPSHOT);
+    for path in COMPANIONS {
+        println!("cargo:rerun-if-changed={}", path);
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let raw = fs::read_to_string(SNAPSHOT).unwrap_or_else(|e| panic!("failed to read {}: {}", SNAPSHOT, e));
+    let prompts: serde_json::Value =
+        serde_json::from_str(&raw).unwrap_or_else(|e| panic!("{} is not valid JSON: {}", SNAPSHOT, e));
+    let Some(entries) = prompts.as_array() else {
+        panic!("{} must be a JSON array of prompts", SNAPSHOT);
+    };
+    for (i, prompt) in entries.iter().enumerate() {
+        for field in ["id", "title", "content"] {
+            if !prompt.get(field).is_some_and(serde_json::Value::is_string) {
+                panic!("{}: prompt #{} has no string `{}`", SNAPSHOT, i, field);
+            }
+        }
+    }
+
+    let minified = serde_json::to_vec(&prompts).expect("re-serialize snapshot");
+    let mut hasher = Sha256::new();
+    hasher.update(&minified);
+    for path in COMPANIONS {
+        let raw = fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e));
+        let value: serde_json::Value =
+            serde_json::from_str(&raw).unwrap_or_else(|e| panic!("{} is not valid JSON: {}", path, e));
+        hasher.update(serde_json::to_vec(&value).expect("re-serialize snapshot"));
+    }
+    let hash: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+
+    // The gzip header carries no timestamp, so builds are reproducible
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&minified).expect("compress snapshot");
+    let compressed = encoder.finish().expect("compress snapshot");
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    fs::write(out_dir.join("registry.json.gz"), compressed).expect("write compressed snapshot");
+    fs::write(out_dir.join("registry.sha256"), hash).expect("write snapshot hash");
+}
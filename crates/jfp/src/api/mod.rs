@@ -0,0 +1,337 @@
+//! Local HTTP API
+//!
+//! `jfp serve --http` answers JSON requests on a loopback port so editors
+//! and dashboards can query the library without spawning `jfp` per call.
+//! Response bodies are the `--json` outputs of the matching commands:
+//!
+//! - `GET /prompts[?category=&tag=&featured=true]` - as `jfp list`
+//! - `GET /prompts/{id}` - as `jfp show`
+//! - `GET /search?q=...[&limit=N]` - as `jfp search`
+//! - `GET|POST /render/{id}` - as `jfp render`; variables come from the
+//!   query string or a JSON body `{ "variables": { ... } }`
+//! - `GET /bundles` - as `jfp bundles`
+//!
+//! Every request needs `Authorization: Bearer <token>`, where the token is
+//! the contents of the token file (created with a random token, mode 0600,
+//! when missing). Errors are `{ error, message }` with a matching status.
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use rand::Rng;
+use serde::Serialize;
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::commands::bundles::BundlesOutput;
+use crate::commands::list::ListOutput;
+use crate::commands::render::RenderOutput;
+use crate::commands::search::SearchOutput;
+use crate::commands::show::ShowOutput;
+use crate::config;
+use crate::error::JfpError;
+use crate::storage::{Database, StorageError};
+use crate::template::{self, Variables};
+
+const TOKEN_FILE_NAME: &str = "http-token";
+
+/// Largest request body read for `POST /render/{id}`
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+/// Status code and JSON body for one request
+struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    fn ok(output: &impl Serialize) -> Self {
+        match serde_json::to_string_pretty(output) {
+            Ok(body) => Self { status: 200, body },
+            Err(e) => Self::error(500, "serialization_error", &e.to_string()),
+        }
+    }
+
+    fn error(status: u16, code: &str, message: &str) -> Self {
+        Self::payload(status, json!({ "error": code, "message": message }))
+    }
+
+    fn payload(status: u16, payload: serde_json::Value) -> Self {
+        Self {
+            status,
+            body: payload.to_string(),
+        }
+    }
+}
+
+impl From<StorageError> for Reply {
+    fn from(e: StorageError) -> Self {
+        Self::error(500, "database_error", &e.to_string())
+    }
+}
+
+/// Request handler over the local library
+pub struct Api {
+    db: Database,
+    token: String,
+}
+
+impl Api {
+    pub fn new(db: Database, token: String) -> Self {
+        Self { db, token }
+    }
+
+    /// Answer requests until the server stops
+    pub fn serve(&self, server: &Server) {
+        for mut request in server.incoming_requests() {
+            let reply = self.handle(&mut request);
+            let response = Response::from_string(reply.body)
+                .with_status_code(reply.status)
+                .with_header(header("Content-Type", "application/json"));
+            let response = if reply.status == 401 {
+                response.with_header(header("WWW-Authenticate", "Bearer"))
+            } else {
+                response
+            };
+            // A client that hung up is not our problem
+            let _ = request.respond(response);
+        }
+    }
+
+    fn handle(&self, request: &mut Request) -> Reply {
+        if !self.authorized(request) {
+            return Reply::error(401, "unauthorized", "Missing or invalid bearer token");
+        }
+
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let query = parse_query(query);
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(percent_decode)
+            .collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+        let method = request.method().clone();
+
+        match (&method, segments.as_slice()) {
+            (Method::Get, ["prompts"]) => self.list(&query),
+            (Method::Get, ["prompts", id]) => self.show(id),
+            (Method::Get, ["search"]) => self.search(&query),
+            (Method::Get, ["render", id]) => self.render(id, query.into_iter().collect()),
+            (Method::Post, ["render", id]) => match read_variables(request) {
+                Ok(body) => self.render(id, query.into_iter().chain(body).collect()),
+                Err(reply) => reply,
+            },
+            (Method::Get, ["bundles"]) => self.bundles(),
+            (_, ["prompts"] | ["prompts", _] | ["search"] | ["render", _] | ["bundles"]) => {
+                Reply::error(405, "method_not_allowed", &format!("{} is not supported here", method))
+            }
+            _ => Reply::error(404, "not_found", &format!("No such endpoint: {}", path)),
+        }
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        request
+            .headers()
+            .iter()
+            .filter(|h| h.field.equiv("Authorization"))
+            .filter_map(|h| h.value.as_str().strip_prefix("Bearer "))
+            .any(|token| constant_time_eq(token.trim().as_bytes(), self.token.as_bytes()))
+    }
+
+    fn list(&self, query: &[(String, String)]) -> Reply {
+        let category = param(query, "category");
+        let tag = param(query, "tag");
+        let featured = param(query, "featured").is_some_and(|f| f == "true" || f == "1");
+        match self.db.list_prompts_filtered(category, tag, featured, None) {
+            Ok(prompts) => Reply::ok(&ListOutput::new(&prompts)),
+            Err(e) => e.into(),
+        }
+    }
+
+    fn show(&self, id: &str) -> Reply {
+        match self.db.get_prompt(id) {
+            Ok(Some(prompt)) => Reply::ok(&ShowOutput::from(&prompt)),
+            Ok(None) => Reply::error(404, "not_found", &format!("Prompt not found: {}", id)),
+            Err(e) => e.into(),
+        }
+    }
+
+    fn search(&self, query: &[(String, String)]) -> Reply {
+        let q = param(query, "q").unwrap_or_default();
+        if q.trim().is_empty() {
+            return Reply::error(400, "empty_query", "Search query cannot be empty");
+        }
+        let limit = match param(query, "limit").map(str::parse::<usize>) {
+            None => DEFAULT_SEARCH_LIMIT,
+            Some(Ok(limit)) if (1..=100).contains(&limit) => limit,
+            Some(_) => return Reply::error(400, "invalid_limit", "Limit must be between 1 and 100"),
+        };
+
+        let results = match self.db.search(q, limit, None) {
+            Ok(r) => r,
+            Err(e) => return Reply::error(500, "search_error", &e.to_string()),
+        };
+        Reply::ok(&SearchOutput::new(q, &results))
+    }
+
+    fn render(&self, id: &str, provided: Variables) -> Reply {
+        let prompt = match self.db.get_prompt(id) {
+            Ok(Some(p)) => p,
+            Ok(None) => return Reply::error(404, "not_found", &format!("Prompt not found: {}", id)),
+            Err(e) => return e.into(),
+        };
+
+        let dynamic = std::env::current_dir()
+            .map(|cwd| template::dynamic_defaults(&cwd))
+            .unwrap_or_default();
+        let variables = match template::resolve(&prompt, provided, dynamic) {
+            Ok(v) => v,
+            Err(e) => {
+                let hint = "Pass values as query parameters or a JSON body { \"variables\": { ... } }";
+                return Reply::payload(422, JfpError::template(&e, hint).to_json());
+            }
+        };
+
+        let rendered = template::render(&prompt.content, &variables);
+        let unresolved = template::placeholders(&rendered);
+        Reply::ok(&RenderOutput {
+            id: prompt.id,
+            title: prompt.title,
+            rendered,
+            variables,
+            unresolved,
+            context: None,
+        })
+    }
+
+    fn bundles(&self) -> Reply {
+        match self.db.list_bundles() {
+            Ok(bundles) => Reply::ok(&BundlesOutput::new(&bundles)),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// Default token file location
+pub fn default_token_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join(TOKEN_FILE_NAME))
+}
+
+/// Read the token from `path`, creating the file with a fresh random token
+/// (readable only by the owner) when it does not exist
+pub fn load_or_create_token(path: &Path) -> io::Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(token) if token.trim().is_empty() => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("token file {} is empty", path.display()),
+        )),
+        Ok(token) => Ok(token.trim().to_string()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let bytes: [u8; 32] = rand::rng().random();
+            let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            write_private(path, &format!("{}\n", token))?;
+            Ok(token)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+/// Variables from a `{ "variables": { ... } }` request body
+fn read_variables(request: &mut Request) -> Result<Variables, Reply> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| Reply::error(400, "invalid_body", &e.to_string()))?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(Reply::error(413, "body_too_large", "Request body is larger than 1MB"));
+    }
+    if body.trim().is_empty() {
+        return Ok(Variables::new());
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_str(&body).map_err(|e| Reply::error(400, "invalid_body", &e.to_string()))?;
+    let Some(variables) = value.get("variables") else {
+        return Ok(Variables::new());
+    };
+    let Some(variables) = variables.as_object() else {
+        return Err(Reply::error(400, "invalid_body", "`variables` must be an object"));
+    };
+    Ok(template::variables_from_json(variables))
+}
+
+fn param<'a>(query: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    query.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+}
+
+/// Decoded `key=value` pairs of a query string, in order
+pub(crate) fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(&key.replace('+', " ")), percent_decode(&value.replace('+', " ")))
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes; malformed escapes are kept as written
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Compare secrets without exiting early on the first difference
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
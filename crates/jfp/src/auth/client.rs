@@ -0,0 +1,137 @@
+//! Premium API client
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 5 (api-client.ts): JSON requests
+//! with a 30s timeout and `Authorization: Bearer <token>` when signed in.
+//! Bodies are parsed only when the response says it is JSON, and an error
+//! message is taken from the `error` or `message` field.
+
+use std::time::Duration;
+
+use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
+use serde_json::Value;
+use tokio::runtime::Runtime;
+
+use super::AuthError;
+use crate::config;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Status and JSON body of one API response
+#[derive(Debug)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub data: Option<Value>,
+}
+
+impl ApiResponse {
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The `error` field of an error body (e.g. `authorization_pending`)
+    pub fn error_code(&self) -> Option<&str> {
+        self.data.as_ref()?.get("error")?.as_str()
+    }
+
+    /// Human-readable reason for a failed request
+    pub fn error_message(&self) -> String {
+        let field = |name| self.data.as_ref()?.get(name)?.as_str().map(str::to_string);
+        field("error_description")
+            .or_else(|| field("message"))
+            .or_else(|| field("error"))
+            .unwrap_or_else(|| format!("HTTP {}", self.status))
+    }
+}
+
+/// Blocking JSON client for the premium site and API
+///
+/// jfp is synchronous, so each client drives its requests on its own
+/// single-threaded runtime.
+pub struct ApiClient {
+    runtime: Runtime,
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl ApiClient {
+    /// Client for `base_url`, without credentials
+    pub fn new(base_url: &str) -> Result<Self, AuthError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(AuthError::Runtime)?;
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|source| AuthError::Request {
+                url: base_url.to_string(),
+                source,
+            })?;
+        Ok(Self {
+            runtime,
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: None,
+        })
+    }
+
+    /// Client for the premium API carrying the current session's token,
+    /// refreshed first if it has expired
+    pub fn authenticated() -> Result<Self, AuthError> {
+        let session = super::session()?;
+        let mut client = Self::new(&config::premium_api_url())?;
+        client.token = Some(session.access_token().to_string());
+        Ok(client)
+    }
+
+    /// GET `path` (relative to the base URL) with query parameters
+    pub fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<ApiResponse, AuthError> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = self.http.get(&url).query(query);
+        self.send(url, request)
+    }
+
+    /// POST a JSON body to `path` (relative to the base URL)
+    pub fn post(&self, path: &str, body: &Value) -> Result<ApiResponse, AuthError> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = self.http.post(&url).json(body);
+        self.send(url, request)
+    }
+
+    /// DELETE `path` (relative to the base URL)
+    pub fn delete(&self, path: &str) -> Result<ApiResponse, AuthError> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = self.http.delete(&url);
+        self.send(url, request)
+    }
+
+    fn send(&self, url: String, request: reqwest::RequestBuilder) -> Result<ApiResponse, AuthError> {
+        let mut request = request
+            .header(ACCEPT, "application/json")
+            .header(USER_AGENT, concat!("jfp/", env!("CARGO_PKG_VERSION")));
+        if let Some(token) = &self.token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+
+        self.runtime.block_on(async {
+            let response = request
+                .send()
+                .await
+                .map_err(|source| AuthError::Request { url, source })?;
+            let status = response.status().as_u16();
+            let is_json = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.contains("application/json"));
+            let data = if is_json {
+                // A malformed body is reported like a missing one
+                response.json::<Value>().await.ok()
+            } else {
+                None
+            };
+            Ok(ApiResponse { status, data })
+        })
+    }
+}
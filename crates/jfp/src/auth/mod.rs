@@ -0,0 +1,269 @@
+//! Premium authentication
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 4 (credentials.ts):
+//! - Credentials live in `<config_dir>/credentials.json`, written atomically
+//!   with mode 0600 (directory 0700); a missing, unreadable or invalid file
+//!   means logged out
+//! - Access tokens count as expired 5 minutes early to allow for clock skew,
+//!   and are refreshed with the refresh token when there is one
+//! - `JFP_TOKEN` bypasses the credentials file (CI); no user details then
+
+mod client;
+
+pub use client::ApiClient;
+
+use std::io;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::config;
+
+/// OAuth client id of the CLI
+pub const CLIENT_ID: &str = "jfp-cli";
+
+const CREDENTIALS_FILE_NAME: &str = "credentials.json";
+
+/// Tokens this close to expiry (seconds) are treated as expired
+const EXPIRY_BUFFER_SECS: i64 = 5 * 60;
+
+/// Subscription tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    Free,
+    Premium,
+}
+
+impl std::fmt::Display for Tier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Tier::Free => "free",
+            Tier::Premium => "premium",
+        })
+    }
+}
+
+/// Stored login (CredentialsSchema); also the shape of token responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Credentials {
+    pub access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// ISO 8601
+    pub expires_at: String,
+    pub email: String,
+    pub tier: Tier,
+    pub user_id: String,
+}
+
+impl Credentials {
+    /// Expired or about to be; an unreadable expiry counts as expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_in()
+            .is_none_or(|remaining| remaining <= EXPIRY_BUFFER_SECS)
+    }
+
+    /// Seconds until the access token expires (negative once it has)
+    pub fn expires_in(&self) -> Option<i64> {
+        let expires_at = DateTime::parse_from_rfc3339(&self.expires_at).ok()?;
+        Some((expires_at.with_timezone(&Utc) - Utc::now()).num_seconds())
+    }
+
+    /// Checks serde cannot express
+    pub fn is_valid(&self) -> bool {
+        !self.access_token.is_empty()
+            && !self.expires_at.is_empty()
+            && !self.user_id.is_empty()
+            && self.email.split_once('@').is_some_and(|(user, host)| !user.is_empty() && !host.is_empty())
+    }
+}
+
+/// Errors raised while authenticating
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("could not determine a configuration directory for credentials")]
+    NoConfigDir,
+
+    #[error("failed to update {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+
+    #[error("failed to start the HTTP client: {0}")]
+    Runtime(io::Error),
+
+    #[error("request to {url} failed: {source}")]
+    Request { url: String, source: reqwest::Error },
+
+    #[error("Not logged in. Run 'jfp login' to sign in")]
+    NotAuthenticated,
+
+    #[error("Session expired. Please run 'jfp login' again")]
+    SessionExpired(Box<Credentials>),
+}
+
+impl AuthError {
+    /// Stable error code for JSON output
+    pub fn code(&self) -> &'static str {
+        match self {
+            AuthError::NoConfigDir | AuthError::Io { .. } => "credentials_error",
+            AuthError::Runtime(_) | AuthError::Request { .. } => "network_error",
+            AuthError::NotAuthenticated => "not_authenticated",
+            AuthError::SessionExpired(_) => "session_expired",
+        }
+    }
+}
+
+/// How the current invocation is signed in
+pub enum Session {
+    /// `JFP_TOKEN`
+    Environment(String),
+
+    /// The credentials file; `refreshed` when the token was just renewed
+    Credentials { credentials: Credentials, refreshed: bool },
+}
+
+impl Session {
+    pub fn access_token(&self) -> &str {
+        match self {
+            Session::Environment(token) => token,
+            Session::Credentials { credentials, .. } => &credentials.access_token,
+        }
+    }
+}
+
+/// Credentials file location
+pub fn credentials_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join(CREDENTIALS_FILE_NAME))
+}
+
+/// Token from `JFP_TOKEN`, if set
+pub fn env_token() -> Option<String> {
+    std::env::var("JFP_TOKEN").ok().filter(|token| !token.trim().is_empty())
+}
+
+/// Stored credentials; `None` when missing, unreadable or invalid
+pub fn load_credentials() -> Option<Credentials> {
+    let content = std::fs::read_to_string(credentials_path()?).ok()?;
+    serde_json::from_str::<Credentials>(&content)
+        .ok()
+        .filter(Credentials::is_valid)
+}
+
+/// Write credentials atomically, readable only by the owner
+pub fn save_credentials(credentials: &Credentials) -> Result<(), AuthError> {
+    let path = credentials_path().ok_or(AuthError::NoConfigDir)?;
+    let io_error = |source| AuthError::Io {
+        path: path.clone(),
+        source,
+    };
+    let content = serde_json::to_string_pretty(credentials).map_err(|e| io_error(e.into()))?;
+    write_private(&path, content.as_bytes()).map_err(io_error)
+}
+
+/// Remove the credentials file; `false` when there was none
+pub fn clear_credentials() -> Result<bool, AuthError> {
+    let path = credentials_path().ok_or(AuthError::NoConfigDir)?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(source) => Err(AuthError::Io { path, source }),
+    }
+}
+
+/// Signed in, judging from local state only: `JFP_TOKEN`, or credentials
+/// that are unexpired or can be refreshed
+pub fn signed_in() -> bool {
+    env_token().is_some()
+        || load_credentials().is_some_and(|c| !c.is_expired() || c.refresh_token.is_some())
+}
+
+/// The current session, renewing an expired access token when possible
+///
+/// A rejected refresh means the session is over; a refresh that could not
+/// reach the server is reported as a network error instead.
+pub fn session() -> Result<Session, AuthError> {
+    if let Some(token) = env_token() {
+        return Ok(Session::Environment(token));
+    }
+    let credentials = load_credentials().ok_or(AuthError::NotAuthenticated)?;
+    if !credentials.is_expired() {
+        return Ok(Session::Credentials {
+            credentials,
+            refreshed: false,
+        });
+    }
+    let Some(refresh_token) = credentials.refresh_token.clone() else {
+        return Err(AuthError::SessionExpired(Box::new(credentials)));
+    };
+
+    match refresh_access_token(&refresh_token)? {
+        Some(refreshed) => {
+            save_credentials(&refreshed)?;
+            Ok(Session::Credentials {
+                credentials: refreshed,
+                refreshed: true,
+            })
+        }
+        None => Err(AuthError::SessionExpired(Box::new(credentials))),
+    }
+}
+
+/// Exchange a refresh token for new credentials; `None` when the server
+/// turns it down
+fn refresh_access_token(refresh_token: &str) -> Result<Option<Credentials>, AuthError> {
+    let client = ApiClient::new(&config::premium_url())?;
+    let response = client.post(
+        "/api/cli/token/refresh",
+        &json!({ "refresh_token": refresh_token, "client_id": CLIENT_ID }),
+    )?;
+    if !response.ok() {
+        return Ok(None);
+    }
+
+    let refreshed = response
+        .data
+        .and_then(|data| serde_json::from_value::<Credentials>(data).ok())
+        .filter(Credentials::is_valid)
+        .map(|mut credentials| {
+            // Servers that do not rotate refresh tokens leave it out
+            credentials.refresh_token.get_or_insert_with(|| refresh_token.to_string());
+            credentials
+        });
+    Ok(refreshed)
+}
+
+#[cfg(unix)]
+fn write_private(path: &std::path::Path, contents: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+
+    let parent = path.parent().unwrap_or(std::path::Path::new("."));
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(parent)?;
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let tmp = parent.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let result = (|| {
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &std::path::Path, contents: &[u8]) -> io::Result<()> {
+    crate::export::write_atomic(path, contents)
+}
@@ -0,0 +1,42 @@
+//! Command-line preprocessing
+//!
+//! The TS CLI accepts template variables as ad-hoc `--NAME=value` flags
+//! (`jfp render idea-wizard --PROJECT_NAME=Acme`). clap rejects unknown
+//! flags, so those are rewritten to `--var NAME=value` before parsing. Only
+//! UPPER_SNAKE_CASE names are touched, which no real jfp flag uses.
+
+use std::ffi::OsString;
+
+/// Rewrite `--NAME=value` arguments into `--var NAME=value`
+pub fn expand_variable_flags<I>(args: I) -> Vec<OsString>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut expanded = Vec::new();
+    let mut args = args.into_iter();
+    for arg in args.by_ref() {
+        if arg == "--" {
+            expanded.push(arg);
+            break;
+        }
+        match arg.to_str().and_then(variable_flag) {
+            Some(assignment) => {
+                expanded.push(OsString::from("--var"));
+                expanded.push(OsString::from(assignment));
+            }
+            None => expanded.push(arg),
+        }
+    }
+    expanded.extend(args);
+    expanded
+}
+
+/// `NAME=value` from `--NAME=value`, if NAME is UPPER_SNAKE_CASE
+fn variable_flag(arg: &str) -> Option<&str> {
+    let assignment = arg.strip_prefix("--")?;
+    let (name, _) = assignment.split_once('=')?;
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    valid.then_some(assignment)
+}
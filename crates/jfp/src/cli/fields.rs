@@ -0,0 +1,219 @@
+//! Field selection for `--fields`
+//!
+//! `--fields id,title,tags` keeps only those fields of a JSON payload, in
+//! the order given. Paths are dotted (`snippet.text`) and start at the top
+//! of the payload; arrays are transparent, so `prompts.id` keeps the id of
+//! every listed prompt. In NDJSON mode each record is its own top.
+//!
+//! A path is unknown when the command's published schema (`jfp schema`)
+//! does not name it, so optional fields that happen to be absent are still
+//! accepted; such commands reject it before they run. Commands without a
+//! schema check each payload instead, and anything goes below an empty
+//! array. `schema_version` and `command` are only fields with `--envelope`.
+
+use std::collections::BTreeSet;
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use crate::error::JfpError;
+
+/// How deep `$ref`s are followed when reading paths from a schema
+const MAX_SCHEMA_DEPTH: usize = 12;
+
+/// Parsed `--fields`
+pub struct Fields {
+    paths: Vec<String>,
+    tree: Tree,
+    /// Checked against the published schema already
+    checked: bool,
+}
+
+/// Selected keys in request order, each with the keys selected below it
+#[derive(Default)]
+struct Tree {
+    /// Keep the whole value: a path ended here
+    whole: bool,
+    children: Vec<(String, Tree)>,
+}
+
+impl Tree {
+    fn insert(&mut self, segments: &[&str]) {
+        if self.whole {
+            return;
+        }
+        let Some((first, rest)) = segments.split_first() else {
+            self.whole = true;
+            self.children.clear();
+            return;
+        };
+        let index = match self.children.iter().position(|(key, _)| key == first) {
+            Some(index) => index,
+            None => {
+                self.children.push((first.to_string(), Tree::default()));
+                self.children.len() - 1
+            }
+        };
+        self.children[index].1.insert(rest);
+    }
+}
+
+impl Fields {
+    /// Parse a comma-separated list of dotted paths, checking them against
+    /// the command's published `schema` when it has one; `records` accepts
+    /// paths within NDJSON records too
+    pub fn parse(spec: &str, schema: Option<&Value>, envelope: bool, records: bool) -> Result<Self, JfpError> {
+        let paths: Vec<String> = spec.split(',').map(|p| p.trim().to_string()).collect();
+        if let Some(bad) = paths.iter().find(|p| p.split('.').any(str::is_empty)) {
+            let message = format!("Invalid --fields path: {:?} (expected e.g. id,title,snippet.text)", bad);
+            return Err(JfpError::invalid("invalid_fields", message));
+        }
+
+        let mut tree = Tree::default();
+        for path in &paths {
+            tree.insert(&path.split('.').collect::<Vec<_>>());
+        }
+
+        let Some(schema) = schema else {
+            return Ok(Self { paths, tree, checked: false });
+        };
+        let mut known = BTreeSet::new();
+        let defs = schema.get("$defs").unwrap_or(&Value::Null);
+        schema_walk(schema, defs, "", &mut known, 0);
+        if !envelope {
+            known.remove("schema_version");
+            known.remove("command");
+        }
+        if records {
+            // Record paths are schema paths below a top-level array
+            let below: Vec<String> =
+                known.iter().filter_map(|p| p.split_once('.').map(|(_, rest)| rest.to_string())).collect();
+            known.extend(below);
+        }
+        check(&paths, &known)?;
+        Ok(Self { paths, tree, checked: true })
+    }
+
+    /// `value` cut down to the selected fields, checking them against it
+    /// when the command has no schema
+    pub fn project<'a>(&'a self, value: &'a Value) -> Result<Projection<'a>, JfpError> {
+        if !self.checked {
+            let mut known = BTreeSet::new();
+            value_walk(value, "", &mut known);
+            check(&self.paths, &known)?;
+        }
+        Ok(Projection { value, tree: &self.tree })
+    }
+}
+
+/// `unknown_field` unless every path is in `known`
+fn check(paths: &[String], known: &BTreeSet<String>) -> Result<(), JfpError> {
+    let unknown: Vec<&str> = paths.iter().map(String::as_str).filter(|p| !allows(known, p)).collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    let message = format!("Unknown field: {}", unknown.join(", "));
+    // `id` for `prompts.id`: paths start at the top of the payload
+    let nearby: Vec<&str> = unknown
+        .iter()
+        .flat_map(|path| known.iter().filter(move |k| k.ends_with(&format!(".{}", path))))
+        .map(String::as_str)
+        .collect();
+    let mut error = JfpError::invalid("unknown_field", message).with("fields", unknown);
+    if !nearby.is_empty() {
+        error = error.with("hint", format!("Did you mean {}?", nearby.join(", ")));
+    }
+    Err(error.with("available", known.iter().map(String::as_str).collect::<Vec<_>>()))
+}
+
+/// A JSON value that serializes only the selected fields
+pub struct Projection<'a> {
+    value: &'a Value,
+    tree: &'a Tree,
+}
+
+impl Serialize for Projection<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.tree.whole {
+            return self.value.serialize(serializer);
+        }
+        match self.value {
+            Value::Array(items) => {
+                serializer.collect_seq(items.iter().map(|value| Projection { value, tree: self.tree }))
+            }
+            Value::Object(map) => {
+                let mut out = serializer.serialize_map(None)?;
+                for (key, tree) in &self.tree.children {
+                    if let Some(value) = map.get(key) {
+                        out.serialize_entry(key, &Projection { value, tree })?;
+                    }
+                }
+                out.end()
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+/// Whether `path`, or a wildcard above it, is in `known`
+fn allows(known: &BTreeSet<String>, path: &str) -> bool {
+    if known.contains(path) || known.contains("*") {
+        return true;
+    }
+    path.match_indices('.').any(|(i, _)| known.contains(&format!("{}.*", &path[..i])))
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) }
+}
+
+/// Collect the object paths in `value`, looking through arrays; an empty
+/// array allows any path below it
+fn value_walk(value: &Value, prefix: &str, out: &mut BTreeSet<String>) {
+    match value {
+        Value::Array(items) if items.is_empty() => {
+            out.insert(join(prefix, "*"));
+        }
+        Value::Array(items) => items.iter().for_each(|item| value_walk(item, prefix, out)),
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = join(prefix, key);
+                value_walk(value, &path, out);
+                out.insert(path);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect the property paths a schema allows, following `$ref`s into
+/// `defs` and looking through arrays and `anyOf`/`oneOf`/`allOf`
+fn schema_walk(schema: &Value, defs: &Value, prefix: &str, out: &mut BTreeSet<String>, depth: usize) {
+    if depth > MAX_SCHEMA_DEPTH {
+        return;
+    }
+    if let Some(name) = schema.get("$ref").and_then(Value::as_str).and_then(|r| r.strip_prefix("#/$defs/"))
+        && let Some(def) = defs.get(name)
+    {
+        schema_walk(def, defs, prefix, out, depth + 1);
+    }
+    for key in ["anyOf", "oneOf", "allOf"] {
+        for branch in schema.get(key).and_then(Value::as_array).into_iter().flatten() {
+            schema_walk(branch, defs, prefix, out, depth + 1);
+        }
+    }
+    if let Some(items) = schema.get("items") {
+        schema_walk(items, defs, prefix, out, depth + 1);
+    }
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (key, property) in properties {
+            let path = join(prefix, key);
+            schema_walk(property, defs, &path, out, depth + 1);
+            out.insert(path);
+        }
+    }
+    if schema.get("additionalProperties").is_some_and(|extra| extra.is_object() || extra == true) {
+        out.insert(join(prefix, "*"));
+    }
+}
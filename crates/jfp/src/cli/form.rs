@@ -0,0 +1,455 @@
+//! Interactive variable form for `--fill`
+//!
+//! Walks a prompt's variables one field at a time, inline on stderr so
+//! stdout stays clean for the rendered prompt or JSON:
+//!
+//! - text: single-line input
+//! - multiline: editor buffer, Enter inserts a newline, Ctrl+D submits
+//! - select: arrow-key selection from the declared options
+//! - file / path: single-line input with Tab path completion
+//!
+//! Defaults are pre-filled and required fields cannot be submitted empty.
+//! Esc or Ctrl+C cancels the whole form.
+
+use std::io::{self, IsTerminal, Stderr, Write};
+use std::path::Path;
+
+use crossterm::cursor::{MoveToColumn, MoveUp};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{QueueableCommand, execute};
+use thiserror::Error;
+
+use crate::template::Variables;
+use crate::types::{PromptVariable, VariableType};
+
+/// Completion candidates listed under a path field
+const MAX_COMPLETIONS: usize = 8;
+
+/// Why the form did not produce values
+#[derive(Debug, Error)]
+pub enum FormError {
+    #[error("--fill needs an interactive terminal; pass --NAME=VALUE flags instead")]
+    NotATty,
+
+    #[error("User cancelled input")]
+    Cancelled,
+
+    #[error("terminal error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Whether the form can run: stdin and stdout must both be terminals
+///
+/// Checked before anything is drawn so agents piping output never hang on
+/// a form they cannot see.
+pub fn available() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Ask for each variable in turn, returning the entered values
+pub fn fill(variables: &[&PromptVariable]) -> Result<Variables, FormError> {
+    if !available() {
+        return Err(FormError::NotATty);
+    }
+
+    let mut out = io::stderr();
+    let mut values = Variables::new();
+    let _raw = RawMode::enable()?;
+
+    for var in variables {
+        let value = Field::new(var).run(&mut out)?;
+        values.insert(var.name.clone(), value);
+    }
+    Ok(values)
+}
+
+/// Restores cooked mode however the form exits
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Editable input state for one variable
+enum Input {
+    Line(Editor),
+    Multiline(Editor),
+    Select { options: Vec<String>, selected: usize },
+}
+
+/// Text buffer with a cursor (char index)
+struct Editor {
+    text: Vec<char>,
+    cursor: usize,
+}
+
+impl Editor {
+    fn new(initial: &str) -> Self {
+        let text: Vec<char> = initial.chars().collect();
+        Self {
+            cursor: text.len(),
+            text,
+        }
+    }
+
+    fn value(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.text.remove(self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    /// Start of the line containing the cursor
+    fn line_start(&self) -> usize {
+        self.text[..self.cursor]
+            .iter()
+            .rposition(|c| *c == '\n')
+            .map_or(0, |i| i + 1)
+    }
+
+    /// End of the line containing the cursor
+    fn line_end(&self) -> usize {
+        self.text[self.cursor..]
+            .iter()
+            .position(|c| *c == '\n')
+            .map_or(self.text.len(), |i| self.cursor + i)
+    }
+
+    /// Move to the same column on the previous line
+    fn up(&mut self) {
+        let start = self.line_start();
+        if start == 0 {
+            return;
+        }
+        let column = self.cursor - start;
+        let prev_start = self.text[..start - 1]
+            .iter()
+            .rposition(|c| *c == '\n')
+            .map_or(0, |i| i + 1);
+        self.cursor = prev_start + column.min(start - 1 - prev_start);
+    }
+
+    /// Move to the same column on the next line
+    fn down(&mut self) {
+        let end = self.line_end();
+        if end == self.text.len() {
+            return;
+        }
+        let column = self.cursor - self.line_start();
+        let next_start = end + 1;
+        let next_end = self.text[next_start..]
+            .iter()
+            .position(|c| *c == '\n')
+            .map_or(self.text.len(), |i| next_start + i);
+        self.cursor = next_start + column.min(next_end - next_start);
+    }
+
+    /// Shared cursor movement and editing keys
+    fn handle(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.text.len()),
+            KeyCode::Home => self.cursor = self.line_start(),
+            KeyCode::End => self.cursor = self.line_end(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.cursor = self.line_start()
+            }
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.cursor = self.line_end()
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let start = self.line_start();
+                self.text.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.insert(c),
+            _ => {}
+        }
+    }
+}
+
+/// One variable being filled in
+struct Field<'a> {
+    var: &'a PromptVariable,
+    input: Input,
+    /// Validation error or completion list shown under the input
+    message: Option<String>,
+    /// Row of the cursor within the last drawing, for redraws
+    cursor_row: u16,
+}
+
+impl<'a> Field<'a> {
+    fn new(var: &'a PromptVariable) -> Self {
+        let default = var.default.as_deref().unwrap_or("");
+        let input = match var.kind {
+            VariableType::Select if !var.options.is_empty() => Input::Select {
+                selected: var.options.iter().position(|o| o == default).unwrap_or(0),
+                options: var.options.clone(),
+            },
+            VariableType::Multiline => Input::Multiline(Editor::new(default)),
+            _ => Input::Line(Editor::new(default)),
+        };
+        Self {
+            var,
+            input,
+            message: None,
+            cursor_row: 0,
+        }
+    }
+
+    /// Read keys until the field is submitted
+    fn run(mut self, out: &mut Stderr) -> Result<String, FormError> {
+        loop {
+            self.draw(out)?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => return Err(self.cancel(out)),
+                KeyCode::Char('c') if ctrl => return Err(self.cancel(out)),
+                _ => {}
+            }
+
+            if let Some(value) = self.handle(key) {
+                match self.validate(&value) {
+                    Ok(()) => {
+                        self.finish(out, &value)?;
+                        return Ok(value);
+                    }
+                    Err(message) => self.message = Some(message),
+                }
+            }
+        }
+    }
+
+    /// Apply a key; returns the value when the field is submitted
+    fn handle(&mut self, key: KeyEvent) -> Option<String> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let completes_paths = matches!(self.var.kind, VariableType::File | VariableType::Path);
+        self.message = None;
+
+        match &mut self.input {
+            Input::Line(editor) => match key.code {
+                KeyCode::Enter => return Some(editor.value()),
+                KeyCode::Tab if completes_paths => {
+                    let (completed, candidates) = complete_path(&editor.value());
+                    *editor = Editor::new(&completed);
+                    if candidates.len() > 1 {
+                        self.message = Some(candidates.join("  "));
+                    }
+                }
+                _ => editor.handle(key),
+            },
+            Input::Multiline(editor) => match key.code {
+                KeyCode::Char('d') if ctrl => return Some(editor.value()),
+                KeyCode::Enter => editor.insert('\n'),
+                KeyCode::Up => editor.up(),
+                KeyCode::Down => editor.down(),
+                _ => editor.handle(key),
+            },
+            Input::Select { options, selected } => match key.code {
+                KeyCode::Enter => return Some(options[*selected].clone()),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    *selected = selected.checked_sub(1).unwrap_or(options.len() - 1)
+                }
+                KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                    *selected = (*selected + 1) % options.len()
+                }
+                _ => {}
+            },
+        }
+        None
+    }
+
+    fn validate(&self, value: &str) -> Result<(), String> {
+        if value.trim().is_empty() {
+            return if self.var.required {
+                Err(format!("{} is required", self.var.label))
+            } else {
+                Ok(())
+            };
+        }
+        if self.var.kind == VariableType::File && !Path::new(value).is_file() {
+            return Err(format!("File not found: {}", value));
+        }
+        Ok(())
+    }
+
+    /// Lines to draw and the cursor position (row, column) within them
+    fn lines(&self) -> (Vec<String>, (u16, u16)) {
+        let mut header = format!("? {}", self.var.label);
+        if let Some(description) = &self.var.description {
+            header.push_str(&format!(" ({})", description));
+        }
+        if self.var.required {
+            header.push_str(" *");
+        }
+
+        let mut lines = vec![header];
+        let cursor = match &self.input {
+            Input::Line(editor) => {
+                lines.push(format!("> {}", editor.value()));
+                (1, 2 + editor.cursor)
+            }
+            Input::Multiline(editor) => {
+                let before: String = editor.text[..editor.cursor].iter().collect();
+                let row = before.matches('\n').count();
+                let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count());
+                lines.extend(editor.value().split('\n').map(|l| format!("| {}", l)));
+                (1 + row, 2 + column)
+            }
+            Input::Select { options, selected } => {
+                for (i, option) in options.iter().enumerate() {
+                    let marker = if i == *selected { ">" } else { " " };
+                    lines.push(format!("  {} {}", marker, option));
+                }
+                (1 + selected, 2)
+            }
+        };
+
+        let hint = match (&self.input, self.var.kind) {
+            (Input::Select { .. }, _) => "up/down to choose, Enter to confirm",
+            (Input::Multiline(_), _) => "Enter for a new line, Ctrl+D to finish",
+            (_, VariableType::File | VariableType::Path) => "Tab to complete, Enter to confirm",
+            _ => "Enter to confirm, Esc to cancel",
+        };
+        lines.push(self.message.clone().unwrap_or_else(|| format!("  {}", hint)));
+
+        (lines, (cursor.0 as u16, cursor.1 as u16))
+    }
+
+    fn draw(&mut self, out: &mut Stderr) -> io::Result<()> {
+        let (lines, (row, column)) = self.lines();
+        self.clear(out)?;
+        for (i, line) in lines.iter().enumerate() {
+            if i == 0 {
+                out.queue(SetAttribute(Attribute::Bold))?
+                    .queue(Print(line))?
+                    .queue(SetAttribute(Attribute::Reset))?;
+            } else {
+                out.queue(Print("\r\n"))?.queue(Print(line))?;
+            }
+        }
+
+        let last = lines.len() as u16 - 1;
+        if last > row {
+            out.queue(MoveUp(last - row))?;
+        }
+        out.queue(MoveToColumn(column))?;
+        self.cursor_row = row;
+        out.flush()
+    }
+
+    /// Erase the previous drawing, leaving the cursor at its first line
+    fn clear(&self, out: &mut Stderr) -> io::Result<()> {
+        if self.cursor_row > 0 {
+            out.queue(MoveUp(self.cursor_row))?;
+        }
+        out.queue(MoveToColumn(0))?
+            .queue(Clear(ClearType::FromCursorDown))?;
+        Ok(())
+    }
+
+    /// Collapse the field to a one-line summary
+    fn finish(&mut self, out: &mut Stderr, value: &str) -> io::Result<()> {
+        let shown = value.lines().next().unwrap_or("");
+        let more = if value.lines().nth(1).is_some() { " ..." } else { "" };
+        self.clear(out)?;
+        execute!(
+            out,
+            Print(format!("{}: {}{}\r\n", self.var.label, shown, more))
+        )
+    }
+
+    fn cancel(&mut self, out: &mut Stderr) -> FormError {
+        let _ = self.clear(out).and_then(|_| out.flush());
+        FormError::Cancelled
+    }
+}
+
+/// Complete the last path segment against the filesystem
+///
+/// Returns the completed text and every candidate when ambiguous.
+fn complete_path(text: &str) -> (String, Vec<String>) {
+    let (dir, prefix) = match text.rfind('/') {
+        Some(i) => (&text[..=i], &text[i + 1..]),
+        None => ("", text),
+    };
+    let listing = if dir.is_empty() { Path::new(".") } else { Path::new(dir) };
+
+    let mut candidates: Vec<(String, bool)> = match std::fs::read_dir(listing) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().into_owned();
+                let hidden = name.starts_with('.') && !prefix.starts_with('.');
+                (name.starts_with(prefix) && !hidden)
+                    .then(|| (name, e.path().is_dir()))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    candidates.sort();
+
+    match candidates.as_slice() {
+        [] => (text.to_string(), Vec::new()),
+        [(name, is_dir)] => {
+            let slash = if *is_dir { "/" } else { "" };
+            (format!("{}{}{}", dir, name, slash), Vec::new())
+        }
+        _ => {
+            let names: Vec<String> = candidates.into_iter().map(|(n, _)| n).collect();
+            let common = common_prefix(&names);
+            let shown = names.iter().take(MAX_COMPLETIONS).cloned().collect();
+            (format!("{}{}", dir, common), shown)
+        }
+    }
+}
+
+fn common_prefix(names: &[String]) -> String {
+    let Some(first) = names.first() else {
+        return String::new();
+    };
+    let mut prefix: &str = first;
+    for name in &names[1..] {
+        while !name.starts_with(prefix) {
+            let mut chars = prefix.chars();
+            chars.next_back();
+            prefix = chars.as_str();
+        }
+    }
+    prefix.to_string()
+}
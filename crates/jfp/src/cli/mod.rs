@@ -1,3 +1,6 @@
 //! CLI utilities and output formatting
 
+pub mod args;
+pub mod fields;
+pub mod form;
 pub mod output;
@@ -1,27 +1,484 @@
 //! Output formatting utilities for JSON and terminal output
+//!
+//! JSON goes through [`print_output`] and [`print_record`]. Human output
+//! uses the rendering helpers at the end of the file: colors (off unless
+//! stdout is a terminal and neither `--no-color`, `NO_COLOR` nor
+//! `JFP_NO_COLOR` is set), terminal width, grapheme-safe truncation and
+//! wrapping, and aligned tables. Widths count terminal columns and skip
+//! color codes, so styled text lines up too.
 
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::io::{self, IsTerminal, Write};
+use std::process::ExitCode;
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+use crossterm::style::{Color, Stylize};
 use serde::Serialize;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-/// Print output in JSON or human-readable format
-pub fn print_output<T: Serialize + std::fmt::Display>(data: &T, use_json: bool) {
-    if use_json {
-        match serde_json::to_string_pretty(data) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing to JSON: {}", e),
-        }
-    } else {
-        println!("{}", data);
+use super::fields::Fields;
+use crate::error::JfpError;
+
+/// Version of the JSON output shapes, published by `jfp schema`
+///
+/// Bump it whenever a payload changes shape; the schema snapshot tests
+/// fail until it is.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// How commands print their results (`--format`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One pretty-printed JSON document (`--json`)
+    Json,
+    /// One compact JSON record per line; list, search, tags, categories and
+    /// export stream their rows
+    Ndjson,
+    /// Human-readable output (the default on a terminal)
+    Table,
+    /// Undecorated text, tab-separated fields, for `cut` and `awk`
+    Plain,
+}
+
+impl OutputFormat {
+    /// Whether the format is JSON, and errors go to stderr as JSON
+    pub fn is_json(self) -> bool {
+        matches!(self, Self::Json | Self::Ndjson)
+    }
+}
+
+/// Format chosen for this run
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Command name added to JSON payloads, when `--envelope` asked for it
+static ENVELOPE: OnceLock<String> = OnceLock::new();
+
+/// Fields kept in JSON payloads, when `--fields` asked for them
+static FIELDS: OnceLock<Fields> = OnceLock::new();
+
+/// Set the output format for the rest of the run
+pub fn set_format(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+/// The output format for this run (JSON until one is set)
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or(OutputFormat::Json)
+}
+
+/// Add `schema_version` and `command` to every JSON payload printed from
+/// now on
+pub fn enable_envelope(command: String) {
+    let _ = ENVELOPE.set(command);
+}
+
+/// Keep only `fields` of every JSON payload printed from now on
+pub fn select_fields(fields: Fields) {
+    let _ = FIELDS.set(fields);
+}
+
+/// `data` behind the envelope fields
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    schema_version: u32,
+    command: &'a str,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+/// Print a command's JSON payload on stdout
+///
+/// In NDJSON mode the payload is one compact line; commands that stream
+/// print each row with [`print_record`] instead.
+pub fn print_output<T: Serialize>(data: &T) -> Result<(), JfpError> {
+    print_selected(data, format() != OutputFormat::Ndjson)
+}
+
+/// Print one NDJSON record as soon as it is ready
+pub fn print_record<T: Serialize>(record: &T) -> Result<(), JfpError> {
+    print_selected(record, false)
+}
+
+/// Print JSON as-is: pretty, or on one line in NDJSON mode
+pub fn print_json<T: Serialize>(value: &T) -> Result<(), JfpError> {
+    let json = to_json(value, format() != OutputFormat::Ndjson);
+    write_line(&json.map_err(|e| JfpError::serialization(&e))?)
+}
+
+/// Print `data` cut down to `--fields`, with the envelope fields when
+/// `--envelope` asked for them
+fn print_selected<T: Serialize>(data: &T, pretty: bool) -> Result<(), JfpError> {
+    let Some(fields) = FIELDS.get() else {
+        return print_enveloped(data, pretty);
+    };
+    let value = serde_json::to_value(data).map_err(|e| JfpError::serialization(&e))?;
+    print_enveloped(&fields.project(&value)?, pretty)
+}
+
+fn print_enveloped<T: Serialize>(data: &T, pretty: bool) -> Result<(), JfpError> {
+    let json = match ENVELOPE.get() {
+        Some(command) => to_json(
+            &Envelope {
+                schema_version: SCHEMA_VERSION,
+                command,
+                data,
+            },
+            pretty,
+        ),
+        None => to_json(data, pretty),
+    };
+    write_line(&json.map_err(|e| JfpError::serialization(&e))?)
+}
+
+/// Write `text` and a newline on stdout
+///
+/// A reader that goes away early (`jfp --format ndjson list | head`) has
+/// all it wanted, so a broken pipe ends the run with exit code 0.
+fn write_line(text: &str) -> Result<(), JfpError> {
+    let mut stdout = io::stdout().lock();
+    match writeln!(stdout, "{}", text).and_then(|()| stdout.flush()) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => std::process::exit(0),
+        Err(e) => Err(JfpError::internal("io_error", e.to_string())),
     }
 }
 
-/// Print error in JSON or human-readable format
-pub fn print_error(message: &str, use_json: bool) {
+fn to_json<T: Serialize>(value: &T, pretty: bool) -> serde_json::Result<String> {
+    if pretty { serde_json::to_string_pretty(value) } else { serde_json::to_string(value) }
+}
+
+/// Print an error on stderr: its JSON payload, or the message and any hint
+pub fn print_error(error: &JfpError, use_json: bool) {
     if use_json {
-        let error = serde_json::json!({
-            "error": message
+        eprintln!("{}", error.to_json());
+        return;
+    }
+    eprintln!("Error: {}", error);
+    if let Some(hint) = error.hint() {
+        eprintln!("{}", hint);
+    }
+}
+
+/// Report `error` and exit with its category's code
+pub fn fail(error: JfpError, use_json: bool) -> ExitCode {
+    print_error(&error, use_json);
+    ExitCode::from(error.exit_code())
+}
+
+/// Width used when the terminal size is unknown
+const DEFAULT_WIDTH: usize = 80;
+
+/// Narrowest terminal width layout assumes
+const MIN_WIDTH: usize = 20;
+
+/// Marks truncated text
+const ELLIPSIS: &str = "…";
+
+/// Colors for the bundled categories; others hash into [`PALETTE`]
+const CATEGORY_COLORS: &[(&str, Color)] = &[
+    ("ideation", Color::Magenta),
+    ("documentation", Color::Blue),
+    ("automation", Color::Cyan),
+    ("refactoring", Color::Green),
+    ("debugging", Color::Red),
+    ("testing", Color::Yellow),
+];
+
+const PALETTE: &[Color] = &[
+    Color::Magenta,
+    Color::Blue,
+    Color::Cyan,
+    Color::Green,
+    Color::Red,
+    Color::Yellow,
+    Color::DarkCyan,
+    Color::DarkMagenta,
+];
+
+/// Whether human output may use color
+static COLOR: OnceLock<bool> = OnceLock::new();
+
+/// Whether to color output: only for people, so only table output on a
+/// terminal, and not when `--no-color`, `NO_COLOR` or `JFP_NO_COLOR` opts
+/// out. An empty variable counts as unset (see no-color.org).
+pub fn color_enabled(
+    format: OutputFormat,
+    no_color_flag: bool,
+    no_color_env: Option<&OsStr>,
+    jfp_no_color_env: Option<&OsStr>,
+    is_tty: bool,
+) -> bool {
+    let set = |value: Option<&OsStr>| value.is_some_and(|value| !value.is_empty());
+    format == OutputFormat::Table && !no_color_flag && !set(no_color_env) && !set(jfp_no_color_env) && is_tty
+}
+
+/// Turn colors on or off for the rest of the run
+pub fn set_color(enabled: bool) {
+    let _ = COLOR.set(enabled);
+}
+
+fn color() -> bool {
+    COLOR.get().copied().unwrap_or(false)
+}
+
+/// `text` in `color`, when colors are on
+pub fn paint(text: &str, color: Color) -> Cow<'_, str> {
+    if self::color() { Cow::Owned(text.with(color).to_string()) } else { Cow::Borrowed(text) }
+}
+
+/// `text` in bold, when colors are on
+pub fn bold(text: &str) -> Cow<'_, str> {
+    if color() { Cow::Owned(text.bold().to_string()) } else { Cow::Borrowed(text) }
+}
+
+/// `text` dimmed, when colors are on
+pub fn dim(text: &str) -> Cow<'_, str> {
+    if color() { Cow::Owned(text.dim().to_string()) } else { Cow::Borrowed(text) }
+}
+
+/// A category name in its color
+pub fn category(name: &str) -> Cow<'_, str> {
+    let color = CATEGORY_COLORS
+        .iter()
+        .find(|(category, _)| *category == name)
+        .map(|(_, color)| *color)
+        .unwrap_or_else(|| {
+            let hash = name.bytes().fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+            PALETTE[hash % PALETTE.len()]
         });
-        eprintln!("{}", serde_json::to_string_pretty(&error).unwrap_or_default());
+    paint(name, color)
+}
+
+/// Markers around highlighted matches: bold, or nothing without color
+pub fn highlight_markers() -> (&'static str, &'static str) {
+    if color() { ("\x1b[1m", "\x1b[22m") } else { ("", "") }
+}
+
+/// Terminal width in columns: the terminal's, else `COLUMNS`, else 80;
+/// never less than [`MIN_WIDTH`]
+pub fn width() -> usize {
+    let columns = if std::io::stdout().is_terminal() {
+        crossterm::terminal::size().ok().map(|(columns, _)| columns as usize)
     } else {
-        eprintln!("Error: {}", message);
+        None
+    };
+    columns
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .filter(|&columns| columns > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+        .max(MIN_WIDTH)
+}
+
+/// Columns `text` takes on screen, not counting color codes
+pub fn display_width(text: &str) -> usize {
+    strip_ansi(text).width()
+}
+
+/// `text` cut to at most `max` columns, ending in `…` when cut; never
+/// splits a character or grapheme cluster
+pub fn truncate(text: &str, max: usize) -> Cow<'_, str> {
+    if text.width() <= max {
+        return Cow::Borrowed(text);
+    }
+    let Some(budget) = max.checked_sub(ELLIPSIS.width()) else {
+        return Cow::Borrowed("");
+    };
+    let mut used = 0;
+    let mut out = String::new();
+    for grapheme in text.graphemes(true) {
+        let width = grapheme.width();
+        if used + width > budget {
+            break;
+        }
+        used += width;
+        out.push_str(grapheme);
+    }
+    out.truncate(out.trim_end().len());
+    out.push_str(ELLIPSIS);
+    Cow::Owned(out)
+}
+
+/// `text` wrapped at word boundaries into lines of at most `max` columns;
+/// words longer than a line are broken between graphemes
+pub fn wrap(text: &str, max: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for word in paragraph.split_whitespace() {
+            let mut word = Cow::Borrowed(word);
+            let mut width = display_width(&word);
+            if used > 0 && used + 1 + width <= max {
+                line.push(' ');
+                line.push_str(&word);
+                used += 1 + width;
+                continue;
+            }
+            if used > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            while width > max {
+                let (head, tail) = split_at_width(&word, max);
+                lines.push(head.to_string());
+                word = Cow::Owned(tail.to_string());
+                width = display_width(&word);
+            }
+            line.push_str(&word);
+            used = width;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Split `text` after the graphemes that fit in `max` columns
+fn split_at_width(text: &str, max: usize) -> (&str, &str) {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > max {
+            return text.split_at(index.max(grapheme.len()));
+        }
+    }
+    (text, "")
+}
+
+/// `text` without ANSI escape sequences
+fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI: ESC [ parameters final-byte
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Columns of text printed aligned under bold headers
+///
+/// Cells may be colored. The last column is truncated so rows fit the
+/// terminal.
+pub struct Table {
+    headers: Vec<&'static str>,
+    right: Vec<bool>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            right: vec![false; headers.len()],
+            rows: Vec::new(),
+        }
+    }
+
+    /// Right-align a column (for numbers)
+    pub fn align_right(mut self, column: usize) -> Self {
+        self.right[column] = true;
+        self
+    }
+
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    pub fn print(&self) {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.width()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(display_width(cell));
+            }
+        }
+        // Two-space indent, and two spaces between columns
+        let last = widths.len() - 1;
+        let fixed: usize = 2 + widths[..last].iter().map(|w| w + 2).sum::<usize>();
+        widths[last] = widths[last].min(width().saturating_sub(fixed).max(MIN_WIDTH));
+
+        let headers: Vec<String> = self.headers.iter().map(|h| h.to_string()).collect();
+        println!("{}", bold(&self.line(&headers, &widths)));
+        for row in &self.rows {
+            println!("{}", self.line(row, &widths));
+        }
+    }
+
+    fn line(&self, cells: &[String], widths: &[usize]) -> String {
+        let last = widths.len() - 1;
+        let mut parts = Vec::with_capacity(cells.len());
+        for (column, (cell, &width)) in cells.iter().zip(widths).enumerate() {
+            let cell = if column == last && display_width(cell) > width {
+                truncate(&strip_ansi(cell), width).into_owned()
+            } else {
+                cell.clone()
+            };
+            let pad = " ".repeat(width.saturating_sub(display_width(&cell)));
+            parts.push(if self.right[column] {
+                pad + &cell
+            } else if column == last {
+                cell
+            } else {
+                cell + &pad
+            });
+        }
+        format!("  {}", parts.join("  "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(format: OutputFormat, flag: bool, no_color: Option<&str>, jfp_no_color: Option<&str>, tty: bool) -> bool {
+        color_enabled(format, flag, no_color.map(OsStr::new), jfp_no_color.map(OsStr::new), tty)
+    }
+
+    #[test]
+    fn table_output_on_a_terminal_is_colored() {
+        assert!(enabled(OutputFormat::Table, false, None, None, true));
+    }
+
+    #[test]
+    fn each_opt_out_disables_color() {
+        assert!(!enabled(OutputFormat::Table, true, None, None, true));
+        assert!(!enabled(OutputFormat::Table, false, Some("1"), None, true));
+        assert!(!enabled(OutputFormat::Table, false, None, Some("1"), true));
+        assert!(!enabled(OutputFormat::Table, false, None, None, false));
+        for format in [OutputFormat::Json, OutputFormat::Ndjson, OutputFormat::Plain] {
+            assert!(!enabled(format, false, None, None, true), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn empty_no_color_variables_are_unset() {
+        assert!(enabled(OutputFormat::Table, false, Some(""), None, true));
+        assert!(enabled(OutputFormat::Table, false, None, Some(""), true));
+    }
+
+    #[test]
+    fn truncate_keeps_to_narrow_widths() {
+        assert_eq!(truncate("testing, review", 5), "test…");
+        assert_eq!(truncate("testing, review", 1), "…");
+        assert_eq!(truncate("testing, review", 0), "");
+        assert_eq!(truncate("résumé", 6), "résumé");
+    }
+
+    #[test]
+    fn wrap_keeps_to_narrow_widths() {
+        assert_eq!(wrap("one two three", 5), ["one", "two", "three"]);
+        assert_eq!(wrap("abcdefg", 3), ["abc", "def", "g"]);
+        assert!(wrap("Crème brûlée 👩‍👩‍👧‍👦 family", 4).iter().all(|line| display_width(line) <= 4));
     }
 }
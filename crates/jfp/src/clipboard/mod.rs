@@ -0,0 +1,200 @@
+//! Clipboard access
+//!
+//! Port of packages/cli/src/lib/clipboard.ts. External tools are probed on
+//! `PATH` in a platform-dependent order and the first one that accepts the
+//! text wins:
+//!
+//! - Linux: `wl-copy`, `xclip`, `xsel`, `pbcopy`, `clip.exe` (WSL)
+//! - macOS: `pbcopy` first
+//! - Windows: `clip.exe` first
+//!
+//! When no tool works, or inside an SSH session where local tools would
+//! fill the remote machine's clipboard, the text is sent to the user's
+//! terminal as an OSC 52 escape sequence instead. That only happens when
+//! jfp is attached to a terminal; piped runs never emit escape sequences.
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+/// How long a clipboard tool may take before it is killed
+const TOOL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Largest OSC 52 payload (base64 bytes) most terminals accept
+const OSC52_MAX_BYTES: usize = 100_000;
+
+/// A way of reaching the system clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    WlCopy,
+    Xclip,
+    Xsel,
+    Pbcopy,
+    ClipExe,
+    Osc52,
+}
+
+impl Backend {
+    /// Name reported in command output
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::WlCopy => "wl-copy",
+            Self::Xclip => "xclip",
+            Self::Xsel => "xsel",
+            Self::Pbcopy => "pbcopy",
+            Self::ClipExe => "clip.exe",
+            Self::Osc52 => "osc52",
+        }
+    }
+
+    /// Executable and arguments for tool backends
+    fn command(self) -> Option<(&'static str, &'static [&'static str])> {
+        match self {
+            Self::WlCopy => Some(("wl-copy", &[])),
+            Self::Xclip => Some(("xclip", &["-selection", "clipboard"])),
+            Self::Xsel => Some(("xsel", &["--clipboard", "--input"])),
+            Self::Pbcopy => Some(("pbcopy", &[])),
+            Self::ClipExe => Some(("clip.exe", &[])),
+            Self::Osc52 => None,
+        }
+    }
+}
+
+/// Tool backends in the order they are tried on this platform
+fn tool_order() -> &'static [Backend] {
+    use Backend::*;
+    if cfg!(target_os = "macos") {
+        &[Pbcopy, WlCopy, Xclip, Xsel]
+    } else if cfg!(target_os = "windows") {
+        &[ClipExe]
+    } else {
+        &[WlCopy, Xclip, Xsel, Pbcopy, ClipExe]
+    }
+}
+
+/// Copy `text` to the clipboard, returning the backend that took it
+pub fn copy(text: &str) -> Option<Backend> {
+    let remote = is_ssh_session();
+    if remote && osc52(text) {
+        return Some(Backend::Osc52);
+    }
+
+    for &backend in tool_order() {
+        if let Some((program, args)) = backend.command()
+            && let Some(path) = find_executable(program)
+            && run_tool(&path, args, text)
+        {
+            return Some(backend);
+        }
+    }
+
+    (!remote && osc52(text)).then_some(Backend::Osc52)
+}
+
+fn is_ssh_session() -> bool {
+    ["SSH_TTY", "SSH_CONNECTION", "SSH_CLIENT"]
+        .iter()
+        .any(|var| env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
+
+/// Locate `program` on `PATH`
+fn find_executable(program: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Pipe `text` into a clipboard tool; true if it exits successfully in time
+fn run_tool(path: &Path, args: &[&str], text: &str) -> bool {
+    let child = Command::new(path)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return false;
+    };
+
+    let Some(mut stdin) = child.stdin.take() else {
+        return false;
+    };
+    // Written from another thread, so a tool that stops reading cannot hold
+    // us past the timeout; dropping stdin closes the pipe so the tool sees EOF
+    let (done, written) = mpsc::channel();
+    let text = text.to_owned();
+    thread::spawn(move || {
+        let _ = done.send(stdin.write_all(text.as_bytes()).is_ok());
+    });
+    wait_with_timeout(&mut child) && written.recv_timeout(TOOL_TIMEOUT).unwrap_or(false)
+}
+
+fn wait_with_timeout(child: &mut Child) -> bool {
+    let deadline = Instant::now() + TOOL_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.success(),
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
+
+/// Send `text` to the controlling terminal as an OSC 52 sequence
+///
+/// Written to the terminal device rather than stdout so piped output stays
+/// clean. Terminals give no acknowledgement, so success means "sent".
+fn osc52(text: &str) -> bool {
+    if !(std::io::stdout().is_terminal() || std::io::stderr().is_terminal()) {
+        return false;
+    }
+
+    let encoded = BASE64.encode(text);
+    if encoded.len() > OSC52_MAX_BYTES {
+        return false;
+    }
+
+    let mut sequence = format!("\x1b]52;c;{}\x07", encoded);
+    // tmux only forwards escape sequences wrapped in a DCS passthrough
+    if env::var_os("TMUX").is_some() {
+        sequence = format!("\x1bPtmux;\x1b{}\x1b\\", sequence);
+    }
+
+    OpenOptions::new()
+        .write(true)
+        .open(terminal_device())
+        .and_then(|mut tty| {
+            tty.write_all(sequence.as_bytes())?;
+            tty.flush()
+        })
+        .is_ok()
+}
+
+fn terminal_device() -> &'static str {
+    if cfg!(windows) { "CONOUT$" } else { "/dev/tty" }
+}
@@ -2,24 +2,36 @@
 
 use std::process::ExitCode;
 
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::output::{fail, print_output};
+
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// JSON output for about command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct AboutOutput {
+    name: &'static str,
+    version: &'static str,
+    description: &'static str,
+    author: &'static str,
+    website: &'static str,
+    repository: &'static str,
+}
+
 pub fn run(use_json: bool) -> ExitCode {
     if use_json {
-        let about = serde_json::json!({
-            "name": "jfp",
-            "version": VERSION,
-            "description": "Agent-optimized CLI for JeffreysPrompts.com",
-            "author": "Jeffrey Emanuel",
-            "website": "https://jeffreysprompts.com",
-            "repository": "https://github.com/Dicklesworthstone/jeffreysprompts.com"
-        });
-        match serde_json::to_string_pretty(&about) {
-            Ok(json) => println!("{}", json),
-            Err(e) => {
-                eprintln!("{{\"error\": \"Failed to serialize: {}\"}}", e);
-                return ExitCode::FAILURE;
-            }
+        let about = AboutOutput {
+            name: "jfp",
+            version: VERSION,
+            description: "Agent-optimized CLI for JeffreysPrompts.com",
+            author: "Jeffrey Emanuel",
+            website: "https://jeffreysprompts.com",
+            repository: "https://github.com/Dicklesworthstone/jeffreysprompts.com",
+        };
+        if let Err(e) = print_output(&about) {
+            return fail(e, use_json);
         }
     } else {
         println!("jfp v{}", VERSION);
@@ -0,0 +1,232 @@
+//! Logout and whoami command implementations
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (logout / whoami):
+//! - `logout`: refuses under `JFP_TOKEN` (`env_token`); `--revoke` also
+//!   calls `POST /cli/revoke`, and a failed revoke is a warning, not an error
+//! - `whoami`: `JFP_TOKEN` -> { authenticated, source: "environment" };
+//!   otherwise the stored user, after refreshing an expired token when a
+//!   refresh token allows it. Not logged in -> `not_authenticated`; expired
+//!   and not refreshable -> `session_expired`
+
+use std::process::ExitCode;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::auth::{self, ApiClient, AuthError, Session, Tier};
+use crate::cli::output::{fail, print_output};
+use crate::error::JfpError;
+
+/// JSON output for logout command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct LogoutOutput<'a> {
+    logged_out: bool,
+    /// Absent when nobody was logged in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<&'a str>,
+    /// Whether the server revoked the token (`--revoke`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    revoked: Option<bool>,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<&'a str>,
+}
+
+/// Where the session comes from
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum SessionSource {
+    /// `JFP_TOKEN`; no user details
+    Environment,
+    CredentialsFile,
+}
+
+/// JSON output for whoami command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WhoamiOutput<'a> {
+    authenticated: bool,
+    source: SessionSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tier: Option<Tier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<&'a str>,
+    /// Seconds until the token expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_in: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expired: Option<bool>,
+    /// Whether an expired token was just refreshed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refreshed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<&'a str>,
+}
+
+pub fn logout(revoke: bool, use_json: bool) -> ExitCode {
+    if auth::env_token().is_some() {
+        return fail(
+            JfpError::invalid("env_token", "Cannot logout when using JFP_TOKEN environment variable")
+                .with("hint", "Unset the JFP_TOKEN environment variable to logout"),
+            use_json,
+        );
+    }
+
+    let Some(credentials) = auth::load_credentials() else {
+        // A corrupt file still goes, so the next login starts clean
+        let _ = auth::clear_credentials();
+        if use_json {
+            let output = LogoutOutput {
+                logged_out: true,
+                email: None,
+                revoked: None,
+                message: "Not logged in (nothing to do)",
+                warning: None,
+            };
+            if let Err(e) = print_output(&output) {
+                return fail(e, use_json);
+            }
+        } else {
+            println!("Not logged in (nothing to do)");
+        }
+        return ExitCode::SUCCESS;
+    };
+
+    let warning = if revoke { revoke_session().err() } else { None };
+    let revoked = revoke && warning.is_none();
+
+    if let Err(e) = auth::clear_credentials() {
+        return fail(e.into(), use_json);
+    }
+
+    let message = format!("Logged out from {}", credentials.email);
+    if use_json {
+        let output = LogoutOutput {
+            logged_out: true,
+            email: Some(&credentials.email),
+            revoked: Some(revoked),
+            message: &message,
+            warning: warning.as_deref(),
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else {
+        if let Some(warning) = &warning {
+            eprintln!("Warning: {}", warning);
+        }
+        println!("{}", message);
+        if revoked {
+            println!("Token revoked on server");
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Revoke the stored session on the server
+fn revoke_session() -> Result<(), String> {
+    let client = ApiClient::authenticated().map_err(|e| format!("Could not revoke token on server ({})", e))?;
+    match client.post("/cli/revoke", &json!({})) {
+        Ok(response) if response.ok() => Ok(()),
+        Ok(response) => Err(format!(
+            "Could not revoke token on server ({})",
+            response.error_message()
+        )),
+        Err(_) => Err("Could not reach server to revoke token".to_string()),
+    }
+}
+
+pub fn whoami(use_json: bool) -> ExitCode {
+    let session = match auth::session() {
+        Ok(s) => s,
+        Err(AuthError::SessionExpired(credentials)) => {
+            return fail(
+                JfpError::denied("session_expired", "Session expired. Please run 'jfp login' again.")
+                    .with("authenticated", false)
+                    .with("email", credentials.email)
+                    .with("tier", json!(credentials.tier))
+                    .with("user_id", credentials.user_id)
+                    .with("expires_at", credentials.expires_at)
+                    .with("expired", true),
+                use_json,
+            );
+        }
+        Err(e @ AuthError::NotAuthenticated) => {
+            return fail(JfpError::from(e).with("authenticated", false), use_json);
+        }
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    let (credentials, refreshed) = match session {
+        Session::Environment(_) => {
+            if use_json {
+                let output = WhoamiOutput {
+                    authenticated: true,
+                    source: SessionSource::Environment,
+                    email: None,
+                    tier: None,
+                    user_id: None,
+                    expires_at: None,
+                    expires_in: None,
+                    expired: None,
+                    refreshed: None,
+                    message: Some("Authenticated via JFP_TOKEN environment variable"),
+                    note: Some("User details not available with token-based auth"),
+                };
+                if let Err(e) = print_output(&output) {
+                    return fail(e, use_json);
+                }
+            } else {
+                println!("Authenticated via JFP_TOKEN environment variable");
+                println!("User details not available with token-based auth");
+            }
+            return ExitCode::SUCCESS;
+        }
+        Session::Credentials { credentials, refreshed } => (credentials, refreshed),
+    };
+
+    let expires_in = credentials.expires_in().unwrap_or_default();
+    if use_json {
+        let output = WhoamiOutput {
+            authenticated: true,
+            source: SessionSource::CredentialsFile,
+            email: Some(&credentials.email),
+            tier: Some(credentials.tier),
+            user_id: Some(&credentials.user_id),
+            expires_at: Some(&credentials.expires_at),
+            expires_in: Some(expires_in),
+            expired: Some(false),
+            refreshed: Some(refreshed),
+            message: None,
+            note: None,
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else {
+        println!("Logged in\n");
+        println!("Email:   {}", credentials.email);
+        println!("Tier:    {}", credentials.tier);
+        println!("Expires: in {}", format_duration(expires_in));
+    }
+    ExitCode::SUCCESS
+}
+
+/// "2 days, 3 hours" / "3 hours, 5 minutes" / "5 minutes"
+fn format_duration(seconds: i64) -> String {
+    let plural = |n: i64, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+    if days > 0 {
+        format!("{}, {}", plural(days, "day"), plural(hours, "hour"))
+    } else if hours > 0 {
+        format!("{}, {}", plural(hours, "hour"), plural(minutes, "minute"))
+    } else {
+        plural(minutes, "minute")
+    }
+}
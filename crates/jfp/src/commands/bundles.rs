@@ -0,0 +1,225 @@
+//! Bundles command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (bundles / bundle):
+//! - `bundles`: JSON { bundles: [{ id, title, description, version, prompt_count, featured, author }], count }
+//! - `bundle <id>`: the bundle plus resolved `prompts` [{ id, title, description, category }]
+//!   and any `missing` prompt ids
+//! - Not found -> `not_found` error
+
+use std::process::ExitCode;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::output::{fail, print_output};
+use crate::error::JfpError;
+use crate::registry;
+use crate::storage::Database;
+use crate::types::{Bundle, Prompt};
+
+/// Summary row for bundles list
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct BundleSummary<'a> {
+    id: &'a str,
+    title: &'a str,
+    description: &'a str,
+    version: &'a str,
+    prompt_count: usize,
+    featured: bool,
+    author: &'a str,
+}
+
+/// JSON output for bundles command (also served by `jfp serve --http`)
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct BundlesOutput<'a> {
+    bundles: Vec<BundleSummary<'a>>,
+    count: usize,
+}
+
+impl<'a> BundlesOutput<'a> {
+    pub(crate) fn new(bundles: &'a [Bundle]) -> Self {
+        Self {
+            bundles: bundles
+                .iter()
+                .map(|b| BundleSummary {
+                    id: &b.id,
+                    title: &b.title,
+                    description: &b.description,
+                    version: &b.version,
+                    prompt_count: b.prompt_ids.len(),
+                    featured: b.featured,
+                    author: &b.author,
+                })
+                .collect(),
+            count: bundles.len(),
+        }
+    }
+}
+
+/// Member prompt in bundle details
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct BundlePrompt<'a> {
+    id: &'a str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<&'a str>,
+}
+
+/// JSON output for bundle command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct BundleOutput<'a> {
+    #[serde(flatten)]
+    bundle: &'a Bundle,
+    prompts: Vec<BundlePrompt<'a>>,
+    /// Member ids that are not in the local library
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing: Vec<&'a str>,
+}
+
+pub fn list_bundles(use_json: bool) -> ExitCode {
+    let db = match open_seeded(use_json) {
+        Ok(db) => db,
+        Err(code) => return code,
+    };
+
+    let bundles = match db.list_bundles() {
+        Ok(b) => b,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    if use_json {
+        let output = BundlesOutput::new(&bundles);
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if bundles.is_empty() {
+        println!("No bundles found.");
+    } else {
+        println!("Bundles ({}):\n", bundles.len());
+        for bundle in &bundles {
+            print!("  {} - {} ({} prompts)", bundle.id, bundle.title, bundle.prompt_ids.len());
+            if bundle.featured {
+                print!(" [featured]");
+            }
+            println!();
+            println!("    {}\n", bundle.description);
+        }
+        println!("Use \"jfp bundle <id>\" for details.");
+    }
+
+    ExitCode::SUCCESS
+}
+
+pub fn show_bundle(id: &str, use_json: bool) -> ExitCode {
+    let db = match open_seeded(use_json) {
+        Ok(db) => db,
+        Err(code) => return code,
+    };
+
+    let bundle = match db.get_bundle(id) {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            let available: Vec<String> = db
+                .list_bundles()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|b| b.id)
+                .collect();
+            return fail(
+                JfpError::not_found("not_found", format!("Bundle not found: {}", id)).with("available", available),
+                use_json,
+            );
+        }
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // Resolve members against the local library, keeping bundle order
+    let mut prompts: Vec<Prompt> = Vec::new();
+    let mut missing = Vec::new();
+    for prompt_id in &bundle.prompt_ids {
+        match db.get_prompt(prompt_id) {
+            Ok(Some(p)) => prompts.push(p),
+            Ok(None) => missing.push(prompt_id.as_str()),
+            Err(e) => return fail(e.into(), use_json),
+        }
+    }
+
+    if use_json {
+        let output = BundleOutput {
+            bundle: &bundle,
+            prompts: prompts
+                .iter()
+                .map(|p| BundlePrompt {
+                    id: &p.id,
+                    title: &p.title,
+                    description: p.description.as_deref(),
+                    category: p.category.as_deref(),
+                })
+                .collect(),
+            missing,
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    println!("# {} - {} (v{})", bundle.id, bundle.title, bundle.version);
+    println!();
+    println!("{}", bundle.description);
+    println!();
+    println!("Author: {}", bundle.author);
+    println!("Updated: {}", bundle.updated_at);
+    if bundle.featured {
+        println!("Featured: yes");
+    }
+
+    println!("\nIncluded prompts ({}):\n", prompts.len());
+    for prompt in &prompts {
+        println!("  • {} - {}", prompt.id, prompt.title);
+        if let Some(desc) = &prompt.description {
+            println!("    {}", desc);
+        }
+        if let Some(cat) = &prompt.category {
+            print!("    Category: {}", cat);
+        }
+        if !prompt.tags.is_empty() {
+            let tags: Vec<&str> = prompt.tags.iter().take(3).map(String::as_str).collect();
+            print!("  Tags: {}", tags.join(", "));
+        }
+        println!("\n");
+    }
+    for prompt_id in &missing {
+        println!("  • {} (not in local library)\n", prompt_id);
+    }
+
+    if let Some(workflow) = &bundle.workflow {
+        println!("Workflow:\n");
+        println!("{}\n", workflow);
+    }
+    if !bundle.when_to_use.is_empty() {
+        println!("When to use:\n");
+        for item in &bundle.when_to_use {
+            println!("  → {}", item);
+        }
+        println!();
+    }
+
+    println!("Export as a skill with: jfp export --bundle {} --format skill", bundle.id);
+
+    ExitCode::SUCCESS
+}
+
+/// Open the database, seeded from (or upgraded to) the bundled snapshots
+fn open_seeded(use_json: bool) -> Result<Database, ExitCode> {
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return Err(fail(e.into(), use_json)),
+    };
+
+    let _ = registry::seed(&db);
+
+    Ok(db)
+}
@@ -2,22 +2,26 @@
 //!
 //! From EXISTING_JFP_STRUCTURE.md section 10:
 //! - categories: counts prompts per category, sorted by name
+//! - NDJSON output: one { name, count } per line, streamed from the database
+//! - Plain output: `name<TAB>count` per line
 
 use std::process::ExitCode;
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::registry::bundled_prompts;
+use crate::cli::output::{self, OutputFormat, Table, fail, print_output, print_record};
+use crate::registry;
 use crate::storage::Database;
 
-#[derive(Serialize)]
-struct CategoryOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CategoryOutput {
     name: String,
     count: usize,
 }
 
-#[derive(Serialize)]
-struct CategoriesOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CategoriesOutput {
     categories: Vec<CategoryOutput>,
     total: usize,
 }
@@ -26,36 +30,24 @@ pub fn run(use_json: bool) -> ExitCode {
     // Open database
     let db = match Database::open() {
         Ok(db) => db,
-        Err(e) => {
-            if use_json {
-                eprintln!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error opening database: {}", e);
-            }
-            return ExitCode::FAILURE;
-        }
+        Err(e) => return fail(e.into(), use_json),
     };
 
-    // Seed if empty
-    let count = db.prompt_count().unwrap_or(0);
-    if count == 0 {
-        let prompts = bundled_prompts();
-        for prompt in &prompts {
-            let _ = db.upsert_prompt(prompt);
-        }
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    if output::format() == OutputFormat::Ndjson {
+        let streamed = db.each_category_count(|name, count| print_record(&CategoryOutput { name, count }));
+        return match streamed {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => fail(e, use_json),
+        };
     }
 
     // Get category counts
     let categories = match db.category_counts() {
         Ok(c) => c,
-        Err(e) => {
-            if use_json {
-                eprintln!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error getting categories: {}", e);
-            }
-            return ExitCode::FAILURE;
-        }
+        Err(e) => return fail(e.into(), use_json),
     };
 
     let total = categories.len();
@@ -68,21 +60,23 @@ pub fn run(use_json: bool) -> ExitCode {
                 .collect(),
             total,
         };
-        match serde_json::to_string_pretty(&output) {
-            Ok(json) => println!("{}", json),
-            Err(e) => {
-                eprintln!(r#"{{"error": "serialization_error", "message": "{}"}}"#, e);
-                return ExitCode::FAILURE;
-            }
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if output::format() == OutputFormat::Plain {
+        for (name, count) in &categories {
+            println!("{}\t{}", name, count);
         }
     } else {
         if categories.is_empty() {
             println!("No categories found.");
         } else {
             println!("Categories ({}):\n", total);
+            let mut table = Table::new(&["CATEGORY", "PROMPTS"]).align_right(1);
             for (name, count) in &categories {
-                println!("  {} ({})", name, count);
+                table.row(vec![output::category(name).into_owned(), count.to_string()]);
             }
+            table.print();
         }
     }
 
@@ -0,0 +1,492 @@
+//! Collections command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (collections), kept local-first:
+//! collections live in SQLite and need no account.
+//! - `collections`: JSON { collections: [{ name, description?, prompt_count,
+//!   created_at, updated_at, synced_at? }], count }
+//! - `collections create <name>`: { created, collection }
+//! - `collections add|remove <name> <id>...`: { collection, added |
+//!   removed, already_present | not_in_collection, count }; only prompts in
+//!   the library can be added
+//! - `collections show <name>`: { collection, items: [{ id, title,
+//!   description, category }], missing?, count }
+//! - `collections export <name>`: md or skill files through the export
+//!   writers (JSON: the export manifest), or `--stdout`
+//! - `collections sync [name]`: pushes to premium with POST
+//!   `/cli/collections` { name, description } and POST
+//!   `/cli/collections/<name>/prompts` { prompt_id } per member, where 409
+//!   means it is already there; JSON { synced, failed: [{ name, error }] }
+//! - Errors: `missing_argument`, `already_exists`, `not_found` (with the
+//!   `available` collections or the `missing` prompt ids), `invalid_format`
+
+use std::process::ExitCode;
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::json;
+
+use super::sync::{require_premium, requires_premium, session_expired};
+use crate::auth::ApiClient;
+use crate::cli::output::{fail, print_output};
+use crate::error::{JfpError, exit};
+use crate::export::{self, Format, OnConflict};
+use crate::registry;
+use crate::storage::{self, Database};
+use crate::types::{Collection, Prompt};
+
+/// What needs premium, for error messages
+const SYNC_ACTION: &str = "sync collections";
+
+/// Collection metadata, without its members
+#[derive(Serialize, JsonSchema)]
+struct CollectionSummary<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    prompt_count: usize,
+    created_at: &'a str,
+    updated_at: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    synced_at: Option<&'a str>,
+}
+
+impl<'a> From<&'a Collection> for CollectionSummary<'a> {
+    fn from(c: &'a Collection) -> Self {
+        Self {
+            name: &c.name,
+            description: c.description.as_deref(),
+            prompt_count: c.prompt_ids.len(),
+            created_at: &c.created_at,
+            updated_at: &c.updated_at,
+            synced_at: c.synced_at.as_deref(),
+        }
+    }
+}
+
+/// Member prompt in collection details
+#[derive(Serialize, JsonSchema)]
+struct CollectionItem<'a> {
+    id: &'a str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<&'a str>,
+}
+
+/// A collection that could not be pushed to premium
+#[derive(Serialize, JsonSchema)]
+struct FailedCollection {
+    name: String,
+    error: String,
+}
+
+/// JSON output for collections command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CollectionsOutput<'a> {
+    collections: Vec<CollectionSummary<'a>>,
+    count: usize,
+}
+
+/// JSON output for `collections create`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CollectionCreateOutput<'a> {
+    created: bool,
+    collection: CollectionSummary<'a>,
+}
+
+/// JSON output for `collections add`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CollectionAddOutput<'a> {
+    collection: &'a str,
+    added: Vec<String>,
+    already_present: Vec<&'a str>,
+    /// Members afterwards
+    count: usize,
+}
+
+/// JSON output for `collections remove`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CollectionRemoveOutput<'a> {
+    collection: &'a str,
+    removed: Vec<String>,
+    not_in_collection: Vec<&'a str>,
+    /// Members afterwards
+    count: usize,
+}
+
+/// JSON output for `collections show`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CollectionShowOutput<'a> {
+    collection: CollectionSummary<'a>,
+    items: Vec<CollectionItem<'a>>,
+    /// Member ids that are not in the local library
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing: Vec<&'a str>,
+    count: usize,
+}
+
+/// JSON output for `collections sync`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CollectionSyncOutput<'a> {
+    synced: Vec<&'a str>,
+    failed: &'a [FailedCollection],
+}
+
+pub fn list(use_json: bool) -> ExitCode {
+    with_db(use_json, |db| {
+        let collections = db.list_collections()?;
+
+        if use_json {
+            let output = CollectionsOutput {
+                collections: collections.iter().map(CollectionSummary::from).collect(),
+                count: collections.len(),
+            };
+            print_output(&output)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        if collections.is_empty() {
+            println!("You don't have any collections yet.");
+            println!("\nCreate one with: jfp collections create <name>");
+            return Ok(ExitCode::SUCCESS);
+        }
+        println!("Collections ({}):\n", collections.len());
+        for collection in &collections {
+            let updated = collection.updated_at.get(..10).unwrap_or(&collection.updated_at);
+            println!("  {} ({} prompts, updated {})", collection.name, collection.prompt_ids.len(), updated);
+            if let Some(description) = &collection.description {
+                println!("    {}", description);
+            }
+        }
+        println!("\nUse 'jfp collections show <name>' for details.");
+        Ok(ExitCode::SUCCESS)
+    })
+}
+
+pub fn create(name: &str, description: Option<String>, use_json: bool) -> ExitCode {
+    let name = name.trim();
+    if name.is_empty() {
+        return fail(JfpError::invalid("missing_argument", "Collection name is required"), use_json);
+    }
+    let description = description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+
+    with_db(use_json, |db| {
+        let Some(collection) = db.create_collection(name, description)? else {
+            return Err(JfpError::conflict("already_exists", format!("Collection already exists: {}", name)));
+        };
+
+        if use_json {
+            let output = CollectionCreateOutput { created: true, collection: CollectionSummary::from(&collection) };
+            print_output(&output)?;
+        } else {
+            println!("✓ Created collection \"{}\"", collection.name);
+            println!("  Add prompts with: jfp collections add {} <prompt-id>", collection.name);
+        }
+        Ok(ExitCode::SUCCESS)
+    })
+}
+
+pub fn add(name: &str, prompt_ids: Vec<String>, use_json: bool) -> ExitCode {
+    let prompt_ids = dedup(prompt_ids);
+    with_db(use_json, |db| {
+        let collection = load(db, name)?;
+        let mut missing = Vec::new();
+        for id in &prompt_ids {
+            if db.get_prompt(id)?.is_none() {
+                missing.push(id.as_str());
+            }
+        }
+        if !missing.is_empty() {
+            let message = format!("Prompt not found: {}", missing.join(", "));
+            return Err(JfpError::not_found("not_found", message).with("missing", missing));
+        }
+
+        let added = db.add_to_collection(&collection.name, &prompt_ids)?;
+        let already_present: Vec<&str> =
+            prompt_ids.iter().filter(|id| !added.contains(id)).map(String::as_str).collect();
+        let count = collection.prompt_ids.len() + added.len();
+
+        if use_json {
+            let output = CollectionAddOutput {
+                collection: &collection.name,
+                added,
+                already_present,
+                count,
+            };
+            print_output(&output)?;
+        } else {
+            for id in &added {
+                println!("✓ Added {} to {}", id, collection.name);
+            }
+            for id in &already_present {
+                println!("  {} is already in {}", id, collection.name);
+            }
+            println!("\n{} now has {} prompts", collection.name, count);
+        }
+        Ok(ExitCode::SUCCESS)
+    })
+}
+
+pub fn remove(name: &str, prompt_ids: Vec<String>, use_json: bool) -> ExitCode {
+    let prompt_ids = dedup(prompt_ids);
+    with_db(use_json, |db| {
+        let collection = load(db, name)?;
+        let removed = db.remove_from_collection(&collection.name, &prompt_ids)?;
+        let not_in_collection: Vec<&str> =
+            prompt_ids.iter().filter(|id| !removed.contains(id)).map(String::as_str).collect();
+        let count = collection.prompt_ids.len() - removed.len();
+
+        if use_json {
+            let output = CollectionRemoveOutput {
+                collection: &collection.name,
+                removed,
+                not_in_collection,
+                count,
+            };
+            print_output(&output)?;
+        } else {
+            for id in &removed {
+                println!("✓ Removed {} from {}", id, collection.name);
+            }
+            for id in &not_in_collection {
+                println!("  {} is not in {}", id, collection.name);
+            }
+            println!("\n{} now has {} prompts", collection.name, count);
+        }
+        Ok(ExitCode::SUCCESS)
+    })
+}
+
+pub fn show(name: &str, use_json: bool) -> ExitCode {
+    with_db(use_json, |db| {
+        let collection = load(db, name)?;
+        let (prompts, missing) = resolve(db, &collection)?;
+
+        if use_json {
+            let items: Vec<CollectionItem> = prompts
+                .iter()
+                .map(|p| CollectionItem {
+                    id: &p.id,
+                    title: &p.title,
+                    description: p.description.as_deref(),
+                    category: p.category.as_deref(),
+                })
+                .collect();
+            let output = CollectionShowOutput {
+                collection: CollectionSummary::from(&collection),
+                count: items.len(),
+                items,
+                missing,
+            };
+            print_output(&output)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        println!("# {}", collection.name);
+        if let Some(description) = &collection.description {
+            println!("\n{}", description);
+        }
+        println!();
+        println!("Prompts: {}", collection.prompt_ids.len());
+        println!("Updated: {}", collection.updated_at);
+        match &collection.synced_at {
+            Some(synced_at) => println!("Synced: {}", synced_at),
+            None => println!("Synced: never"),
+        }
+
+        if collection.prompt_ids.is_empty() {
+            println!("\nThis collection is empty.");
+        } else {
+            println!("\nPrompts in collection:\n");
+            for prompt in &prompts {
+                println!("  • {} - {}", prompt.id, prompt.title);
+                if let Some(desc) = &prompt.description {
+                    println!("    {}", desc);
+                }
+                if let Some(cat) = &prompt.category {
+                    println!("    Category: {}", cat);
+                }
+                println!();
+            }
+            for prompt_id in &missing {
+                println!("  • {} (not in local library)\n", prompt_id);
+            }
+        }
+        println!("Add a prompt: jfp collections add {} <prompt-id>", collection.name);
+        println!("Export prompts: jfp collections export {}", collection.name);
+        Ok(ExitCode::SUCCESS)
+    })
+}
+
+pub fn export(
+    name: &str,
+    format: &str,
+    output_dir: Option<String>,
+    stdout: bool,
+    on_conflict: &str,
+    use_json: bool,
+) -> ExitCode {
+    let format = match Format::parse(format) {
+        Some(format @ (Format::Markdown | Format::Skill)) => format,
+        _ => {
+            let message = format!("Unknown collection export format: {} (expected md or skill)", format);
+            return fail(JfpError::invalid("invalid_format", message), use_json);
+        }
+    };
+    let Some(on_conflict) = OnConflict::parse(on_conflict) else {
+        let message = format!(
+            "Unknown --on-conflict value: {} (expected one of: {})",
+            on_conflict,
+            OnConflict::NAMES.join(", ")
+        );
+        return fail(JfpError::invalid("invalid_argument", message), use_json);
+    };
+
+    with_db(use_json, |db| {
+        let collection = load(db, name)?;
+        let (prompts, missing) = resolve(db, &collection)?;
+        if !missing.is_empty() {
+            let message =
+                format!("Collection {} includes prompts not in the library: {}", collection.name, missing.join(", "));
+            return Err(JfpError::not_found("not_found", message).with("missing", missing));
+        }
+
+        if stdout {
+            print!("{}", export::stdout_text(&prompts, &[], format));
+            return Ok(ExitCode::SUCCESS);
+        }
+        let dir = super::export::create_output_dir(output_dir)?;
+        Ok(super::export::write(&dir, export::files(&prompts, &[], format), format, on_conflict, use_json))
+    })
+}
+
+pub fn sync(name: Option<&str>, use_json: bool) -> ExitCode {
+    with_db(use_json, |db| {
+        let collections = match name {
+            Some(name) => vec![load(db, name)?],
+            None => db.list_collections()?,
+        };
+        require_premium(SYNC_ACTION)?;
+        let client = ApiClient::authenticated()?;
+
+        let mut synced = Vec::new();
+        let mut failed = Vec::new();
+        for collection in &collections {
+            match push(&client, collection) {
+                Ok(()) => {
+                    db.mark_collection_synced(&collection.name, &Utc::now().to_rfc3339())?;
+                    synced.push(collection.name.as_str());
+                }
+                Err(Push::Rejected(401)) => return Err(session_expired()),
+                Err(Push::Rejected(403)) => return Err(requires_premium(SYNC_ACTION, None)),
+                Err(Push::Rejected(status)) => failed.push(FailedCollection {
+                    name: collection.name.clone(),
+                    error: format!("server answered {}", status),
+                }),
+                Err(Push::Offline(message)) => failed.push(FailedCollection {
+                    name: collection.name.clone(),
+                    error: message,
+                }),
+            }
+        }
+
+        if use_json {
+            let output = CollectionSyncOutput { synced, failed: &failed };
+            print_output(&output)?;
+        } else {
+            if collections.is_empty() {
+                println!("No collections to sync.");
+            }
+            for name in &synced {
+                println!("✓ Synced {}", name);
+            }
+            for failure in &failed {
+                eprintln!("Failed to sync {}: {}", failure.name, failure.error);
+            }
+        }
+        Ok(if failed.is_empty() { ExitCode::SUCCESS } else { ExitCode::from(exit::NETWORK) })
+    })
+}
+
+/// Why a collection did not reach the server
+enum Push {
+    /// The server answered with this status
+    Rejected(u16),
+    /// The request failed
+    Offline(String),
+}
+
+/// Create the collection on the server and add its members; anything the
+/// server already has (409) is left as it is
+fn push(client: &ApiClient, collection: &Collection) -> Result<(), Push> {
+    let post = |path: &str, body: serde_json::Value| match client.post(path, &body) {
+        Ok(response) if response.ok() || response.status == 409 => Ok(()),
+        Ok(response) => Err(Push::Rejected(response.status)),
+        Err(e) => Err(Push::Offline(e.to_string())),
+    };
+    post(
+        "/cli/collections",
+        json!({ "name": collection.name, "description": collection.description }),
+    )?;
+    let members = format!("/cli/collections/{}/prompts", path_segment(&collection.name));
+    for prompt_id in &collection.prompt_ids {
+        post(&members, json!({ "prompt_id": prompt_id }))?;
+    }
+    Ok(())
+}
+
+/// Look up a collection; `not_found` lists the available names
+pub(super) fn load(db: &Database, name: &str) -> Result<Collection, JfpError> {
+    if let Some(collection) = db.get_collection(name)? {
+        return Ok(collection);
+    }
+    let available: Vec<String> = db.list_collections()?.into_iter().map(|c| c.name).collect();
+    Err(JfpError::not_found("not_found", format!("Collection not found: {}", name)).with("available", available))
+}
+
+/// Members in the local library, in collection order, and the ids that are not
+fn resolve<'a>(db: &Database, collection: &'a Collection) -> storage::Result<(Vec<Prompt>, Vec<&'a str>)> {
+    let mut prompts = Vec::new();
+    let mut missing = Vec::new();
+    for prompt_id in &collection.prompt_ids {
+        match db.get_prompt(prompt_id)? {
+            Some(prompt) => prompts.push(prompt),
+            None => missing.push(prompt_id.as_str()),
+        }
+    }
+    Ok((prompts, missing))
+}
+
+/// Open the database and run `command`, reporting its error
+fn with_db(use_json: bool, command: impl FnOnce(&Database) -> Result<ExitCode, JfpError>) -> ExitCode {
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let _ = registry::seed(&db);
+
+    command(&db).unwrap_or_else(|e| fail(e, use_json))
+}
+
+/// Prompt ids in the order given, without repeats
+fn dedup(ids: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    unique
+}
+
+/// Percent-encode a collection name as one URL path segment
+fn path_segment(name: &str) -> String {
+    name.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
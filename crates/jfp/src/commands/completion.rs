@@ -0,0 +1,23 @@
+//! Completion command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10:
+//! - Generates shell completions for bash, zsh, fish
+
+use std::process::ExitCode;
+
+use clap::Command;
+use clap_complete::Shell;
+
+use crate::cli::output::fail;
+use crate::error::JfpError;
+
+pub fn run(shell: &str, mut cmd: Command, use_json: bool) -> ExitCode {
+    let Ok(shell) = shell.parse::<Shell>() else {
+        let message = format!("Unsupported shell '{}' (expected bash, zsh, fish, powershell, elvish)", shell);
+        return fail(JfpError::invalid("invalid_shell", message), use_json);
+    };
+
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, name, &mut std::io::stdout());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,7 @@
+//! Config command implementation (not yet ported)
+
+use std::process::ExitCode;
+
+pub fn run(_action: &str, _key: Option<String>, _value: Option<String>, use_json: bool) -> ExitCode {
+    super::not_implemented("config", use_json)
+}
@@ -0,0 +1,105 @@
+//! Copy command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (copy):
+//! - Options: --fill, --print, --json; variables as for `render`
+//! - JSON output on success: { success, id, title, characters, backend, message }
+//! - No clipboard: `clipboard_failed` with a `fallback`, exit 8, unless
+//!   `--print` asks for the prompt on stdout instead
+
+use std::process::ExitCode;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::render::{form_error, resolve_variables};
+use crate::cli::form::{self, FormError};
+use crate::cli::output::{fail, print_output};
+use crate::clipboard;
+use crate::error::JfpError;
+use crate::registry;
+use crate::storage::Database;
+use crate::template;
+
+/// Backend name reported when `--print` wrote the prompt to stdout
+const PRINT_BACKEND: &str = "stdout";
+
+/// JSON output for copy command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CopyOutput {
+    success: bool,
+    id: String,
+    title: String,
+    characters: usize,
+    backend: &'static str,
+    message: String,
+    /// The prompt text, when it was printed rather than copied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+pub fn run(id: &str, vars: Vec<String>, fill: bool, print: bool, use_json: bool) -> ExitCode {
+    // Refuse up front so agents never wait on a form they cannot see
+    if fill && !form::available() {
+        return form_error(&FormError::NotATty, use_json);
+    }
+
+    // Open database
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    let prompt = match db.get_prompt(id) {
+        Ok(Some(p)) => p,
+        Ok(None) => return fail(JfpError::not_found("not_found", format!("Prompt not found: {}", id)), use_json),
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    let variables = match resolve_variables(&prompt, &vars, fill, use_json) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+    let rendered = template::render(&prompt.content, &variables);
+    let characters = rendered.chars().count();
+
+    let backend = clipboard::copy(&rendered);
+    if backend.is_none() && !print {
+        let error = JfpError::internal(
+            "clipboard_failed",
+            "No clipboard tool available (tried wl-copy, xclip, xsel, pbcopy, clip.exe, OSC 52)",
+        )
+        .with("success", false)
+        .with("hint", "Use --print to write the prompt to stdout instead.")
+        .with("fallback", rendered);
+        return fail(error, use_json);
+    }
+
+    if use_json {
+        let output = CopyOutput {
+            success: true,
+            id: prompt.id,
+            backend: backend.map_or(PRINT_BACKEND, |b| b.name()),
+            message: match backend {
+                Some(_) => format!("Copied \"{}\" to clipboard", prompt.title),
+                None => format!("No clipboard available; printed \"{}\"", prompt.title),
+            },
+            content: backend.is_none().then_some(rendered),
+            title: prompt.title,
+            characters,
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if let Some(backend) = backend {
+        println!("Copied \"{}\" to clipboard (via {})", prompt.title, backend.name());
+        println!("  {} characters", characters);
+    } else {
+        eprintln!("No clipboard tool available. Printing to stdout:");
+        println!("{}", rendered);
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,7 @@
+//! Doctor command implementation (not yet ported)
+
+use std::process::ExitCode;
+
+pub fn run(use_json: bool) -> ExitCode {
+    super::not_implemented("doctor", use_json)
+}
@@ -0,0 +1,227 @@
+//! Export command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (export):
+//! - Options: --format, --output-dir (default cwd), --stdout, --json; `all`
+//!   exports the whole library, `--bundle <id>` a bundle's members (as one
+//!   combined SKILL.md with `--format skill`)
+//! - No ids -> `missing_ids` (exit 2); any unknown id -> `not_found`, nothing written
+//! - `--stdout` prints content directly (no JSON summary)
+//! - JSON output (files): the export manifest
+//!   { success, format, output_dir, exported: [{file, ids, bytes, sha256}], skipped?, failed? }
+//! - NDJSON output: one line per file as it is written, its manifest entry
+//!   tagged with `status` (exported, skipped, failed)
+//! - Plain output: the path of each exported file
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+
+use crate::cli::output::{self, OutputFormat, fail, print_output, print_record};
+use crate::error::{JfpError, exit};
+use crate::export::{self, ExportFile, Format, Manifest, OnConflict};
+use crate::registry;
+use crate::storage::{Database, StorageError};
+use crate::types::Prompt;
+
+pub fn run(
+    ids: Vec<String>,
+    format: &str,
+    output_dir: Option<String>,
+    stdout: bool,
+    on_conflict: &str,
+    bundle: Option<String>,
+    use_json: bool,
+) -> ExitCode {
+    let Some(format) = Format::parse(format) else {
+        let message = format!("Unknown export format: {} (expected one of: {})", format, Format::NAMES.join(", "));
+        let mut error = JfpError::invalid("invalid_format", message);
+        if OutputFormat::from_str(format, true).is_ok() {
+            error = error.with("hint", format!("--format picks the file format here; use --output-format {}", format));
+        }
+        return fail(error, use_json);
+    };
+    let Some(on_conflict) = OnConflict::parse(on_conflict) else {
+        let message = format!(
+            "Unknown --on-conflict value: {} (expected one of: {})",
+            on_conflict,
+            OnConflict::NAMES.join(", ")
+        );
+        return fail(JfpError::invalid("invalid_argument", message), use_json);
+    };
+    if ids.is_empty() && bundle.is_none() {
+        let message = "No prompts specified. Use <id>..., 'all' or --bundle <id>";
+        return fail(JfpError::invalid("missing_ids", message), use_json);
+    }
+
+    // Open database
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    let bundle = match bundle {
+        None => None,
+        Some(id) => match db.get_bundle(&id) {
+            Ok(Some(bundle)) => Some(bundle),
+            Ok(None) => return fail(JfpError::not_found("not_found", format!("Bundle not found: {}", id)), use_json),
+            Err(e) => return fail(e.into(), use_json),
+        },
+    };
+    let ids = match &bundle {
+        Some(bundle) => bundle.prompt_ids.clone(),
+        None => ids,
+    };
+
+    let selected = select_prompts(&db, &ids).and_then(|prompts| {
+        // Only the registry payload carries bundles
+        let bundles = if format == Format::Registry {
+            db.list_bundles().map_err(Selection::Database)?
+        } else {
+            Vec::new()
+        };
+        Ok((prompts, bundles))
+    });
+    let (prompts, bundles) = match selected {
+        Ok(selected) => selected,
+        Err(Selection::Missing(missing)) => {
+            let message = match &bundle {
+                Some(bundle) => format!("Bundle {} includes prompts not in the library: {}", bundle.id, missing.join(", ")),
+                None => format!("Prompt not found: {}", missing.join(", ")),
+            };
+            return fail(JfpError::not_found("not_found", message).with("missing", missing), use_json);
+        }
+        Err(Selection::Database(e)) => return fail(e.into(), use_json),
+    };
+
+    if stdout {
+        match &bundle {
+            Some(bundle) if format == Format::Skill => print!("{}", export::bundle_skill_md(bundle, &prompts)),
+            _ => print!("{}", export::stdout_text(&prompts, &bundles, format)),
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let dir = match create_output_dir(output_dir) {
+        Ok(dir) => dir,
+        Err(e) => return fail(e, use_json),
+    };
+
+    let files = match &bundle {
+        Some(bundle) => export::bundle_files(bundle, &prompts, format),
+        None => export::files(&prompts, &bundles, format),
+    };
+    write(&dir, files, format, on_conflict, use_json)
+}
+
+/// `--output-dir` (default: the working directory), created if missing
+pub(super) fn create_output_dir(output_dir: Option<String>) -> Result<PathBuf, JfpError> {
+    let dir = match output_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        let message = format!("Failed to create output directory {}: {}", dir.display(), e);
+        return Err(JfpError::filesystem("fs_error", message));
+    }
+    Ok(dir)
+}
+
+/// Write `files` and report them: a line per file as it is written in
+/// NDJSON mode, the manifest once done otherwise
+pub(super) fn write(
+    dir: &Path,
+    files: Vec<ExportFile>,
+    format: Format,
+    on_conflict: OnConflict,
+    use_json: bool,
+) -> ExitCode {
+    let stream = output::format() == OutputFormat::Ndjson;
+    let mut streamed = Ok(());
+    let manifest = export::write_files(dir, files, format, on_conflict, |written| {
+        if stream && streamed.is_ok() {
+            streamed = print_record(&written);
+        }
+    });
+    if let Err(e) = streamed {
+        return fail(e, use_json);
+    }
+    if stream {
+        return status(&manifest);
+    }
+    report(&manifest, use_json)
+}
+
+/// Print an export manifest
+fn report(manifest: &Manifest, use_json: bool) -> ExitCode {
+    if use_json {
+        if let Err(e) = print_output(manifest) {
+            return fail(e, use_json);
+        }
+    } else if output::format() == OutputFormat::Plain {
+        for entry in &manifest.exported {
+            println!("{}", entry.file);
+        }
+        for failed in &manifest.failed {
+            eprintln!("Failed to write {}: {}", failed.file, failed.error);
+        }
+    } else {
+        if !manifest.exported.is_empty() {
+            println!("Exported {} file(s):", manifest.exported.len());
+            for entry in &manifest.exported {
+                match &entry.renamed_from {
+                    Some(original) => println!("  ✓ {} ({} exists)", entry.file, original),
+                    None => println!("  ✓ {}", entry.file),
+                }
+            }
+        }
+        if !manifest.skipped.is_empty() {
+            println!("Skipped {} existing file(s):", manifest.skipped.len());
+            for skipped in &manifest.skipped {
+                println!("  - {}", skipped.file);
+            }
+        }
+        for failed in &manifest.failed {
+            eprintln!("Failed to write {}: {}", failed.file, failed.error);
+        }
+    }
+
+    status(manifest)
+}
+
+/// Exit with the filesystem code when any file could not be written
+fn status(manifest: &Manifest) -> ExitCode {
+    if manifest.success { ExitCode::SUCCESS } else { ExitCode::from(exit::FILESYSTEM) }
+}
+
+enum Selection {
+    Missing(Vec<String>),
+    Database(StorageError),
+}
+
+/// Prompts named by `ids` in the order given, or the whole library for `all`
+fn select_prompts(db: &Database, ids: &[String]) -> Result<Vec<Prompt>, Selection> {
+    if ids.iter().any(|id| id == "all") {
+        return db
+            .list_prompts_filtered(None, None, false, None)
+            .map_err(Selection::Database);
+    }
+
+    let mut prompts: Vec<Prompt> = Vec::new();
+    let mut missing = Vec::new();
+    for id in ids {
+        if prompts.iter().any(|p| &p.id == id) {
+            continue;
+        }
+        match db.get_prompt(id) {
+            Ok(Some(prompt)) => prompts.push(prompt),
+            Ok(None) => missing.push(id.clone()),
+            Err(e) => return Err(Selection::Database(e)),
+        }
+    }
+
+    if missing.is_empty() { Ok(prompts) } else { Err(Selection::Missing(missing)) }
+}
@@ -0,0 +1,542 @@
+//! Interactive command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (interactive): the TS CLI uses
+//! an inquirer search prompt followed by an action menu. The Rust picker is
+//! a single full-screen view over the local SQLite library (no network):
+//!
+//! - Typing fuzzy-matches id, title, category, tags and description
+//! - `@category` and `#tag` words in the query filter inline (Tab completes)
+//! - The preview pane shows the highlighted prompt's metadata and content
+//! - Enter copies; Ctrl+R renders, Ctrl+E exports, Ctrl+O opens in browser
+//!
+//! Actions run after the screen is restored, through the regular command
+//! implementations, so they behave exactly as on the command line.
+
+use std::io::{self, IsTerminal, Stdout};
+use std::process::ExitCode;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::{Frame, Terminal};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+
+use super::render::ContextArgs;
+use crate::cli::output::fail;
+use crate::error::JfpError;
+use crate::registry;
+use crate::search::fuzzy_score;
+use crate::storage::Database;
+use crate::template::context::DEFAULT_MAX_CONTEXT;
+use crate::types::Prompt;
+
+/// Lines moved per PageUp/PageDown in the preview
+const PREVIEW_PAGE: u16 = 10;
+
+/// Filter completions listed in the footer
+const MAX_SUGGESTIONS: usize = 6;
+
+/// What to do with the chosen prompt once the picker closes
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Copy,
+    Render,
+    Export,
+    Open,
+}
+
+pub fn run(use_json: bool) -> ExitCode {
+    if !(io::stdin().is_terminal() && io::stdout().is_terminal()) {
+        let message = "jfp i needs an interactive terminal; use jfp search or jfp list instead";
+        return fail(JfpError::invalid("not_a_tty", message), use_json);
+    }
+
+    // Open database
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    let prompts = match db.list_prompts_filtered(None, None, false, None) {
+        Ok(p) => p,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    drop(db);
+
+    let choice = match Picker::new(prompts).run() {
+        Ok(choice) => choice,
+        Err(e) => return fail(JfpError::internal("terminal_error", e.to_string()), use_json),
+    };
+
+    match choice {
+        None => ExitCode::SUCCESS,
+        Some((Action::Copy, id)) => super::copy::run(&id, Vec::new(), false, false, use_json),
+        Some((Action::Render, id)) => {
+            let context = ContextArgs {
+                paths: Vec::new(),
+                stdin: false,
+                max_bytes: DEFAULT_MAX_CONTEXT,
+                max_tokens: None,
+            };
+            super::render::run(&id, Vec::new(), true, context, use_json)
+        }
+        Some((Action::Export, id)) => super::export::run(vec![id], "md", None, false, "overwrite", None, use_json),
+        Some((Action::Open, id)) => super::open::run(&id, use_json),
+    }
+}
+
+/// Alternate screen and raw mode, restored on drop
+struct Screen {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        if let Err(e) = execute!(stdout, EnterAlternateScreen) {
+            let _ = terminal::disable_raw_mode();
+            return Err(e);
+        }
+        Ok(Self {
+            terminal: Terminal::new(CrosstermBackend::new(stdout))?,
+        })
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+/// Query parsed into inline filters and fuzzy terms
+struct Query<'a> {
+    categories: Vec<&'a str>,
+    tags: Vec<&'a str>,
+    pattern: String,
+}
+
+impl<'a> Query<'a> {
+    fn parse(text: &'a str) -> Self {
+        let mut query = Self {
+            categories: Vec::new(),
+            tags: Vec::new(),
+            pattern: String::new(),
+        };
+        let mut terms = Vec::new();
+        for word in text.split_whitespace() {
+            match (word.strip_prefix('@'), word.strip_prefix('#')) {
+                (Some(category), _) if !category.is_empty() => query.categories.push(category),
+                (_, Some(tag)) if !tag.is_empty() => query.tags.push(tag),
+                _ => terms.push(word),
+            }
+        }
+        query.pattern = terms.join(" ");
+        query
+    }
+
+    /// Filters match by case-insensitive prefix so they work while typing
+    fn accepts(&self, prompt: &Prompt) -> bool {
+        let category = prompt.category.as_deref().unwrap_or("");
+        self.categories.iter().all(|c| starts_with_ignore_case(category, c))
+            && self
+                .tags
+                .iter()
+                .all(|t| prompt.tags.iter().any(|tag| starts_with_ignore_case(tag, t)))
+    }
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.to_lowercase().starts_with(&prefix.to_lowercase())
+}
+
+/// Picker state
+struct Picker {
+    prompts: Vec<Prompt>,
+    /// Text fuzzy-matched for each prompt
+    haystacks: Vec<String>,
+    categories: Vec<String>,
+    tags: Vec<String>,
+    query: String,
+    /// Indices into `prompts`, best match first
+    matches: Vec<usize>,
+    list: ListState,
+    preview_scroll: u16,
+}
+
+impl Picker {
+    fn new(prompts: Vec<Prompt>) -> Self {
+        let haystacks = prompts
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} {} {} {} {}",
+                    p.title,
+                    p.id,
+                    p.category.as_deref().unwrap_or(""),
+                    p.tags.join(" "),
+                    p.description.as_deref().unwrap_or("")
+                )
+            })
+            .collect();
+
+        let mut categories: Vec<String> = prompts.iter().filter_map(|p| p.category.clone()).collect();
+        categories.sort();
+        categories.dedup();
+        let mut tags: Vec<String> = prompts.iter().flat_map(|p| p.tags.clone()).collect();
+        tags.sort();
+        tags.dedup();
+
+        let mut picker = Self {
+            prompts,
+            haystacks,
+            categories,
+            tags,
+            query: String::new(),
+            matches: Vec::new(),
+            list: ListState::default(),
+            preview_scroll: 0,
+        };
+        picker.refresh();
+        picker
+    }
+
+    fn run(mut self) -> io::Result<Option<(Action, String)>> {
+        let mut screen = Screen::enter()?;
+        loop {
+            screen.terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(outcome) = self.handle(key) {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    /// Apply a key; `Some` ends the picker (`Some(None)` = quit)
+    fn handle(&mut self, key: KeyEvent) -> Option<Option<(Action, String)>> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let action = match key.code {
+            KeyCode::Esc => return Some(None),
+            KeyCode::Char('c') if ctrl => return Some(None),
+            KeyCode::Enter => Some(Action::Copy),
+            KeyCode::Char('y') if ctrl => Some(Action::Copy),
+            KeyCode::Char('r') if ctrl => Some(Action::Render),
+            KeyCode::Char('e') if ctrl => Some(Action::Export),
+            KeyCode::Char('o') if ctrl => Some(Action::Open),
+            _ => None,
+        };
+        if let Some(action) = action {
+            return self.selected().map(|p| Some((action, p.id.clone())));
+        }
+
+        match key.code {
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Char('p' | 'k') if ctrl => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('n' | 'j') if ctrl => self.move_selection(1),
+            KeyCode::PageUp => self.preview_scroll = self.preview_scroll.saturating_sub(PREVIEW_PAGE),
+            KeyCode::PageDown => self.preview_scroll = self.preview_scroll.saturating_add(PREVIEW_PAGE),
+            KeyCode::Tab => self.complete_filter(),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refresh();
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.refresh();
+            }
+            KeyCode::Char('w') if ctrl => {
+                let trimmed = self.query.trim_end();
+                let cut = trimmed.rfind(' ').map_or(0, |i| i + 1);
+                self.query.truncate(cut);
+                self.refresh();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.refresh();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Recompute matches for the current query
+    fn refresh(&mut self) {
+        let query = Query::parse(&self.query);
+        let mut scored: Vec<(usize, i64)> = self
+            .prompts
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| query.accepts(p))
+            .filter_map(|(i, _)| fuzzy_score(&query.pattern, &self.haystacks[i]).map(|s| (i, s)))
+            .collect();
+        // Stable sort keeps the library order (featured, then title) on ties
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+
+        self.matches = scored.into_iter().map(|(i, _)| i).collect();
+        self.list.select((!self.matches.is_empty()).then_some(0));
+        self.preview_scroll = 0;
+    }
+
+    fn selected(&self) -> Option<&Prompt> {
+        self.list
+            .selected()
+            .and_then(|i| self.matches.get(i))
+            .map(|&i| &self.prompts[i])
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let len = self.matches.len() as isize;
+        let current = self.list.selected().unwrap_or(0) as isize;
+        self.list.select(Some((current + delta).rem_euclid(len) as usize));
+        self.preview_scroll = 0;
+    }
+
+    /// Filter values matching the `@`/`#` word being typed, if any
+    fn filter_suggestions(&self) -> Vec<String> {
+        if self.query.ends_with(' ') {
+            return Vec::new();
+        }
+        let Some(word) = self.query.split_whitespace().last() else {
+            return Vec::new();
+        };
+        let (sigil, prefix, values) = match (word.strip_prefix('@'), word.strip_prefix('#')) {
+            (Some(prefix), _) => ('@', prefix, &self.categories),
+            (_, Some(prefix)) => ('#', prefix, &self.tags),
+            _ => return Vec::new(),
+        };
+        values
+            .iter()
+            .filter(|v| starts_with_ignore_case(v, prefix))
+            .map(|v| format!("{}{}", sigil, v))
+            .collect()
+    }
+
+    /// Replace the `@`/`#` word being typed with its first completion
+    fn complete_filter(&mut self) {
+        if let Some(first) = self.filter_suggestions().into_iter().next() {
+            let start = self.query.rfind(' ').map_or(0, |i| i + 1);
+            self.query.truncate(start);
+            self.query.push_str(&first);
+            self.query.push(' ');
+            self.refresh();
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [input_area, main_area, footer_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(main_area);
+
+        let input = Paragraph::new(format!("> {}", self.query)).block(Block::bordered().title(" jfp "));
+        frame.render_widget(input, input_area);
+        frame.set_cursor_position((
+            input_area.x + 3 + self.query.chars().count() as u16,
+            input_area.y + 1,
+        ));
+
+        self.draw_list(frame, list_area);
+        self.draw_preview(frame, preview_area);
+
+        let suggestions = self.filter_suggestions();
+        let footer = if suggestions.is_empty() {
+            "enter copy | ^R render | ^E export | ^O open | @category #tag filter | PgUp/PgDn scroll | esc quit"
+                .to_string()
+        } else {
+            let shown: Vec<&str> = suggestions.iter().take(MAX_SUGGESTIONS).map(String::as_str).collect();
+            format!("tab: {}", shown.join("  "))
+        };
+        frame.render_widget(
+            Paragraph::new(footer).style(Style::default().add_modifier(Modifier::DIM)),
+            footer_area,
+        );
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|&i| {
+                let prompt = &self.prompts[i];
+                let marker = if prompt.featured { "* " } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::raw(marker),
+                    Span::raw(prompt.title.clone()),
+                    Span::styled(
+                        format!("  {}", prompt.category.as_deref().unwrap_or("")),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let title = format!(" Prompts {}/{} ", self.matches.len(), self.prompts.len());
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">");
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_preview(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(" Preview ");
+        let Some(prompt) = self.selected() else {
+            frame.render_widget(Paragraph::new("No matching prompts").block(block), area);
+            return;
+        };
+
+        let label = Style::default().add_modifier(Modifier::BOLD);
+        let dim = Style::default().add_modifier(Modifier::DIM);
+        let mut lines = vec![
+            Line::styled(prompt.title.clone(), label),
+            Line::styled(prompt.id.clone(), dim),
+            Line::default(),
+            Line::from(vec![
+                Span::styled("Category: ", label),
+                Span::raw(prompt.category.clone().unwrap_or_default()),
+            ]),
+            Line::from(vec![Span::styled("Tags: ", label), Span::raw(prompt.tags.join(", "))]),
+        ];
+        if let Some(author) = &prompt.author {
+            lines.push(Line::from(vec![Span::styled("Author: ", label), Span::raw(author.clone())]));
+        }
+        if !prompt.variables.is_empty() {
+            let names: Vec<&str> = prompt.variables.iter().map(|v| v.name.as_str()).collect();
+            lines.push(Line::from(vec![
+                Span::styled("Variables: ", label),
+                Span::raw(names.join(", ")),
+            ]));
+        }
+        if let Some(description) = &prompt.description {
+            lines.push(Line::default());
+            lines.push(Line::raw(description.clone()));
+        }
+        lines.push(Line::styled("-".repeat(area.width.saturating_sub(2) as usize), dim));
+        lines.extend(prompt.content.lines().map(|l| Line::raw(l.to_string())));
+
+        let preview = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.preview_scroll, 0));
+        frame.render_widget(preview, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(id: &str, category: &str, tags: &[&str]) -> Prompt {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": id,
+            "category": category,
+            "tags": tags,
+            "content": "Body",
+        }))
+        .unwrap()
+    }
+
+    fn picker() -> Picker {
+        Picker::new(vec![
+            prompt("idea-wizard", "ideation", &["brainstorming", "ideas"]),
+            prompt("readme-reviser", "documentation", &["readme", "docs"]),
+            prompt("doc-sweeper", "documentation", &["docs", "cleanup"]),
+        ])
+    }
+
+    fn ids(picker: &Picker) -> Vec<&str> {
+        picker.matches.iter().map(|&i| picker.prompts[i].id.as_str()).collect()
+    }
+
+    fn press(picker: &mut Picker, code: KeyCode) {
+        picker.handle(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(picker: &mut Picker, text: &str) {
+        text.chars().for_each(|c| press(picker, KeyCode::Char(c)));
+    }
+
+    #[test]
+    fn query_splits_filters_from_fuzzy_terms() {
+        let query = Query::parse("@doc read #docs me @ #");
+        assert_eq!(query.categories, ["doc"]);
+        assert_eq!(query.tags, ["docs"]);
+        // A lone sigil is an ordinary term
+        assert_eq!(query.pattern, "read me @ #");
+    }
+
+    #[test]
+    fn filters_match_by_prefix_ignoring_case() {
+        let sweeper = prompt("doc-sweeper", "documentation", &["docs", "cleanup"]);
+        assert!(Query::parse("@DOC").accepts(&sweeper));
+        assert!(Query::parse("#clean #docs").accepts(&sweeper));
+        assert!(!Query::parse("#clean #ideas").accepts(&sweeper));
+        assert!(!Query::parse("@ideation").accepts(&sweeper));
+        assert!(!Query::parse("@doc").accepts(&prompt("untitled", "", &[])));
+    }
+
+    #[test]
+    fn filters_and_terms_narrow_the_matches() {
+        let mut picker = picker();
+        assert_eq!(ids(&picker).len(), 3);
+
+        type_text(&mut picker, "@documentation");
+        assert_eq!(ids(&picker), ["readme-reviser", "doc-sweeper"]);
+        type_text(&mut picker, " sweep");
+        assert_eq!(ids(&picker), ["doc-sweeper"]);
+
+        press(&mut picker, KeyCode::Char('x'));
+        assert!(ids(&picker).is_empty());
+        assert!(picker.selected().is_none());
+    }
+
+    #[test]
+    fn tab_completes_the_filter_being_typed() {
+        let mut picker = picker();
+        type_text(&mut picker, "wiz #b");
+        assert_eq!(picker.filter_suggestions(), ["#brainstorming"]);
+        press(&mut picker, KeyCode::Tab);
+        assert_eq!(picker.query, "wiz #brainstorming ");
+        assert_eq!(ids(&picker), ["idea-wizard"]);
+
+        // Nothing to complete after a space or outside a filter word
+        assert!(picker.filter_suggestions().is_empty());
+        type_text(&mut picker, "wiz");
+        assert!(picker.filter_suggestions().is_empty());
+        press(&mut picker, KeyCode::Tab);
+        assert_eq!(picker.query, "wiz #brainstorming wiz");
+
+        let mut picker = self::picker();
+        type_text(&mut picker, "@DOC");
+        assert_eq!(picker.filter_suggestions(), ["@documentation"]);
+        type_text(&mut picker, " #d");
+        assert_eq!(picker.filter_suggestions(), ["#docs"]);
+        press(&mut picker, KeyCode::Tab);
+        assert_eq!(picker.query, "@DOC #docs ");
+        assert_eq!(ids(&picker), ["readme-reviser", "doc-sweeper"]);
+    }
+}
@@ -2,53 +2,77 @@
 //!
 //! From EXISTING_JFP_STRUCTURE.md section 10 (list):
 //! - Options: --category, --tag, --mine, --saved, --json
+//! - `--collection <name>` lists a collection's members in collection order;
+//!   an unknown collection -> `not_found`
 //! - JSON output: { prompts, count, offline?, offlineAge? }
+//! - NDJSON output: one prompt summary per line, streamed from the database
+//! - Plain output: `id<TAB>title` per line
 
 use std::process::ExitCode;
 
+use crossterm::style::Color;
+use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::registry::bundled_prompts;
+use super::collections;
+use crate::cli::output::{self, OutputFormat, fail, print_output, print_record};
+use crate::registry;
 use crate::storage::Database;
-use crate::types::PromptSummary;
+use crate::types::{Prompt, PromptSummary};
 
-/// JSON output for list command
-#[derive(Serialize)]
-struct ListOutput {
+/// JSON output for list command (also served by `jfp serve --http`)
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ListOutput {
     prompts: Vec<PromptSummary>,
     count: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     source: Option<String>,
 }
 
+impl ListOutput {
+    pub(crate) fn new(prompts: &[Prompt]) -> Self {
+        Self {
+            prompts: prompts.iter().map(PromptSummary::from).collect(),
+            count: prompts.len(),
+            source: Some("local".to_string()),
+        }
+    }
+}
+
 pub fn run(
     category: Option<String>,
     tag: Option<String>,
     featured: bool,
+    collection: Option<String>,
     use_json: bool,
 ) -> ExitCode {
     // Try to open database
     let db = match Database::open() {
         Ok(db) => db,
-        Err(e) => {
-            if use_json {
-                eprintln!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error opening database: {}", e);
-            }
-            return ExitCode::FAILURE;
-        }
+        Err(e) => return fail(e.into(), use_json),
     };
 
-    // Check if database has prompts, if not, seed with bundled
-    let count = db.prompt_count().unwrap_or(0);
-    if count == 0 {
-        let prompts = bundled_prompts();
-        for prompt in &prompts {
-            if let Err(e) = db.upsert_prompt(prompt) {
-                eprintln!("Warning: Failed to seed prompt {}: {}", prompt.id, e);
-            }
-        }
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    if let Some(name) = &collection
+        && let Err(e) = collections::load(&db, name)
+    {
+        return fail(e, use_json);
+    }
+
+    if output::format() == OutputFormat::Ndjson {
+        let streamed = db.each_prompt_filtered(
+            category.as_deref(),
+            tag.as_deref(),
+            featured,
+            collection.as_deref(),
+            |prompt| print_record(&PromptSummary::from(&prompt)),
+        );
+        return match streamed {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => fail(e, use_json),
+        };
     }
 
     // List prompts with filters
@@ -56,65 +80,54 @@ pub fn run(
         category.as_deref(),
         tag.as_deref(),
         featured,
+        collection.as_deref(),
     ) {
         Ok(p) => p,
-        Err(e) => {
-            if use_json {
-                eprintln!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error listing prompts: {}", e);
-            }
-            return ExitCode::FAILURE;
-        }
+        Err(e) => return fail(e.into(), use_json),
     };
 
     let count = prompts.len();
 
     if use_json {
-        let output = ListOutput {
-            prompts: prompts.iter().map(PromptSummary::from).collect(),
-            count,
-            source: Some("local".to_string()),
-        };
-        match serde_json::to_string_pretty(&output) {
-            Ok(json) => println!("{}", json),
-            Err(e) => {
-                eprintln!(r#"{{"error": "serialization_error", "message": "{}"}}"#, e);
-                return ExitCode::FAILURE;
-            }
+        let output = ListOutput::new(&prompts);
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if output::format() == OutputFormat::Plain {
+        for prompt in &prompts {
+            println!("{}\t{}", prompt.id, prompt.title);
         }
     } else {
         if prompts.is_empty() {
             println!("No prompts found.");
-            if category.is_some() || tag.is_some() || featured {
+            if category.is_some() || tag.is_some() || featured || collection.is_some() {
                 println!("Try different filters or run without filters.");
             }
         } else {
+            let width = output::width().saturating_sub(4);
             println!("Prompts ({}):\n", count);
             for prompt in &prompts {
                 // Print prompt summary
-                print!("  {} - {}", prompt.id, prompt.title);
+                print!("  {} - {}", output::bold(&prompt.id), prompt.title);
                 if prompt.featured {
-                    print!(" [featured]");
+                    print!(" {}", output::paint("[featured]", Color::Yellow));
                 }
                 println!();
 
                 if let Some(desc) = &prompt.description {
-                    let truncated = if desc.len() > 60 {
-                        format!("{}...", &desc[..57])
-                    } else {
-                        desc.clone()
-                    };
-                    println!("    {}", truncated);
+                    println!("    {}", output::truncate(desc, width));
                 }
 
+                let mut details = String::new();
                 if let Some(cat) = &prompt.category {
-                    print!("    Category: {}", cat);
+                    details = format!("Category: {}  ", output::category(cat));
                 }
                 if !prompt.tags.is_empty() {
-                    print!("  Tags: {}", prompt.tags.join(", "));
+                    let room = width.saturating_sub(output::display_width(&details) + "Tags: ".len());
+                    let tags = prompt.tags.join(", ");
+                    details.push_str(&format!("Tags: {}", output::dim(&output::truncate(&tags, room))));
                 }
-                println!("\n");
+                println!("    {}\n", details.trim_end());
             }
         }
     }
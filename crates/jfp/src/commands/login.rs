@@ -0,0 +1,339 @@
+//! Login command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (login):
+//! - Browser flow (default): a callback server on 127.0.0.1 (OS-assigned
+//!   port) and `<JFP_PREMIUM_URL>/cli/auth?port=<port>&redirect=local&state=<nonce>`;
+//!   `/callback` carries token, email, tier, expires_at, user_id,
+//!   refresh_token (or error), and must echo the nonce back as `state`.
+//!   `--no-browser` prints the URL instead of opening it
+//! - Device code flow (`--remote`, or no display / SSH): POST
+//!   `/api/cli/device-code`, then poll `/api/cli/device-token` every
+//!   max(interval, 2)s, with 5s extra on `slow_down`
+//! - JSON: a one-line { status: "pending", ... } once the user has to act,
+//!   then { authenticated, email, tier }
+//! - Errors: `already_logged_in`, `network_error`, `device_code_failed`,
+//!   `expired_token`, `access_denied`, `login_failed`, `timeout`
+
+use std::io::Write;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tiny_http::{Header, Response, Server};
+
+use super::open::open_url;
+use crate::api::{constant_time_eq, parse_query};
+use crate::auth::{self, ApiClient, CLIENT_ID, Credentials, Tier};
+use crate::cli::output::{fail, print_output};
+use crate::config;
+use crate::error::JfpError;
+
+/// Shortest device-token poll interval
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Poll attempts before giving up, whatever the timeout
+const MAX_POLL_ATTEMPTS: u64 = 60;
+
+/// Extra wait when the server answers `slow_down`
+const SLOW_DOWN_DELAY: Duration = Duration::from_secs(5);
+
+/// Tokens from a callback without `expires_at` last a day
+const DEFAULT_TOKEN_LIFETIME: chrono::Duration = chrono::Duration::hours(24);
+
+/// JSON output for login command, once signed in
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct LoginOutput<'a> {
+    authenticated: bool,
+    email: &'a str,
+    tier: Tier,
+}
+
+pub struct LoginOptions {
+    pub remote: bool,
+    pub no_browser: bool,
+    pub timeout: u64,
+}
+
+pub fn run(options: LoginOptions, use_json: bool) -> ExitCode {
+    if let Some(existing) = auth::load_credentials().filter(|c| !c.is_expired()) {
+        let message = format!("Already logged in as {}. Run 'jfp logout' first to switch accounts", existing.email);
+        return fail(JfpError::conflict("already_logged_in", message).with("email", existing.email), use_json);
+    }
+
+    let timeout = Duration::from_secs(options.timeout);
+    let credentials = if options.remote || (!options.no_browser && !can_open_browser()) {
+        login_remote(timeout, use_json)
+    } else {
+        login_local(timeout, options.no_browser, use_json)
+    };
+    let credentials = match credentials {
+        Ok(c) => c,
+        Err(e) => return fail(e, use_json),
+    };
+
+    if let Err(e) = auth::save_credentials(&credentials) {
+        return fail(e.into(), use_json);
+    }
+
+    if use_json {
+        let output = LoginOutput {
+            authenticated: true,
+            email: &credentials.email,
+            tier: credentials.tier,
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else {
+        println!("\n✓ Logged in as {}", credentials.email);
+        println!("Tier: {}", credentials.tier);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Whether a local browser can be opened (not over SSH, and on Linux only
+/// with a display)
+fn can_open_browser() -> bool {
+    if std::env::var_os("SSH_CLIENT").is_some() || std::env::var_os("SSH_TTY").is_some() {
+        return false;
+    }
+    !cfg!(target_os = "linux")
+        || std::env::var_os("DISPLAY").is_some()
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// One-line JSON progress message, printed before waiting on the user
+fn print_pending(payload: Value) {
+    println!("{}", payload);
+    let _ = std::io::stdout().flush();
+}
+
+fn login_local(timeout: Duration, no_browser: bool, use_json: bool) -> Result<Credentials, JfpError> {
+    let server = Server::http("127.0.0.1:0")
+        .map_err(|e| JfpError::network("login_failed", format!("Could not start the callback server: {}", e)))?;
+    let port = server.server_addr().to_ip().map(|a| a.port()).unwrap_or_default();
+    // Any page can send the browser to the callback port; only the auth
+    // server saw this nonce
+    let state: String = rand::rng().random::<[u8; 16]>().iter().map(|b| format!("{:02x}", b)).collect();
+    let auth_url = format!("{}/cli/auth?port={}&redirect=local&state={}", config::premium_url(), port, state);
+
+    if use_json {
+        print_pending(json!({
+            "status": "pending",
+            "authenticated": false,
+            "auth_url": auth_url,
+            "port": port,
+        }));
+    } else {
+        println!("Sign in to JeffreysPrompts Premium at:\n  {}\n", auth_url);
+    }
+    if !no_browser && !open_url(&auth_url) && !use_json {
+        println!("Could not open a browser automatically; open the URL above.");
+    }
+    if !use_json {
+        println!("Waiting for authentication...");
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let request = match server.recv_timeout(remaining) {
+            Ok(Some(request)) => request,
+            Ok(None) => return Err(JfpError::network("timeout", "Login timed out")),
+            Err(e) => return Err(JfpError::network("login_failed", e.to_string())),
+        };
+
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        if path != "/callback" {
+            let _ = request.respond(Response::from_string("Not found").with_status_code(404));
+            continue;
+        }
+
+        let params = parse_query(query);
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+                .filter(|v| !v.is_empty())
+        };
+        if !param("state").is_some_and(|s| constant_time_eq(s.as_bytes(), state.as_bytes())) {
+            // Not from the auth server: ignore it and keep waiting
+            let _ = request.respond(html(403, &error_page("Invalid login state")));
+            continue;
+        }
+        if let Some(error) = param("error") {
+            let _ = request.respond(html(200, &error_page(&error)));
+            return Err(JfpError::denied("login_failed", error));
+        }
+        let (Some(token), Some(email), Some(user_id)) = (param("token"), param("email"), param("user_id")) else {
+            let _ = request.respond(html(400, &error_page("Invalid callback parameters")));
+            return Err(JfpError::denied("login_failed", "Invalid callback parameters"));
+        };
+
+        let _ = request.respond(html(200, &success_page(&email)));
+        return Ok(Credentials {
+            access_token: token,
+            refresh_token: param("refresh_token"),
+            expires_at: param("expires_at")
+                .unwrap_or_else(|| (Utc::now() + DEFAULT_TOKEN_LIFETIME).to_rfc3339()),
+            email,
+            tier: if param("tier").as_deref() == Some("premium") {
+                Tier::Premium
+            } else {
+                Tier::Free
+            },
+            user_id,
+        });
+    }
+}
+
+fn login_remote(timeout: Duration, use_json: bool) -> Result<Credentials, JfpError> {
+    let network_error = |e: auth::AuthError| {
+        JfpError::network("network_error", format!("Could not reach authentication server: {}", e))
+    };
+    let client = ApiClient::new(&config::premium_url()).map_err(network_error)?;
+    let response = client
+        .post("/api/cli/device-code", &json!({ "client_id": CLIENT_ID }))
+        .map_err(network_error)?;
+    if !response.ok() {
+        return Err(JfpError::network("device_code_failed", "Failed to initiate authentication"));
+    }
+
+    let data = response.data.unwrap_or_default();
+    let field = |name: &str| data.get(name).and_then(Value::as_str).filter(|v| !v.is_empty());
+    let (Some(device_code), Some(user_code), Some(verification_url)) =
+        (field("device_code"), field("user_code"), field("verification_url"))
+    else {
+        return Err(JfpError::network("device_code_failed", "Missing device code fields in response"));
+    };
+    let user_code = format_user_code(user_code);
+
+    if use_json {
+        print_pending(json!({
+            "status": "pending",
+            "authenticated": false,
+            "verification_url": verification_url,
+            "user_code": user_code,
+            "message": format!("Visit {} and enter code: {}", verification_url, user_code),
+        }));
+    } else {
+        println!("To sign in, visit:\n\n  {}\n\nAnd enter code:\n\n  {}\n", verification_url, user_code);
+        println!("Waiting for authentication... (Press Ctrl+C to cancel)");
+    }
+
+    let interval = data
+        .get("interval")
+        .and_then(Value::as_u64)
+        .map_or(MIN_POLL_INTERVAL, Duration::from_secs)
+        .max(MIN_POLL_INTERVAL);
+    let attempts = MAX_POLL_ATTEMPTS.min(timeout.as_secs().div_ceil(interval.as_secs()));
+    let body = json!({ "device_code": device_code, "client_id": CLIENT_ID });
+
+    for _ in 0..attempts {
+        std::thread::sleep(interval);
+        let response = match client.post("/api/cli/device-token", &body) {
+            Ok(r) => r,
+            // Keep polling through network blips
+            Err(_) => {
+                progress("x", use_json);
+                continue;
+            }
+        };
+
+        if response.ok() {
+            return response
+                .data
+                .and_then(|data| serde_json::from_value::<Credentials>(data).ok())
+                .filter(Credentials::is_valid)
+                .ok_or_else(|| JfpError::network("login_failed", "Invalid token response"));
+        }
+
+        match response.error_code() {
+            Some("authorization_pending") => progress(".", use_json),
+            Some("slow_down") => std::thread::sleep(SLOW_DOWN_DELAY),
+            Some("expired_token") => {
+                return Err(JfpError::denied("expired_token", "Device code expired. Please try again."));
+            }
+            Some("access_denied") => return Err(JfpError::denied("access_denied", "Authentication was denied.")),
+            // Codes outside the contract are passed along as `reason`
+            code => {
+                let error = JfpError::denied("login_failed", response.error_message());
+                return Err(match code {
+                    Some(code) => error.with("reason", code),
+                    None => error,
+                });
+            }
+        }
+    }
+
+    Err(JfpError::network("timeout", "Authentication timed out. Please try again."))
+}
+
+fn progress(mark: &str, use_json: bool) {
+    if !use_json {
+        print!("{}", mark);
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// "XYZW1234" -> "XYZW-1234"
+fn format_user_code(code: &str) -> String {
+    if code.len() == 8 && code.is_ascii() && !code.contains('-') {
+        format!("{}-{}", &code[..4], &code[4..])
+    } else {
+        code.to_string()
+    }
+}
+
+fn html(status: u16, body: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "text/html; charset=utf-8").expect("static header is valid"))
+}
+
+const PAGE_STYLE: &str = "body { font-family: system-ui; display: flex; justify-content: center; align-items: center; \
+min-height: 100vh; margin: 0; background: #0a0a0a; color: #fff; } .card { text-align: center; padding: 2rem; } \
+.mark { font-size: 4rem; } h1 { margin: 1rem 0 0.5rem; } p { color: #888; }";
+
+fn success_page(email: &str) -> String {
+    page(
+        "Login Successful",
+        "<div class=\"mark\" style=\"color: #22c55e\">✓</div>",
+        &format!(
+            "<p>Signed in as {}</p><p>You can close this window and return to the terminal.</p>",
+            escape_html(email)
+        ),
+    )
+}
+
+fn error_page(error: &str) -> String {
+    page(
+        "Login Failed",
+        "<div class=\"mark\" style=\"color: #ef4444\">✕</div>",
+        &format!(
+            "<p>{}</p><p>Please close this window and try again.</p>",
+            escape_html(error)
+        ),
+    )
+}
+
+fn page(title: &str, mark: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n  <title>{title}</title>\n  <style>{PAGE_STYLE}</style>\n</head>\n\
+         <body>\n  <div class=\"card\">{mark}<h1>{title}!</h1>{body}</div>\n</body>\n</html>\n"
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#039;")
+}
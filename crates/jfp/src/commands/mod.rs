@@ -1,8 +1,10 @@
 //! Command implementations
 
 pub mod about;
+pub mod auth;
 pub mod bundles;
 pub mod categories;
+pub mod collections;
 pub mod completion;
 pub mod config;
 pub mod copy;
@@ -10,13 +12,35 @@ pub mod doctor;
 pub mod export;
 pub mod interactive;
 pub mod list;
+pub mod login;
+pub mod notes;
 pub mod open;
 pub mod random;
 pub mod refresh;
 pub mod render;
+pub mod schema;
 pub mod search;
+pub mod serve;
 pub mod show;
 pub mod status;
 pub mod suggest;
+pub mod sync;
 pub mod tags;
 pub mod update_cli;
+pub mod workflows;
+
+use std::process::ExitCode;
+
+use crate::cli::output::fail;
+use crate::error::JfpError;
+
+/// Report a command that has not been ported from the TypeScript CLI yet
+pub(crate) fn not_implemented(command: &str, use_json: bool) -> ExitCode {
+    fail(
+        JfpError::internal(
+            "not_implemented",
+            format!("`jfp {}` is not yet available in the Rust CLI", command),
+        ),
+        use_json,
+    )
+}
@@ -0,0 +1,188 @@
+//! Notes command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (notes), kept offline-first:
+//! - `jfp notes <id>` lists, `--add <text>` adds, `--delete <note-id>`
+//!   deletes; the prompt must be in the library or the synced premium library
+//! - Notes are stored locally and indexed for search; with a session they
+//!   are uploaded right away (see [`crate::sync::notes`]), otherwise queued
+//!   until the next `jfp notes` or `jfp sync` that can reach the server
+//! - JSON: list { prompt_id, notes, count, pending, online }; add { added,
+//!   prompt_id, note, queued }; delete { deleted, prompt_id, note_id, queued }
+//! - Errors: `not_found`, `empty_note`, `note_not_found`
+
+use std::process::ExitCode;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::auth::{self, ApiClient};
+use crate::cli::output::{fail, print_output};
+use crate::error::JfpError;
+use crate::registry;
+use crate::storage::{self, Database};
+use crate::sync::notes::{self, NoteSync};
+use crate::types::Note;
+
+/// JSON output for notes command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct NotesOutput<'a> {
+    prompt_id: &'a str,
+    notes: Vec<Note>,
+    count: usize,
+    /// Changes not uploaded yet
+    pending: usize,
+    /// Whether the server was reached
+    online: bool,
+}
+
+/// JSON output for `notes --add`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct NoteAddOutput<'a> {
+    added: bool,
+    prompt_id: &'a str,
+    note: Note,
+    /// Saved locally, to be uploaded on the next sync
+    queued: bool,
+}
+
+/// JSON output for `notes --delete`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct NoteDeleteOutput<'a> {
+    deleted: bool,
+    prompt_id: &'a str,
+    note_id: &'a str,
+    /// Deleted locally, to be deleted on the server on the next sync
+    queued: bool,
+}
+
+pub fn run(id: &str, add: Option<String>, delete: Option<String>, use_json: bool) -> ExitCode {
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let _ = registry::seed(&db);
+
+    let known = match db.get_prompt(id) {
+        Ok(Some(_)) => Ok(true),
+        Ok(None) => db.has_library_prompt(id),
+        Err(e) => Err(e),
+    };
+    match known {
+        Ok(true) => {}
+        Ok(false) => return fail(JfpError::not_found("not_found", format!("Prompt not found: {}", id)), use_json),
+        Err(e) => return fail(e.into(), use_json),
+    }
+
+    let result = match (add, delete) {
+        (Some(content), _) => add_note(&db, id, &content, use_json),
+        (None, Some(note_id)) => delete_note(&db, id, &note_id, use_json),
+        (None, None) => list_notes(&db, id, use_json),
+    };
+    result.unwrap_or_else(|e| fail(e, use_json))
+}
+
+/// Upload what is queued for `id` (and fetch the server's notes when
+/// `pull`), if there is a session to do it with
+fn sync_notes(db: &Database, id: &str, pull: bool) -> storage::Result<NoteSync> {
+    let client = auth::signed_in().then(ApiClient::authenticated).and_then(Result::ok);
+    let Some(client) = client else {
+        return Ok(NoteSync {
+            pending: db.pending_notes(Some(id))?.len(),
+            ..NoteSync::default()
+        });
+    };
+    let mut sync = notes::push(db, &client, Some(id))?;
+    if pull && sync.online {
+        sync.online = notes::pull(db, &client, id)?;
+    }
+    Ok(sync)
+}
+
+fn list_notes(db: &Database, id: &str, use_json: bool) -> Result<ExitCode, JfpError> {
+    let sync = sync_notes(db, id, true)?;
+    let notes = db.notes(id)?;
+
+    if use_json {
+        let output = NotesOutput {
+            prompt_id: id,
+            count: notes.len(),
+            notes,
+            pending: sync.pending,
+            online: sync.online,
+        };
+        print_output(&output)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if notes.is_empty() {
+        println!("No notes for prompt: {}", id);
+        println!("\nAdd a note with: jfp notes {} --add 'Your note here'", id);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    println!("Notes for {} ({})\n", id, notes.len());
+    for note in &notes {
+        let date = note.created_at.get(..10).unwrap_or(&note.created_at);
+        let marker = if note.synced { "" } else { "  (not uploaded yet)" };
+        println!("[{}] {}{}", note.id, date, marker);
+        println!("  {}\n", note.content);
+    }
+    println!("Delete a note with: jfp notes {} --delete <note-id>", id);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn add_note(db: &Database, id: &str, content: &str, use_json: bool) -> Result<ExitCode, JfpError> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(JfpError::invalid("empty_note", "Note content cannot be empty"));
+    }
+
+    let added = db.add_note(id, content)?;
+    sync_notes(db, id, false)?;
+    let note = db
+        .notes(id)?
+        .into_iter()
+        .find(|n| n.id == added.id)
+        .unwrap_or(added);
+
+    if use_json {
+        let output = NoteAddOutput {
+            added: true,
+            prompt_id: id,
+            queued: !note.synced,
+            note,
+        };
+        print_output(&output)?;
+    } else {
+        println!("✓ Note added to {}", id);
+        println!("  ID: {}", note.id);
+        if !note.synced {
+            println!("  Saved locally; it will be uploaded on the next sync");
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn delete_note(db: &Database, id: &str, note_id: &str, use_json: bool) -> Result<ExitCode, JfpError> {
+    if !db.delete_note(id, note_id)? {
+        return Err(JfpError::not_found("note_not_found", format!("No note {} on prompt {}", note_id, id)));
+    }
+    sync_notes(db, id, false)?;
+    let queued = db
+        .pending_notes(Some(id))?
+        .iter()
+        .any(|(note, _)| note.id == note_id || note.remote_id.as_deref() == Some(note_id));
+
+    if use_json {
+        let output = NoteDeleteOutput {
+            deleted: true,
+            prompt_id: id,
+            note_id,
+            queued,
+        };
+        print_output(&output)?;
+    } else {
+        println!("✓ Note deleted: {}", note_id);
+    }
+    Ok(ExitCode::SUCCESS)
+}
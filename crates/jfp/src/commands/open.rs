@@ -0,0 +1,89 @@
+//! Open command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (open):
+//! - Opens `https://jeffreysprompts.com/prompts/<id>` via the platform opener
+//! - If the browser can't be launched the URL is printed instead
+
+use std::process::{Command, ExitCode, Stdio};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::output::{fail, print_output};
+use crate::error::JfpError;
+use crate::export;
+use crate::registry;
+use crate::storage::Database;
+
+/// JSON output for open command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct OpenOutput {
+    id: String,
+    url: String,
+    opened: bool,
+}
+
+pub fn run(id: &str, use_json: bool) -> ExitCode {
+    // Open database
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    let prompt = match db.get_prompt(id) {
+        Ok(Some(p)) => p,
+        Ok(None) => return fail(JfpError::not_found("not_found", format!("Prompt not found: {}", id)), use_json),
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    let url = export::prompt_url(&prompt.id);
+    let opened = open_url(&url);
+
+    if use_json {
+        let output = OpenOutput {
+            id: prompt.id,
+            url,
+            opened,
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if opened {
+        println!("Opening {} in browser...", prompt.title);
+        println!("{}", url);
+    } else {
+        println!("Could not open browser. Visit:");
+        println!("{}", url);
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Launch the platform browser opener without waiting for it
+pub(crate) fn open_url(url: &str) -> bool {
+    // `start` is a cmd.exe built-in; the empty title keeps it from taking
+    // the URL as the window title
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.args(["/c", "start", "", url]);
+        c
+    } else if cfg!(target_os = "macos") {
+        let mut c = Command::new("open");
+        c.arg(url);
+        c
+    } else {
+        let mut c = Command::new("xdg-open");
+        c.arg(url);
+        c
+    };
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .is_ok()
+}
@@ -0,0 +1,112 @@
+//! Random command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (random):
+//! - Options: --category, --tag, --copy, --json
+//! - JSON output: { prompt, copied, backend? }
+
+use std::process::ExitCode;
+
+use rand::seq::IndexedRandom;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::output::{self, fail, print_output};
+use crate::clipboard;
+use crate::error::JfpError;
+use crate::registry;
+use crate::storage::Database;
+use crate::types::Prompt;
+
+/// Lines of content shown in the text preview
+const PREVIEW_LINES: usize = 10;
+
+/// JSON output for random command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct RandomOutput<'a> {
+    prompt: &'a Prompt,
+    copied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backend: Option<&'static str>,
+}
+
+pub fn run(
+    category: Option<String>,
+    tag: Option<String>,
+    copy: bool,
+    use_json: bool,
+) -> ExitCode {
+    // Open database
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    let prompts = match db.list_prompts_filtered(category.as_deref(), tag.as_deref(), false, None) {
+        Ok(p) => p,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    let Some(prompt) = prompts.choose(&mut rand::rng()) else {
+        let message = match (&category, &tag) {
+            (Some(c), _) => format!("No prompts found in category: {}", c),
+            (None, Some(t)) => format!("No prompts found with tag: {}", t),
+            (None, None) => "No prompts available".to_string(),
+        };
+        return fail(JfpError::not_found("no_prompts", message), use_json);
+    };
+
+    let backend = if copy { clipboard::copy(&prompt.content) } else { None };
+
+    if use_json {
+        let output = RandomOutput {
+            prompt,
+            copied: backend.is_some(),
+            backend: backend.map(|b| b.name()),
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    println!("{}", output::bold(&format!("# {} - {}", prompt.id, prompt.title)));
+    println!();
+    if let Some(desc) = &prompt.description {
+        for line in output::wrap(desc, output::width()) {
+            println!("{}", line);
+        }
+        println!();
+    }
+
+    let lines: Vec<&str> = prompt.content.lines().collect();
+    println!("---");
+    for line in lines.iter().take(PREVIEW_LINES) {
+        println!("{}", line);
+    }
+    if lines.len() > PREVIEW_LINES {
+        println!("... ({} more lines)", lines.len() - PREVIEW_LINES);
+    }
+    println!("---");
+    println!();
+
+    if let Some(category) = &prompt.category {
+        println!("Category: {}", output::category(category));
+    }
+    if !prompt.tags.is_empty() {
+        println!("Tags: {}", output::dim(&prompt.tags.join(", ")));
+    }
+
+    if copy {
+        match backend {
+            Some(b) => println!("\nCopied to clipboard (via {})", b.name()),
+            None => eprintln!("\nWarning: No clipboard tool available; prompt was not copied"),
+        }
+    } else {
+        println!("\nTip: jfp copy {}  |  jfp show {}", prompt.id, prompt.id);
+    }
+
+    ExitCode::SUCCESS
+}
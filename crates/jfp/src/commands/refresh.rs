@@ -0,0 +1,138 @@
+//! Refresh command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 6 (registry loader):
+//! - Downloads the registry from `JFP_REGISTRY_URL` (default
+//!   [`config::DEFAULT_REGISTRY_URL`]) with `If-None-Match` /
+//!   `If-Modified-Since` from the last download of that URL; `--force`
+//!   skips the validators
+//! - A new registry is validated, then swapped in in one transaction
+//!   (see [`Database::replace_remote_prompts`]); local prompts keep their ids
+//! - JSON: { source: remote|cache, status: updated|not_modified, url,
+//!   prompt_count, added, updated, removed, kept_local, etag?, last_modified?,
+//!   fetched_at, checked_at }
+//! - Errors: `network_error`, `http_error` (with `status`), `invalid_registry`;
+//!   the library is left untouched
+
+use std::process::ExitCode;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::output::{fail, print_output};
+use crate::config;
+use crate::error::JfpError;
+use crate::registry;
+use crate::registry::remote::{self, Fetched, Validators};
+use crate::storage::Database;
+use crate::types::{RegistryCache, RemoteSwap};
+
+/// JSON output for refresh command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct RefreshOutput<'a> {
+    source: &'static str,
+    status: &'static str,
+    url: &'a str,
+    prompt_count: usize,
+    added: usize,
+    updated: usize,
+    removed: usize,
+    kept_local: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    etag: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified: Option<&'a str>,
+    fetched_at: &'a str,
+    checked_at: &'a str,
+}
+
+impl<'a> RefreshOutput<'a> {
+    fn new(cache: &'a RegistryCache, swap: Option<&'a RemoteSwap>) -> Self {
+        Self {
+            source: if swap.is_some() { "remote" } else { "cache" },
+            status: if swap.is_some() { "updated" } else { "not_modified" },
+            url: &cache.url,
+            prompt_count: cache.prompt_count,
+            added: swap.map_or(0, |s| s.added),
+            updated: swap.map_or(0, |s| s.updated),
+            removed: swap.map_or(0, |s| s.removed),
+            kept_local: swap.map_or(&[], |s| &s.kept_local),
+            etag: cache.etag.as_deref(),
+            last_modified: cache.last_modified.as_deref(),
+            fetched_at: &cache.fetched_at,
+            checked_at: &cache.checked_at,
+        }
+    }
+}
+
+pub fn run(force: bool, use_json: bool) -> ExitCode {
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let _ = registry::seed(&db);
+
+    let url = config::registry_url();
+    let cached = match db.registry_cache() {
+        Ok(c) => c,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    // Validators only describe the registry they came from
+    let validators = match &cached {
+        Some(cache) if cache.url == url && !force => Validators {
+            etag: cache.etag.as_deref(),
+            last_modified: cache.last_modified.as_deref(),
+        },
+        _ => Validators::default(),
+    };
+
+    let fetched = match remote::fetch(&url, validators) {
+        Ok(f) => f,
+        Err(e) => return fail(JfpError::from(&e), use_json),
+    };
+
+    let swapped = match fetched {
+        Fetched::NotModified => db.touch_registry_cache().map(|()| None),
+        Fetched::Updated {
+            prompts,
+            etag,
+            last_modified,
+        } => db
+            .replace_remote_prompts(&url, etag.as_deref(), last_modified.as_deref(), &prompts)
+            .map(Some),
+    };
+    let swap = match swapped {
+        Ok(s) => s,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let cache = match db.registry_cache() {
+        Ok(Some(cache)) => cache,
+        // Only possible when a server answers 304 to an unconditional request
+        Ok(None) => {
+            let message = format!("{} answered 304 Not Modified with nothing cached", url);
+            return fail(JfpError::network("http_error", message).with("status", 304), use_json);
+        }
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    let output = RefreshOutput::new(&cache, swap.as_ref());
+    if use_json {
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if let Some(swap) = &swap {
+        println!(
+            "Registry updated from {}: {} prompts ({} added, {} updated, {} removed).",
+            cache.url, cache.prompt_count, swap.added, swap.updated, swap.removed
+        );
+        if !swap.kept_local.is_empty() {
+            println!("Kept your local prompts: {}", swap.kept_local.join(", "));
+        }
+    } else {
+        println!(
+            "Registry unchanged since {} ({} prompts).",
+            cache.fetched_at, cache.prompt_count
+        );
+    }
+
+    ExitCode::SUCCESS
+}
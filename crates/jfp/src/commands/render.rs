@@ -0,0 +1,176 @@
+//! Render command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (render):
+//! - Variables: `--var NAME=VALUE` or `--NAME=VALUE`, plus dynamic defaults
+//!   (CWD, PROJECT_NAME, GIT_BRANCH)
+//! - `--fill` asks for unset variables in a terminal form; refused with
+//!   `not_a_tty` when stdin/stdout aren't terminals, Ctrl+C -> `cancelled` (130)
+//! - Missing required variables -> `missing_variables` with a `missing` list
+//! - Context: `--stdin` and repeatable `--context` under a byte budget
+//!   (`--max-context`, default 200KB, or `--max-context-tokens`)
+//! - JSON output: { id, title, rendered, variables?, context? }
+
+use std::process::ExitCode;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::form::{self, FormError};
+use crate::cli::output::{fail, print_output};
+use crate::error::JfpError;
+use crate::registry;
+use crate::storage::Database;
+use crate::template::context::{BYTES_PER_TOKEN, Context};
+use crate::template::{self, TemplateError, Variables};
+use crate::types::Prompt;
+
+/// Where to gather context from and how much of it to keep
+pub struct ContextArgs {
+    pub paths: Vec<String>,
+    pub stdin: bool,
+    pub max_bytes: usize,
+    pub max_tokens: Option<usize>,
+}
+
+impl ContextArgs {
+    fn requested(&self) -> bool {
+        self.stdin || !self.paths.is_empty()
+    }
+
+    /// Effective budget: the tighter of the byte and token limits
+    fn budget(&self) -> usize {
+        match self.max_tokens {
+            Some(tokens) => self.max_bytes.min(tokens.saturating_mul(BYTES_PER_TOKEN)),
+            None => self.max_bytes,
+        }
+    }
+}
+
+/// JSON output for render command (also served by `jfp serve --http`)
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct RenderOutput {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) rendered: String,
+    #[serde(skip_serializing_if = "Variables::is_empty")]
+    pub(crate) variables: Variables,
+    /// Placeholders left in `rendered` because nothing supplied a value
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) unresolved: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) context: Option<Context>,
+}
+
+pub fn run(id: &str, vars: Vec<String>, fill: bool, context: ContextArgs, use_json: bool) -> ExitCode {
+    // Refuse up front so agents never wait on a form they cannot see
+    if fill && !form::available() {
+        return form_error(&FormError::NotATty, use_json);
+    }
+
+    // Open database
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    let prompt = match db.get_prompt(id) {
+        Ok(Some(p)) => p,
+        Ok(None) => return fail(JfpError::not_found("not_found", format!("Prompt not found: {}", id)), use_json),
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    let variables = match resolve_variables(&prompt, &vars, fill, use_json) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+
+    let mut rendered = template::render(&prompt.content, &variables);
+    let unresolved = template::placeholders(&rendered);
+
+    // Context is appended after substitution so its text is never templated
+    let context = if context.requested() {
+        match Context::gather(context.stdin, &context.paths, context.budget()) {
+            Ok(c) => Some(c),
+            Err(e) => return fail(JfpError::from(&e), use_json),
+        }
+    } else {
+        None
+    };
+    if let Some(context) = context.as_ref().filter(|c| !c.is_empty()) {
+        rendered.push_str(&context.to_markdown());
+    }
+
+    if use_json {
+        let output = RenderOutput {
+            id: prompt.id,
+            title: prompt.title,
+            rendered,
+            variables,
+            unresolved,
+            context,
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else {
+        println!("{}", rendered);
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Work out every variable value for `prompt` from `--var` assignments,
+/// dynamic defaults and (with `fill`) the interactive form
+///
+/// Shared with `jfp copy`; `jfp workflow run` uses the pieces. Problems are
+/// reported here; callers just return the exit code.
+pub(crate) fn resolve_variables(
+    prompt: &Prompt,
+    vars: &[String],
+    fill: bool,
+    use_json: bool,
+) -> Result<Variables, ExitCode> {
+    let mut provided = parse_variables(vars, fill, use_json)?;
+
+    let dynamic = std::env::current_dir()
+        .map(|cwd| template::dynamic_defaults(&cwd))
+        .unwrap_or_default();
+
+    if fill {
+        let mut current = dynamic.clone();
+        current.extend(provided.clone());
+        let values = form::fill(&template::unfilled(prompt, &current)).map_err(|e| form_error(&e, use_json))?;
+        provided.extend(values);
+    }
+
+    template::resolve(prompt, provided, dynamic).map_err(|e| template_error(&e, fill, use_json))
+}
+
+/// Parse `NAME=VALUE` assignments from `--var` / `--NAME=VALUE`
+pub(crate) fn parse_variables(vars: &[String], fill: bool, use_json: bool) -> Result<Variables, ExitCode> {
+    let mut provided = Variables::new();
+    for assignment in vars {
+        let (name, value) =
+            template::parse_assignment(assignment).map_err(|e| template_error(&e, fill, use_json))?;
+        provided.insert(name, value);
+    }
+    Ok(provided)
+}
+
+/// Report a variable problem with enough structure for agents to fix it
+pub(crate) fn template_error(err: &TemplateError, fill: bool, use_json: bool) -> ExitCode {
+    let hint = if fill {
+        "Required variables cannot be empty"
+    } else {
+        "Use --fill to prompt interactively or provide --NAME=VALUE flags"
+    };
+    fail(JfpError::template(err, hint), use_json)
+}
+
+/// Report a form that could not run or was cancelled (exit 130)
+pub(crate) fn form_error(err: &FormError, use_json: bool) -> ExitCode {
+    fail(JfpError::from(err), use_json)
+}
@@ -0,0 +1,144 @@
+//! Schema command implementation
+//!
+//! `jfp schema <command>` prints the JSON Schema (draft 2020-12) of the
+//! command's `--json` payload, generated from the Rust output types, so
+//! agents can validate what they parse.
+//! - `schema_version` and `command` appear as optional properties: they are
+//!   only present with `--envelope`
+//! - `jfp schema` alone lists the commands with a schema:
+//!   { schema_version, commands }
+//! - Unknown command -> `not_found` with the `available` commands
+
+use std::process::ExitCode;
+
+use schemars::generate::SchemaSettings;
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+use serde_json::json;
+
+use super::about::AboutOutput;
+use super::auth::{LogoutOutput, WhoamiOutput};
+use super::bundles::{BundleOutput, BundlesOutput};
+use super::categories::CategoriesOutput;
+use super::collections::{
+    CollectionAddOutput, CollectionCreateOutput, CollectionRemoveOutput, CollectionShowOutput, CollectionSyncOutput,
+    CollectionsOutput,
+};
+use super::copy::CopyOutput;
+use super::list::ListOutput;
+use super::login::LoginOutput;
+use super::notes::{NoteAddOutput, NoteDeleteOutput, NotesOutput};
+use super::open::OpenOutput;
+use super::random::RandomOutput;
+use super::refresh::RefreshOutput;
+use super::render::RenderOutput;
+use super::search::SearchOutput;
+use super::serve::ServeConfig;
+use super::show::ShowOutput;
+use super::status::StatusOutput;
+use super::suggest::SuggestOutput;
+use super::sync::{SyncOutput, SyncStatusOutput};
+use super::tags::TagsOutput;
+use super::workflows::{Transcript, WorkflowOutput, WorkflowsOutput};
+use crate::cli::output::{SCHEMA_VERSION, fail, print_json};
+use crate::error::JfpError;
+use crate::export::Manifest;
+
+/// Builds the schema of one command's payload
+type Generate = fn() -> Schema;
+
+/// Commands with a published schema, by the name `--envelope` reports;
+/// flags that change the payload count as a subcommand (`sync status`)
+const SCHEMAS: &[(&str, Generate)] = &[
+    ("list", schema::<ListOutput>),
+    ("search", schema::<SearchOutput>),
+    ("show", schema::<ShowOutput>),
+    ("categories", schema::<CategoriesOutput>),
+    ("tags", schema::<TagsOutput>),
+    ("random", schema::<RandomOutput<'static>>),
+    ("open", schema::<OpenOutput>),
+    ("status", schema::<StatusOutput>),
+    ("copy", schema::<CopyOutput>),
+    ("export", schema::<Manifest>),
+    ("refresh", schema::<RefreshOutput<'static>>),
+    ("sync", schema::<SyncOutput>),
+    ("sync status", schema::<SyncStatusOutput>),
+    ("render", schema::<RenderOutput>),
+    ("suggest", schema::<SuggestOutput>),
+    ("bundles", schema::<BundlesOutput<'static>>),
+    ("bundle", schema::<BundleOutput<'static>>),
+    ("collections", schema::<CollectionsOutput<'static>>),
+    ("collections create", schema::<CollectionCreateOutput<'static>>),
+    ("collections add", schema::<CollectionAddOutput<'static>>),
+    ("collections remove", schema::<CollectionRemoveOutput<'static>>),
+    ("collections show", schema::<CollectionShowOutput<'static>>),
+    ("collections export", schema::<Manifest>),
+    ("collections sync", schema::<CollectionSyncOutput<'static>>),
+    ("workflows", schema::<WorkflowsOutput<'static>>),
+    ("workflow show", schema::<WorkflowOutput<'static>>),
+    ("workflow run", schema::<Transcript<'static>>),
+    ("notes", schema::<NotesOutput<'static>>),
+    ("notes add", schema::<NoteAddOutput<'static>>),
+    ("notes delete", schema::<NoteDeleteOutput<'static>>),
+    ("login", schema::<LoginOutput<'static>>),
+    ("logout", schema::<LogoutOutput<'static>>),
+    ("whoami", schema::<WhoamiOutput<'static>>),
+    ("serve config", schema::<ServeConfig>),
+    ("about", schema::<AboutOutput>),
+];
+
+pub fn run(command: &[String], use_json: bool) -> ExitCode {
+    let available = || SCHEMAS.iter().map(|(name, _)| *name);
+
+    if command.is_empty() {
+        if use_json {
+            let output = json!({ "schema_version": SCHEMA_VERSION, "commands": available().collect::<Vec<_>>() });
+            return print(&output, use_json);
+        } else {
+            println!("Output schemas (version {}):\n", SCHEMA_VERSION);
+            for name in available() {
+                println!("  {}", name);
+            }
+            println!("\nUse 'jfp schema <command>' to print one.");
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let name = command.join(" ");
+    let Some(schema) = published(&name) else {
+        let error = JfpError::not_found("not_found", format!("No schema for command: {}", name))
+            .with("available", available().collect::<Vec<_>>());
+        return fail(error, use_json);
+    };
+
+    print(&schema, use_json)
+}
+
+/// The published schema of `command`'s payload, if it has one
+pub(crate) fn published(command: &str) -> Option<Schema> {
+    let (name, generate) = SCHEMAS.iter().find(|(name, _)| *name == command)?;
+    let mut schema = generate();
+    schema.insert("title".to_string(), json!(format!("jfp {}", name)));
+    if let Some(properties) = schema.get_mut("properties").and_then(|p| p.as_object_mut()) {
+        properties.insert("schema_version".to_string(), json!({ "const": SCHEMA_VERSION }));
+        properties.insert("command".to_string(), json!({ "const": name }));
+    }
+    Some(schema)
+}
+
+/// Print JSON in either mode; schema output carries no envelope, it
+/// describes it
+fn print<T: Serialize>(value: &T, use_json: bool) -> ExitCode {
+    match print_json(value) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => fail(e, use_json),
+    }
+}
+
+/// Schema of `T` as it serializes
+fn schema<T: JsonSchema>() -> Schema {
+    SchemaSettings::draft2020_12()
+        .for_serialize()
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
@@ -2,27 +2,65 @@
 //!
 //! From EXISTING_JFP_STRUCTURE.md section 10 (search):
 //! - Uses BM25 index from core (buildIndex, searchPrompts)
+//! - Query is tokenized and synonym-expanded (core/search), FTS5 finds the
+//!   candidates and the TS engine's BM25 ranks them
 //! - JSON output: { results, query, authenticated, offline?, warning? }
+//! - Each result carries the fields that matched and a highlighted snippet
+//! - `--collection <name>` searches only that collection's members
+//! - NDJSON output: one result per line, streamed once ranked
+//! - Plain output: `id<TAB>title` per line, best match first
 
 use std::process::ExitCode;
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::registry::bundled_prompts;
-use crate::storage::Database;
-use crate::types::PromptSummary;
+use super::collections;
+use crate::auth;
+use crate::cli::output::{self, OutputFormat, fail, print_output, print_record};
+use crate::error::JfpError;
+use crate::registry;
+use crate::storage::{Database, StorageError};
+use crate::types::{PromptSummary, SearchResult};
+
+/// Highlight markers used in JSON snippets
+const JSON_HIGHLIGHT: (&str, &str) = ("<mark>", "</mark>");
 
 /// Search result for JSON output
-#[derive(Serialize)]
-struct SearchResultOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SearchResultOutput {
     #[serde(flatten)]
     prompt: PromptSummary,
     score: f64,
+    matched_fields: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<SnippetOutput>,
+}
+
+/// Excerpt of the matching field, with matches wrapped in `<mark>` tags
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SnippetOutput {
+    field: &'static str,
+    text: String,
+}
+
+impl From<&SearchResult> for SearchResultOutput {
+    fn from(r: &SearchResult) -> Self {
+        Self {
+            prompt: PromptSummary::from(&r.prompt),
+            score: r.score,
+            matched_fields: r.matched_fields.clone(),
+            snippet: r.snippet.as_ref().map(|s| SnippetOutput {
+                field: s.field,
+                text: s.render(JSON_HIGHLIGHT.0, JSON_HIGHLIGHT.1),
+            }),
+        }
+    }
 }
 
-/// JSON output for search command
-#[derive(Serialize)]
-struct SearchOutput {
+/// JSON output for search command (also served by `jfp serve --http`)
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SearchOutput {
     results: Vec<SearchResultOutput>,
     query: String,
     count: usize,
@@ -31,107 +69,94 @@ struct SearchOutput {
     offline: Option<bool>,
 }
 
-pub fn run(query: &str, limit: usize, use_json: bool) -> ExitCode {
+impl SearchOutput {
+    pub(crate) fn new(query: &str, results: &[SearchResult]) -> Self {
+        Self {
+            results: results.iter().map(SearchResultOutput::from).collect(),
+            query: query.to_string(),
+            count: results.len(),
+            authenticated: auth::signed_in(),
+            offline: None,
+        }
+    }
+}
+
+pub fn run(query: &str, limit: usize, collection: Option<&str>, use_json: bool) -> ExitCode {
     // Validate limit
     if limit == 0 || limit > 100 {
-        if use_json {
-            eprintln!(r#"{{"error": "invalid_limit", "message": "Limit must be between 1 and 100"}}"#);
-        } else {
-            eprintln!("Error: Limit must be between 1 and 100");
-        }
-        return ExitCode::FAILURE;
+        return fail(JfpError::invalid("invalid_limit", "Limit must be between 1 and 100"), use_json);
     }
 
     // Validate query
     if query.trim().is_empty() {
-        if use_json {
-            eprintln!(r#"{{"error": "empty_query", "message": "Search query cannot be empty"}}"#);
-        } else {
-            eprintln!("Error: Search query cannot be empty");
-        }
-        return ExitCode::FAILURE;
+        return fail(JfpError::invalid("empty_query", "Search query cannot be empty"), use_json);
     }
 
     // Open database
     let db = match Database::open() {
         Ok(db) => db,
-        Err(e) => {
-            if use_json {
-                eprintln!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error opening database: {}", e);
-            }
-            return ExitCode::FAILURE;
-        }
+        Err(e) => return fail(e.into(), use_json),
     };
 
-    // Seed if empty
-    let count = db.prompt_count().unwrap_or(0);
-    if count == 0 {
-        let prompts = bundled_prompts();
-        for prompt in &prompts {
-            let _ = db.upsert_prompt(prompt);
-        }
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    if let Some(name) = collection
+        && let Err(e) = collections::load(&db, name)
+    {
+        return fail(e, use_json);
     }
 
-    // Search using FTS5
-    let results = match db.search(query, limit) {
+    if output::format() == OutputFormat::Ndjson {
+        let streamed = db.search_each(query, limit, collection, |result| {
+            print_record(&SearchResultOutput::from(&result)).map_err(Streamed::Print)
+        });
+        return match streamed {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(Streamed::Search(e)) => fail(JfpError::database("search_error", e.to_string()), use_json),
+            Err(Streamed::Print(e)) => fail(e, use_json),
+        };
+    }
+
+    // Only stopwords or punctuation match nothing
+    let results = match db.search(query, limit, collection) {
         Ok(r) => r,
-        Err(e) => {
-            // FTS5 query syntax error - try escaping special chars
-            let escaped_query = escape_fts_query(query);
-            match db.search(&escaped_query, limit) {
-                Ok(r) => r,
-                Err(_) => {
-                    if use_json {
-                        eprintln!(r#"{{"error": "search_error", "message": "{}"}}"#, e);
-                    } else {
-                        eprintln!("Search error: {}", e);
-                    }
-                    return ExitCode::FAILURE;
-                }
-            }
-        }
+        Err(e) => return fail(JfpError::database("search_error", e.to_string()), use_json),
     };
 
     let result_count = results.len();
 
     if use_json {
-        let output = SearchOutput {
-            results: results
-                .iter()
-                .map(|(prompt, score)| SearchResultOutput {
-                    prompt: PromptSummary::from(prompt),
-                    score: *score,
-                })
-                .collect(),
-            query: query.to_string(),
-            count: result_count,
-            authenticated: false,
-            offline: None,
-        };
-        match serde_json::to_string_pretty(&output) {
-            Ok(json) => println!("{}", json),
-            Err(e) => {
-                eprintln!(r#"{{"error": "serialization_error", "message": "{}"}}"#, e);
-                return ExitCode::FAILURE;
-            }
+        let output = SearchOutput::new(query, &results);
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if output::format() == OutputFormat::Plain {
+        for result in &results {
+            println!("{}\t{}", result.prompt.id, result.prompt.title);
         }
     } else {
         if results.is_empty() {
             println!("No results found for \"{}\"", query);
         } else {
+            let (start, end) = output::highlight_markers();
+            let width = output::width().saturating_sub(4);
             println!("Search results for \"{}\" ({} found):\n", query, result_count);
-            for (prompt, score) in &results {
-                println!("  {} - {} (score: {:.2})", prompt.id, prompt.title, score);
-                if let Some(desc) = &prompt.description {
-                    let truncated = if desc.len() > 60 {
-                        format!("{}...", &desc[..57])
-                    } else {
-                        desc.clone()
-                    };
-                    println!("    {}", truncated);
+            for result in &results {
+                let prompt = &result.prompt;
+                print!("  {} - {}", output::bold(&prompt.id), prompt.title);
+                match &prompt.category {
+                    Some(category) => println!("  {}", output::category(category)),
+                    None => println!(),
                 }
+                let excerpt = match &result.snippet {
+                    Some(snippet) => Some(format!("{}: {}", snippet.field, snippet.render(start, end))),
+                    None => prompt.description.clone(),
+                };
+                for line in excerpt.iter().flat_map(|text| output::wrap(text, width)) {
+                    println!("    {}", line);
+                }
+                println!("    {}", output::dim(&format!("matched: {}", result.matched_fields.join(", "))));
                 println!();
             }
         }
@@ -140,9 +165,14 @@ pub fn run(query: &str, limit: usize, use_json: bool) -> ExitCode {
     ExitCode::SUCCESS
 }
 
-/// Escape special FTS5 characters in query
-fn escape_fts_query(query: &str) -> String {
-    // FTS5 special characters: * - + " ( ) { } [ ] ^ ~ : \
-    // For simple queries, we can just wrap in quotes
-    format!("\"{}\"", query.replace('"', "\"\""))
+/// Why streaming search results stopped
+enum Streamed {
+    Search(StorageError),
+    Print(JfpError),
+}
+
+impl From<StorageError> for Streamed {
+    fn from(e: StorageError) -> Self {
+        Self::Search(e)
+    }
 }
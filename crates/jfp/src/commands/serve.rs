@@ -0,0 +1,143 @@
+//! Serve command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (serve):
+//! - MCP server on stdio (see [`crate::mcp`]) over the local library
+//! - `--config` prints the MCP client configuration snippet instead
+//!   (JSON mode: the snippet alone)
+//! - `--http` serves the JSON API on 127.0.0.1 instead (see [`crate::api`]);
+//!   `--port 0` picks a free port. Once listening it prints
+//!   { listening, port, token_file } (JSON) or the URL and token file (text)
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::{self, Api};
+use crate::cli::output::{fail, print_output};
+use crate::error::JfpError;
+use crate::mcp::Server;
+use crate::registry;
+use crate::storage::Database;
+
+/// JSON output for `serve --config`: the `mcpServers` entry MCP clients
+/// need to launch `jfp serve`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ServeConfig {
+    #[serde(rename = "mcpServers")]
+    mcp_servers: BTreeMap<&'static str, McpServer>,
+}
+
+/// How an MCP client starts a server
+#[derive(Serialize, JsonSchema)]
+struct McpServer {
+    command: &'static str,
+    args: Vec<&'static str>,
+}
+
+/// Where `--http` listens and how clients authenticate
+pub struct HttpArgs {
+    pub port: u16,
+    pub token_file: Option<PathBuf>,
+}
+
+pub fn run(config: bool, http: Option<HttpArgs>, use_json: bool) -> ExitCode {
+    if config {
+        return print_config(use_json);
+    }
+    if let Some(http) = http {
+        return run_http(http, use_json);
+    }
+
+    // Open database
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => {
+            // stdout belongs to the protocol, so errors only go to stderr
+            return fail(e.into(), true);
+        }
+    };
+
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    let server = Server::new(db);
+    match server.serve(std::io::stdin().lock(), std::io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => fail(JfpError::internal("io_error", e.to_string()), true),
+    }
+}
+
+/// Serve the HTTP API until killed
+fn run_http(args: HttpArgs, use_json: bool) -> ExitCode {
+    let Some(token_file) = args.token_file.or_else(api::default_token_path) else {
+        let message = "Could not determine a location for the token file; pass --token-file";
+        return fail(JfpError::filesystem("config_error", message), use_json);
+    };
+    let token = match api::load_or_create_token(&token_file) {
+        Ok(token) => token,
+        Err(e) => {
+            let message = format!("Failed to read token file {}: {}", token_file.display(), e);
+            return fail(JfpError::filesystem("token_error", message), use_json);
+        }
+    };
+
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let _ = registry::seed(&db);
+
+    let server = match tiny_http::Server::http(("127.0.0.1", args.port)) {
+        Ok(server) => server,
+        Err(e) => {
+            let message = format!("Failed to listen on 127.0.0.1:{}: {}", args.port, e);
+            return fail(JfpError::network("bind_error", message), use_json);
+        }
+    };
+    let port = server.server_addr().to_ip().map_or(args.port, |addr| addr.port());
+    let url = format!("http://127.0.0.1:{}", port);
+
+    if use_json {
+        let ready = serde_json::json!({
+            "listening": url,
+            "port": port,
+            "token_file": token_file.display().to_string(),
+        });
+        println!("{}", ready);
+    } else {
+        println!("Listening on {}", url);
+        println!("Send \"Authorization: Bearer <token>\" with the token from {}", token_file.display());
+    }
+    let _ = std::io::stdout().flush();
+
+    Api::new(db, token).serve(&server);
+    ExitCode::SUCCESS
+}
+
+/// Print the `mcpServers` entry MCP clients need to launch `jfp serve`
+fn print_config(use_json: bool) -> ExitCode {
+    let server = McpServer { command: "jfp", args: vec!["serve"] };
+    let snippet = ServeConfig { mcp_servers: BTreeMap::from([("jeffreysprompts", server)]) };
+
+    if use_json {
+        if let Err(e) = print_output(&snippet) {
+            return fail(e, use_json);
+        }
+    } else {
+        let json = match serde_json::to_string_pretty(&snippet) {
+            Ok(json) => json,
+            Err(e) => return fail(JfpError::serialization(&e), use_json),
+        };
+        println!("MCP client configuration\n");
+        println!("Add this to your MCP client config, for example Claude Desktop's:");
+        println!("  ~/.config/claude/claude_desktop_config.json (Linux)");
+        println!("  ~/Library/Application Support/Claude/claude_desktop_config.json (macOS)\n");
+        println!("{}\n", json);
+        println!("Restart the client afterwards to load the server.");
+    }
+    ExitCode::SUCCESS
+}
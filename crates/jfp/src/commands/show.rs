@@ -3,18 +3,23 @@
 //! From EXISTING_JFP_STRUCTURE.md section 10 (show):
 //! - Options: --json, --raw
 //! - Not found: JSON payload is exactly { "error": "not_found" }
+//! - The user's notes on the prompt follow it, in JSON as `notes`
 
 use std::process::ExitCode;
 
+use crossterm::style::Color;
+use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::registry::bundled_prompts;
+use crate::cli::output::{self, fail, print_output};
+use crate::error::JfpError;
+use crate::registry;
 use crate::storage::Database;
-use crate::types::Prompt;
+use crate::types::{Note, Prompt};
 
-/// Full prompt output for JSON
-#[derive(Serialize)]
-struct ShowOutput {
+/// Full prompt output for JSON (also served by `jfp serve --http`)
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ShowOutput {
     id: String,
     title: String,
     content: String,
@@ -29,6 +34,8 @@ struct ShowOutput {
     version: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     author: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<Note>,
 }
 
 impl From<&Prompt> for ShowOutput {
@@ -43,96 +50,76 @@ impl From<&Prompt> for ShowOutput {
             featured: p.featured,
             version: p.version.clone(),
             author: p.author.clone(),
+            notes: Vec::new(),
         }
     }
 }
 
+impl ShowOutput {
+    pub(crate) fn with_notes(mut self, notes: Vec<Note>) -> Self {
+        self.notes = notes;
+        self
+    }
+}
+
 pub fn run(id: &str, raw: bool, use_json: bool) -> ExitCode {
     // Validate ID
     if id.trim().is_empty() {
-        if use_json {
-            println!(r#"{{"error": "invalid_id"}}"#);
-        } else {
-            eprintln!("Error: Prompt ID cannot be empty");
-        }
-        return ExitCode::FAILURE;
+        return fail(JfpError::invalid("invalid_id", "Prompt ID cannot be empty").terse(), use_json);
     }
 
     // Open database
     let db = match Database::open() {
         Ok(db) => db,
-        Err(e) => {
-            if use_json {
-                println!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error opening database: {}", e);
-            }
-            return ExitCode::FAILURE;
-        }
+        Err(e) => return fail(e.into(), use_json),
     };
 
-    // Seed if empty
-    let count = db.prompt_count().unwrap_or(0);
-    if count == 0 {
-        let prompts = bundled_prompts();
-        for prompt in &prompts {
-            let _ = db.upsert_prompt(prompt);
-        }
-    }
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
 
     // Get prompt
     let prompt = match db.get_prompt(id) {
         Ok(Some(p)) => p,
         Ok(None) => {
             // Not found - per spec: exactly { "error": "not_found" }
-            if use_json {
-                println!(r#"{{"error": "not_found"}}"#);
-            } else {
-                eprintln!("Prompt not found: {}", id);
-            }
-            return ExitCode::FAILURE;
-        }
-        Err(e) => {
-            if use_json {
-                println!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error loading prompt: {}", e);
-            }
-            return ExitCode::FAILURE;
+            let error = JfpError::not_found("not_found", format!("Prompt not found: {}", id)).terse();
+            return fail(error, use_json);
         }
+        Err(e) => return fail(e.into(), use_json),
     };
 
+    // Notes are an extra; a prompt still shows without them
+    let notes = db.notes(id).unwrap_or_default();
+
     // Output
     if raw {
         // Raw mode: just print content
         print!("{}", prompt.content);
     } else if use_json {
-        let output = ShowOutput::from(&prompt);
-        match serde_json::to_string_pretty(&output) {
-            Ok(json) => println!("{}", json),
-            Err(e) => {
-                println!(r#"{{"error": "serialization_error", "message": "{}"}}"#, e);
-                return ExitCode::FAILURE;
-            }
+        let output = ShowOutput::from(&prompt).with_notes(notes);
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
         }
     } else {
         // Human-readable output
-        println!("# {} - {}", prompt.id, prompt.title);
+        println!("{}", output::bold(&format!("# {} - {}", prompt.id, prompt.title)));
         println!();
 
         if let Some(desc) = &prompt.description {
-            println!("{}", desc);
+            for line in output::wrap(desc, output::width()) {
+                println!("{}", line);
+            }
             println!();
         }
 
         if let Some(cat) = &prompt.category {
-            print!("Category: {}  ", cat);
+            print!("Category: {}  ", output::category(cat));
         }
         if !prompt.tags.is_empty() {
-            print!("Tags: {}", prompt.tags.join(", "));
+            print!("Tags: {}", output::dim(&prompt.tags.join(", ")));
         }
         if prompt.featured {
-            print!("  [Featured]");
+            print!("  {}", output::paint("[Featured]", Color::Yellow));
         }
         println!("\n");
 
@@ -146,6 +133,13 @@ pub fn run(id: &str, raw: bool, use_json: bool) -> ExitCode {
         if let Some(version) = &prompt.version {
             println!("Version: {}", version);
         }
+
+        if !notes.is_empty() {
+            println!("\nNotes ({}):", notes.len());
+            for note in &notes {
+                println!("  [{}] {}", note.id, note.content);
+            }
+        }
     }
 
     ExitCode::SUCCESS
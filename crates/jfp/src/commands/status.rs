@@ -0,0 +1,150 @@
+//! Status command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (status), limited to what the
+//! Rust CLI has so far:
+//! - JSON: { registry: { url, ttl_seconds }, cache: { url, etag?,
+//!   last_modified?, fetched_at, checked_at, age_seconds, prompt_count } | null,
+//!   stale, prompts: { total, bundled, remote, local }, database }
+//! - The cache is stale when it was never fetched, was fetched from another
+//!   URL, or was last checked `JFP_CACHE_TTL` seconds ago or more
+
+use std::process::ExitCode;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::output::{fail, print_output};
+use crate::config;
+use crate::registry;
+use crate::storage::{Database, SOURCE_BUNDLED, SOURCE_LOCAL, SOURCE_REMOTE};
+use crate::types::RegistryCache;
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct RegistryStatus {
+    url: String,
+    ttl_seconds: u64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CacheStatus {
+    #[serde(flatten)]
+    cache: RegistryCache,
+    /// Seconds since the cache was last confirmed current
+    age_seconds: i64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PromptCounts {
+    total: usize,
+    bundled: usize,
+    remote: usize,
+    local: usize,
+}
+
+/// JSON output for status command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct StatusOutput {
+    registry: RegistryStatus,
+    cache: Option<CacheStatus>,
+    stale: bool,
+    prompts: PromptCounts,
+    database: String,
+}
+
+pub fn run(use_json: bool) -> ExitCode {
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let _ = registry::seed(&db);
+
+    let (cache, sources) = match db.registry_cache().and_then(|c| Ok((c, db.source_counts()?))) {
+        Ok(found) => found,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    let url = config::registry_url();
+    let ttl_seconds = config::cache_ttl();
+    let cache = cache.map(|cache| CacheStatus {
+        age_seconds: age_seconds(&cache.checked_at),
+        cache,
+    });
+    let stale = match &cache {
+        Some(status) => status.cache.url != url || status.age_seconds >= ttl_seconds as i64,
+        None => true,
+    };
+
+    let count = |source: &str| {
+        sources
+            .iter()
+            .find(|(s, _)| s == source)
+            .map_or(0, |(_, n)| *n)
+    };
+    let prompts = PromptCounts {
+        total: sources.iter().map(|(_, n)| n).sum(),
+        bundled: count(SOURCE_BUNDLED),
+        remote: count(SOURCE_REMOTE),
+        local: count(SOURCE_LOCAL),
+    };
+
+    let output = StatusOutput {
+        registry: RegistryStatus { url, ttl_seconds },
+        cache,
+        stale,
+        prompts,
+        database: Database::default_path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+    };
+
+    if use_json {
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    println!("Registry: {}", output.registry.url);
+    match &output.cache {
+        Some(status) => {
+            print!(
+                "Cache:    {} prompts, fetched {}, checked {} ago",
+                status.cache.prompt_count,
+                status.cache.fetched_at,
+                format_age(status.age_seconds)
+            );
+            if status.cache.url != output.registry.url {
+                print!(" (from {})", status.cache.url);
+            }
+            println!();
+        }
+        None => println!("Cache:    never refreshed"),
+    }
+    println!(
+        "Prompts:  {} ({} bundled, {} remote, {} local)",
+        output.prompts.total, output.prompts.bundled, output.prompts.remote, output.prompts.local
+    );
+    println!("Database: {}", output.database);
+    if output.stale {
+        println!("\nThe registry cache is stale. Run \"jfp refresh\" to update it.");
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Seconds elapsed since an RFC 3339 timestamp (0 if unparseable or ahead)
+fn age_seconds(timestamp: &str) -> i64 {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| (Utc::now() - t.with_timezone(&Utc)).num_seconds().max(0))
+        .unwrap_or(0)
+}
+
+fn format_age(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
@@ -0,0 +1,311 @@
+//! Suggest command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (suggest):
+//! - Options: --limit, --semantic, --json
+//! - Invalid limit -> `invalid_limit`, empty task -> `empty_task`
+//! - BM25 recall, optional semantic rerank (hash embeddings, no model download)
+//!
+//! Embeddings are cached in SQLite (`prompt_embeddings`) and recomputed only
+//! when a prompt changes.
+
+use std::process::ExitCode;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::cli::output::{fail, print_output};
+use crate::error::JfpError;
+use crate::registry;
+use crate::search::semantic;
+use crate::storage::Database;
+use crate::types::{Prompt, SearchResult};
+
+/// Upper bound on suggestions, as in the TS CLI
+const MAX_LIMIT: usize = 100;
+
+/// Minimum BM25 recall pool when reranking
+const MIN_RERANK_POOL: usize = 10;
+
+/// A single suggestion for JSON output
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SuggestionOutput {
+    id: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    /// 0-1 relevance (combined score when semantic, BM25 / 5 otherwise)
+    relevance: f64,
+    matched_fields: Vec<&'static str>,
+    tip: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blend: Option<BlendOutput>,
+}
+
+/// Per-suggestion breakdown of the semantic blend
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct BlendOutput {
+    bm25: f64,
+    bm25_normalized: f64,
+    semantic: f64,
+    combined: f64,
+}
+
+/// How the semantic rerank was configured
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SemanticOutput {
+    embedder: &'static str,
+    dimensions: usize,
+    bm25_weight: f64,
+    semantic_weight: f64,
+    candidates: usize,
+}
+
+/// JSON output for suggest command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SuggestOutput {
+    task: String,
+    suggestions: Vec<SuggestionOutput>,
+    total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    semantic: Option<SemanticOutput>,
+}
+
+/// A ranked suggestion before formatting
+struct Suggestion {
+    result: SearchResult,
+    blend: Option<semantic::Blend>,
+}
+
+pub fn run(task: &str, limit: usize, semantic: bool, use_json: bool) -> ExitCode {
+    // Validate limit
+    if limit == 0 {
+        return fail(JfpError::invalid("invalid_limit", "Provide a positive number for --limit."), use_json);
+    }
+    if limit > MAX_LIMIT && !use_json {
+        eprintln!("Warning: Limit capped to {} for performance.", MAX_LIMIT);
+    }
+    let limit = limit.min(MAX_LIMIT);
+
+    // Validate task
+    if task.trim().is_empty() {
+        let error = JfpError::invalid("empty_task", "Please provide a task description")
+            .with("hint", "Example: jfp suggest \"improve documentation for my API\"");
+        return fail(error, use_json);
+    }
+
+    // Open database
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    // BM25 recall - widen the pool when reranking
+    let recall_limit = if semantic {
+        (limit * 3).max(MIN_RERANK_POOL)
+    } else {
+        limit
+    };
+    let results = match db.search(task, recall_limit, None) {
+        Ok(r) => r,
+        Err(e) => return fail(JfpError::database("search_error", e.to_string()), use_json),
+    };
+    let candidates = results.len();
+
+    let suggestions = if semantic && !results.is_empty() {
+        if !use_json {
+            eprintln!("Applying semantic reranking...");
+        }
+        match rerank(&db, task, results) {
+            Ok(s) => s,
+            Err(e) => return fail(e.into(), use_json),
+        }
+    } else {
+        results
+            .into_iter()
+            .map(|result| Suggestion { result, blend: None })
+            .collect()
+    };
+    let suggestions: Vec<Suggestion> = suggestions.into_iter().take(limit).collect();
+
+    if use_json {
+        let output = SuggestOutput {
+            task: task.to_string(),
+            total: suggestions.len(),
+            suggestions: suggestions.iter().map(|s| format_suggestion(s, task)).collect(),
+            semantic: semantic.then_some(SemanticOutput {
+                embedder: semantic::EMBEDDER,
+                dimensions: semantic::DIMENSIONS,
+                bm25_weight: semantic::BM25_WEIGHT,
+                semantic_weight: semantic::SEMANTIC_WEIGHT,
+                candidates,
+            }),
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if suggestions.is_empty() {
+        println!("No relevant prompts found for this task.");
+        println!("Try a different task description or use 'jfp list' to see all prompts.");
+    } else {
+        println!("Prompts for: \"{}\"\n", task);
+        for (i, suggestion) in suggestions.iter().enumerate() {
+            let prompt = &suggestion.result.prompt;
+            let percent = (relevance(suggestion) * 100.0).round() as u32;
+            println!("{}. {} ({})", i + 1, prompt.title, prompt.id);
+            println!("   Relevance: {} {}%", relevance_bar(percent), percent);
+            if let Some(blend) = &suggestion.blend {
+                println!(
+                    "   Blend: bm25 {:.2} x {} + semantic {:.2} x {}",
+                    blend.bm25_normalized,
+                    semantic::BM25_WEIGHT,
+                    blend.semantic,
+                    semantic::SEMANTIC_WEIGHT
+                );
+            }
+            println!(
+                "   [{}] {}",
+                prompt.category.as_deref().unwrap_or("uncategorized"),
+                prompt.description.as_deref().unwrap_or("")
+            );
+            println!(
+                "   -> {}",
+                generate_tip(prompt, task, &suggestion.result.matched_fields)
+            );
+            println!();
+        }
+        println!("{}", "-".repeat(50));
+        println!("Use jfp show <id> to view full prompt");
+        println!("Use jfp copy <id> to copy to clipboard");
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Rerank BM25 results with cached (or freshly computed) hash embeddings
+fn rerank(
+    db: &Database,
+    task: &str,
+    results: Vec<SearchResult>,
+) -> crate::storage::Result<Vec<Suggestion>> {
+    let mut candidates = Vec::with_capacity(results.len());
+    for result in results {
+        let embedding = match db.get_embedding(&result.prompt.id, semantic::EMBEDDER)? {
+            Some(v) if v.len() == semantic::DIMENSIONS => v,
+            _ => {
+                let v = semantic::hash_embedding(&embedding_text(&result.prompt));
+                db.put_embedding(&result.prompt.id, semantic::EMBEDDER, &v)?;
+                v
+            }
+        };
+        let score = result.score;
+        candidates.push((result, score, embedding));
+    }
+
+    let query = semantic::hash_embedding(task);
+    Ok(semantic::rerank(&query, candidates)
+        .into_iter()
+        .map(|(result, blend)| Suggestion {
+            result,
+            blend: Some(blend),
+        })
+        .collect())
+}
+
+/// Text embedded for a prompt: title, description and tags (as in the TS CLI)
+fn embedding_text(prompt: &Prompt) -> String {
+    format!(
+        "{} {} {}",
+        prompt.title,
+        prompt.description.as_deref().unwrap_or(""),
+        prompt.tags.join(" ")
+    )
+}
+
+/// Relevance on a 0-1 scale
+fn relevance(suggestion: &Suggestion) -> f64 {
+    match &suggestion.blend {
+        Some(blend) => blend.combined.clamp(0.0, 1.0),
+        None => (suggestion.result.score / 5.0).min(1.0),
+    }
+}
+
+fn format_suggestion(suggestion: &Suggestion, task: &str) -> SuggestionOutput {
+    let prompt = &suggestion.result.prompt;
+    SuggestionOutput {
+        id: prompt.id.clone(),
+        title: prompt.title.clone(),
+        description: prompt.description.clone(),
+        category: prompt.category.clone(),
+        relevance: (relevance(suggestion) * 100.0).round() / 100.0,
+        matched_fields: suggestion.result.matched_fields.clone(),
+        tip: generate_tip(prompt, task, &suggestion.result.matched_fields),
+        blend: suggestion.blend.map(|b| BlendOutput {
+            bm25: b.bm25,
+            bm25_normalized: b.bm25_normalized,
+            semantic: b.semantic,
+            combined: b.combined,
+        }),
+    }
+}
+
+/// Visual relevance bar, 10 cells wide
+fn relevance_bar(percent: u32) -> String {
+    let filled = ((percent as f64) / 10.0).round() as usize;
+    let filled = filled.min(10);
+    format!("{}{}", "█".repeat(filled), "░".repeat(10 - filled))
+}
+
+/// Explain why a prompt matches the task (port of `generateTip`)
+fn generate_tip(prompt: &Prompt, task: &str, matched_fields: &[&str]) -> String {
+    let task = task.to_lowercase();
+    let category = prompt.category.as_deref().unwrap_or("");
+    let mentions = |words: &[&str]| words.iter().any(|w| task.contains(w));
+
+    let category_tip = [
+        (&["idea", "brainstorm", "improve"][..], "ideation", "Great for generating and evaluating improvement ideas"),
+        (&["doc", "readme", "documentation"][..], "documentation", "Helps maintain clear, up-to-date documentation"),
+        (&["cli", "tool", "automat"][..], "automation", "Creates agent-friendly tools and automation"),
+        (&["test", "spec"][..], "testing", "Helps write comprehensive tests"),
+        (&["bug", "fix", "debug"][..], "debugging", "Systematic approach to finding and fixing issues"),
+        (&["refactor", "clean", "improve code"][..], "refactoring", "Helps restructure code for clarity and maintainability"),
+    ]
+    .into_iter()
+    .find(|(words, cat, _)| mentions(words) && category == *cat);
+    if let Some((_, _, tip)) = category_tip {
+        return tip.to_string();
+    }
+
+    if matched_fields.contains(&"title") {
+        return format!("Directly relevant - matches \"{}\"", prompt.title.to_lowercase());
+    }
+
+    if matched_fields.contains(&"tags") && !prompt.tags.is_empty() {
+        let tags: Vec<&str> = prompt.tags.iter().take(3).map(String::as_str).collect();
+        return format!("Tagged with: {}", tags.join(", "));
+    }
+
+    if let Some(desc) = prompt.description.as_ref().filter(|_| matched_fields.contains(&"description")) {
+        return if desc.chars().count() > 60 {
+            format!("{}...", desc.chars().take(57).collect::<String>())
+        } else {
+            desc.clone()
+        };
+    }
+
+    match category {
+        "ideation" => "Helps generate creative solutions".to_string(),
+        "documentation" => "Maintains clear documentation".to_string(),
+        "automation" => "Streamlines repetitive tasks".to_string(),
+        "refactoring" => "Improves code structure".to_string(),
+        "testing" => "Ensures code quality".to_string(),
+        "debugging" => "Identifies and fixes issues".to_string(),
+        "workflow" => "Optimizes your process".to_string(),
+        "communication" => "Improves clarity in writing".to_string(),
+        other => format!("{} prompt", other),
+    }
+}
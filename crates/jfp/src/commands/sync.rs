@@ -0,0 +1,298 @@
+//! Sync command implementation
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (sync) and SYNC_STRATEGY.md:
+//! - Requires a premium session (`not_authenticated`, `session_expired`,
+//!   `requires_premium`)
+//! - GET `/cli/sync?since=<last_synced_at>`; `--force` drops `since` and
+//!   replaces the library with the server's copy instead of merging by id
+//! - The server's `last_modified` (or now) becomes the next `since`
+//! - Queued note changes are uploaded too (see [`sync::notes`])
+//! - Runs under the sync lock (`sync_locked` when another sync holds it past
+//!   the busy timeout); a failed JSONL mirror write is a warning, the
+//!   database stays authoritative
+//! - JSON: { synced, new_prompts, total_prompts, force, synced_at,
+//!   library_path, jsonl_sha256, notes_uploaded, notes_pending };
+//!   `--status`: { synced, last_sync, prompt_count, library_path,
+//!   mirror: { path, sha256, in_sync }, authenticated, user }
+
+use std::process::ExitCode;
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::auth::{self, ApiClient, AuthError, Session, Tier};
+use crate::cli::output::{fail, print_output};
+use crate::config;
+use crate::error::JfpError;
+use crate::registry;
+use crate::storage::Database;
+use crate::sync;
+use crate::types::SyncedPrompt;
+
+const UPGRADE_HINT: &str = "Visit https://pro.jeffreysprompts.com to upgrade";
+
+/// What needs premium, for error messages
+const ACTION: &str = "sync your library";
+
+/// Body of `GET /cli/sync`
+#[derive(Deserialize)]
+struct SyncResponse {
+    prompts: Vec<SyncedPrompt>,
+    #[serde(default)]
+    last_modified: Option<String>,
+}
+
+/// JSON output for sync command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SyncOutput {
+    synced: bool,
+    new_prompts: usize,
+    total_prompts: usize,
+    force: bool,
+    synced_at: String,
+    library_path: Option<String>,
+    jsonl_sha256: Option<String>,
+    notes_uploaded: usize,
+    notes_pending: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+/// JSON output for `sync --status`
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SyncStatusOutput {
+    synced: bool,
+    last_sync: Option<String>,
+    prompt_count: usize,
+    library_path: Option<String>,
+    mirror: MirrorStatus,
+    authenticated: bool,
+    user: Option<SyncUser>,
+}
+
+/// The JSONL mirror on disk
+#[derive(Serialize, JsonSchema)]
+struct MirrorStatus {
+    path: Option<String>,
+    sha256: Option<String>,
+    /// Whether the file is what the last sync wrote
+    in_sync: bool,
+}
+
+/// Who the library belongs to
+#[derive(Serialize, JsonSchema)]
+struct SyncUser {
+    email: String,
+    tier: Tier,
+}
+
+pub fn run(force: bool, status: bool, use_json: bool) -> ExitCode {
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let _ = registry::seed(&db);
+
+    if status {
+        return show_status(&db, use_json);
+    }
+
+    if let Err(e) = require_premium(ACTION) {
+        return fail(e, use_json);
+    }
+
+    let _lock = match sync::lock(config::busy_timeout()) {
+        Ok(lock) => lock,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    let since = if force {
+        None
+    } else {
+        match db.library_sync_state() {
+            Ok(state) => state.last_synced_at,
+            Err(e) => return fail(e.into(), use_json),
+        }
+    };
+    let query: Vec<(&str, &str)> = since.as_deref().map(|since| ("since", since)).into_iter().collect();
+
+    let client = match ApiClient::authenticated() {
+        Ok(client) => client,
+        Err(AuthError::SessionExpired(_)) => return fail(session_expired(), use_json),
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let response = match client.get("/cli/sync", &query) {
+        Ok(response) => response,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    match response.status {
+        401 => return fail(session_expired(), use_json),
+        403 => return fail(requires_premium(ACTION, None), use_json),
+        _ if !response.ok() => {
+            return fail(
+                JfpError::network("sync_failed", format!("Failed to sync library: {}", response.error_message())),
+                use_json,
+            );
+        }
+        _ => {}
+    }
+    let Some(data) = response
+        .data
+        .and_then(|data| serde_json::from_value::<SyncResponse>(data).ok())
+    else {
+        return fail(JfpError::network("sync_failed", "Invalid sync response from server"), use_json);
+    };
+
+    let synced_at = data
+        .last_modified
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+    let total = match db.apply_library_sync(&data.prompts, force, &synced_at) {
+        Ok(total) => total,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let notes = match sync::notes::push(&db, &client, None) {
+        Ok(notes) => notes,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let (library_path, jsonl_sha256, warning) = match sync::write_mirror(&db) {
+        Ok((path, sha256)) => (Some(path.display().to_string()), Some(sha256), None),
+        Err(e) => (None, None, Some(format!("Library synced, but the JSONL mirror was not updated: {}", e))),
+    };
+
+    let output = SyncOutput {
+        synced: true,
+        new_prompts: data.prompts.len(),
+        total_prompts: total,
+        force,
+        synced_at,
+        library_path,
+        jsonl_sha256,
+        notes_uploaded: notes.uploaded,
+        notes_pending: notes.pending,
+        warning,
+    };
+    if use_json {
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else {
+        if let Some(warning) = &output.warning {
+            eprintln!("Warning: {}", warning);
+        }
+        if force {
+            println!("Downloaded {} prompts to local library", output.total_prompts);
+        } else if output.new_prompts == 0 {
+            println!("Library is already up to date");
+        } else {
+            println!("Synced {} prompts ({} total)", output.new_prompts, output.total_prompts);
+        }
+        if output.notes_uploaded > 0 {
+            println!("Uploaded {} note changes", output.notes_uploaded);
+        }
+        if output.notes_pending > 0 {
+            println!("{} note changes are still waiting for upload", output.notes_pending);
+        }
+        if let Some(path) = &output.library_path {
+            println!("Location: {}", path);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Check for a premium session before talking to the server; `action`
+/// completes "Please log in to ..."
+pub(super) fn require_premium(action: &str) -> Result<(), JfpError> {
+    match auth::session() {
+        Ok(Session::Credentials { credentials, .. }) if credentials.tier != Tier::Premium => {
+            Err(requires_premium(action, Some(credentials.tier)))
+        }
+        Ok(_) => Ok(()),
+        Err(AuthError::NotAuthenticated) => Err(JfpError::denied(
+            "not_authenticated",
+            format!("Please log in to {}", action),
+        )
+        .with("hint", "Run 'jfp login' to sign in")),
+        Err(AuthError::SessionExpired(_)) => Err(session_expired()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub(super) fn session_expired() -> JfpError {
+    JfpError::denied("session_expired", "Your session has expired. Please log in again.")
+        .with("hint", "Run 'jfp login' to sign in")
+}
+
+pub(super) fn requires_premium(action: &str, tier: Option<Tier>) -> JfpError {
+    let error = JfpError::denied("requires_premium", format!("You need a premium subscription to {}", action))
+        .with("hint", UPGRADE_HINT);
+    match tier {
+        Some(tier) => error.with("tier", json!(tier)),
+        None => error,
+    }
+}
+
+fn show_status(db: &Database, use_json: bool) -> ExitCode {
+    let state = match db.library_sync_state() {
+        Ok(state) => state,
+        Err(e) => return fail(e.into(), use_json),
+    };
+    let path = sync::library_path();
+    let on_disk = path.as_deref().and_then(sync::mirror_sha256);
+    let in_sync = on_disk.is_some() && on_disk == state.jsonl_sha256;
+    let credentials = auth::load_credentials();
+
+    if use_json {
+        let path = path.as_ref().map(|p| p.display().to_string());
+        let output = SyncStatusOutput {
+            synced: state.last_synced_at.is_some(),
+            last_sync: state.last_synced_at.clone(),
+            prompt_count: state.record_count,
+            library_path: path.clone(),
+            mirror: MirrorStatus { path, sha256: on_disk, in_sync },
+            authenticated: auth::signed_in(),
+            user: credentials.as_ref().map(|c| SyncUser { email: c.email.clone(), tier: c.tier }),
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    println!("Library Sync Status\n");
+    println!("Authentication:");
+    if !auth::signed_in() {
+        println!("  Status: Not logged in");
+        println!("  Run 'jfp login' to sign in");
+    } else {
+        println!("  Status: Authenticated");
+        if let Some(credentials) = &credentials {
+            println!("  Email: {}", credentials.email);
+            println!("  Tier: {}", credentials.tier);
+        }
+    }
+
+    println!("\nLibrary:");
+    match &state.last_synced_at {
+        Some(last_sync) => {
+            println!("  Last sync: {}", last_sync);
+            println!("  Prompts: {}", state.record_count);
+        }
+        None => println!("  Status: Never synced"),
+    }
+    if let Some(path) = &path {
+        let mirror = match (&on_disk, in_sync) {
+            (None, _) => "missing",
+            (Some(_), true) => "in sync",
+            (Some(_), false) => "out of date (run 'jfp sync')",
+        };
+        println!("  Path: {}", path.display());
+        println!("  Mirror: {}", mirror);
+    }
+    if state.last_synced_at.is_none() {
+        println!("\nRun 'jfp sync' to download your library.");
+    }
+    ExitCode::SUCCESS
+}
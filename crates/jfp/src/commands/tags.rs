@@ -2,22 +2,26 @@
 //!
 //! From EXISTING_JFP_STRUCTURE.md section 10:
 //! - tags: counts per tag, sorted by count desc
+//! - NDJSON output: one { name, count } per line, streamed from the database
+//! - Plain output: `name<TAB>count` per line
 
 use std::process::ExitCode;
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::registry::bundled_prompts;
+use crate::cli::output::{self, OutputFormat, Table, fail, print_output, print_record};
+use crate::registry;
 use crate::storage::Database;
 
-#[derive(Serialize)]
-struct TagOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TagOutput {
     name: String,
     count: usize,
 }
 
-#[derive(Serialize)]
-struct TagsOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TagsOutput {
     tags: Vec<TagOutput>,
     total: usize,
 }
@@ -26,36 +30,24 @@ pub fn run(use_json: bool) -> ExitCode {
     // Open database
     let db = match Database::open() {
         Ok(db) => db,
-        Err(e) => {
-            if use_json {
-                eprintln!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error opening database: {}", e);
-            }
-            return ExitCode::FAILURE;
-        }
+        Err(e) => return fail(e.into(), use_json),
     };
 
-    // Seed if empty
-    let count = db.prompt_count().unwrap_or(0);
-    if count == 0 {
-        let prompts = bundled_prompts();
-        for prompt in &prompts {
-            let _ = db.upsert_prompt(prompt);
-        }
+    // Seed from (or upgrade to) the bundled snapshot
+    let _ = registry::seed(&db);
+
+    if output::format() == OutputFormat::Ndjson {
+        let streamed = db.each_tag_count(|name, count| print_record(&TagOutput { name, count }));
+        return match streamed {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => fail(e, use_json),
+        };
     }
 
     // Get tag counts
     let tags = match db.tag_counts() {
         Ok(t) => t,
-        Err(e) => {
-            if use_json {
-                eprintln!(r#"{{"error": "database_error", "message": "{}"}}"#, e);
-            } else {
-                eprintln!("Error getting tags: {}", e);
-            }
-            return ExitCode::FAILURE;
-        }
+        Err(e) => return fail(e.into(), use_json),
     };
 
     let total = tags.len();
@@ -68,21 +60,23 @@ pub fn run(use_json: bool) -> ExitCode {
                 .collect(),
             total,
         };
-        match serde_json::to_string_pretty(&output) {
-            Ok(json) => println!("{}", json),
-            Err(e) => {
-                eprintln!(r#"{{"error": "serialization_error", "message": "{}"}}"#, e);
-                return ExitCode::FAILURE;
-            }
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if output::format() == OutputFormat::Plain {
+        for (name, count) in &tags {
+            println!("{}\t{}", name, count);
         }
     } else {
         if tags.is_empty() {
             println!("No tags found.");
         } else {
             println!("Tags ({}):\n", total);
+            let mut table = Table::new(&["TAG", "PROMPTS"]).align_right(1);
             for (name, count) in &tags {
-                println!("  {} ({})", name, count);
+                table.row(vec![name.clone(), count.to_string()]);
             }
+            table.print();
         }
     }
 
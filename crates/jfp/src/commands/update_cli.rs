@@ -0,0 +1,7 @@
+//! Update-cli command implementation (not yet ported)
+
+use std::process::ExitCode;
+
+pub fn run(_check: bool, _force: bool, use_json: bool) -> ExitCode {
+    super::not_implemented("update-cli", use_json)
+}
@@ -0,0 +1,476 @@
+//! Workflows command implementation
+//!
+//! Workflows are ordered prompt chains from packages/core/src/prompts/workflows.ts.
+//! - `workflows`: JSON { workflows: [{ id, title, description, step_count }], count }
+//! - `workflow show <id>`: the workflow plus resolved `prompts`, any `missing`
+//!   prompt ids and the `unfinished_run` to resume, if there is one
+//! - `workflow run <id>`: renders each step's prompt in order with variables
+//!   shared across steps (`--var`, `--NAME=VALUE`, `--fill`). Every rendered
+//!   step is saved, so `--resume [RUN_ID]` continues where a run stopped;
+//!   `--steps N` stops after N steps. In a terminal the run pauses between steps.
+//! - JSON output (run): the transcript
+//!   { run_id, workflow_id, title, status, variables, started_at, updated_at, completed_at?,
+//!   steps: [{ position, id, prompt_id, title, note, status, rendered?, completed_at? }], next_step? }
+//! - Not found -> `not_found`; `--resume` with nothing to resume -> `no_run_to_resume`;
+//!   a step whose prompt is missing -> `prompt_not_found`; variable problems as in `render`
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::ExitCode;
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::render::{form_error, parse_variables, template_error};
+use crate::cli::form::{self, FormError};
+use crate::cli::output::{fail, print_output};
+use crate::error::JfpError;
+use crate::registry;
+use crate::storage::Database;
+use crate::template::{self, Variables};
+use crate::types::{CompletedStep, Prompt, Workflow, WorkflowRun};
+
+/// How `workflow run` should proceed
+pub struct RunOptions {
+    pub vars: Vec<String>,
+    pub fill: bool,
+    /// Maximum number of steps to render in this invocation
+    pub steps: Option<usize>,
+    /// `Some(None)`: latest unfinished run; `Some(Some(id))`: that run
+    pub resume: Option<Option<i64>>,
+}
+
+/// Summary row for workflows list
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WorkflowSummary<'a> {
+    id: &'a str,
+    title: &'a str,
+    description: &'a str,
+    step_count: usize,
+}
+
+/// JSON output for workflows command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WorkflowsOutput<'a> {
+    workflows: Vec<WorkflowSummary<'a>>,
+    count: usize,
+}
+
+/// Step prompt in workflow details
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WorkflowPrompt<'a> {
+    id: &'a str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<&'a str>,
+}
+
+/// JSON output for workflow show command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WorkflowOutput<'a> {
+    #[serde(flatten)]
+    workflow: &'a Workflow,
+    prompts: Vec<WorkflowPrompt<'a>>,
+    /// Step prompt ids that are not in the local library
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unfinished_run: Option<i64>,
+}
+
+/// One workflow step in a run transcript
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TranscriptStep<'a> {
+    position: usize,
+    id: &'a str,
+    prompt_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+    note: &'a str,
+    /// `done` or `pending`
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rendered: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed_at: Option<&'a str>,
+}
+
+/// JSON output for workflow run command
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct Transcript<'a> {
+    run_id: i64,
+    workflow_id: &'a str,
+    title: &'a str,
+    /// `completed` or `in_progress`
+    status: &'static str,
+    variables: &'a Variables,
+    started_at: &'a str,
+    updated_at: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed_at: Option<&'a str>,
+    steps: Vec<TranscriptStep<'a>>,
+    /// Id of the step a resumed run renders next
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_step: Option<&'a str>,
+}
+
+pub fn list_workflows(use_json: bool) -> ExitCode {
+    let db = match open_seeded(use_json) {
+        Ok(db) => db,
+        Err(code) => return code,
+    };
+
+    let workflows = match db.list_workflows() {
+        Ok(w) => w,
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    if use_json {
+        let output = WorkflowsOutput {
+            workflows: workflows
+                .iter()
+                .map(|w| WorkflowSummary {
+                    id: &w.id,
+                    title: &w.title,
+                    description: &w.description,
+                    step_count: w.steps.len(),
+                })
+                .collect(),
+            count: workflows.len(),
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else if workflows.is_empty() {
+        println!("No workflows found.");
+    } else {
+        println!("Workflows ({}):\n", workflows.len());
+        for workflow in &workflows {
+            let chain: Vec<&str> = workflow.steps.iter().map(|s| s.prompt_id.as_str()).collect();
+            println!("  {} - {} ({} steps)", workflow.id, workflow.title, workflow.steps.len());
+            println!("    {}", workflow.description);
+            println!("    {}\n", chain.join(" → "));
+        }
+        println!("Use \"jfp workflow run <id>\" to run one.");
+    }
+
+    ExitCode::SUCCESS
+}
+
+pub fn show_workflow(id: &str, use_json: bool) -> ExitCode {
+    let db = match open_seeded(use_json) {
+        Ok(db) => db,
+        Err(code) => return code,
+    };
+
+    let workflow = match load_workflow(&db, id, use_json) {
+        Ok(w) => w,
+        Err(code) => return code,
+    };
+
+    // Resolve step prompts against the local library, keeping step order
+    let mut prompts: Vec<Prompt> = Vec::new();
+    let mut missing = Vec::new();
+    for step in &workflow.steps {
+        match db.get_prompt(&step.prompt_id) {
+            Ok(Some(p)) => prompts.push(p),
+            Ok(None) => missing.push(step.prompt_id.as_str()),
+            Err(e) => return fail(e.into(), use_json),
+        }
+    }
+    let unfinished_run = db.latest_unfinished_run(&workflow.id).ok().flatten().map(|r| r.id);
+
+    if use_json {
+        let output = WorkflowOutput {
+            workflow: &workflow,
+            prompts: prompts
+                .iter()
+                .map(|p| WorkflowPrompt {
+                    id: &p.id,
+                    title: &p.title,
+                    description: p.description.as_deref(),
+                    category: p.category.as_deref(),
+                })
+                .collect(),
+            missing,
+            unfinished_run,
+        };
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    println!("# {} - {}", workflow.id, workflow.title);
+    println!();
+    println!("{}", workflow.description);
+
+    println!("\nSteps ({}):\n", workflow.steps.len());
+    for (i, step) in workflow.steps.iter().enumerate() {
+        match prompts.iter().find(|p| p.id == step.prompt_id) {
+            Some(prompt) => println!("  {}. {} - {} ({})", i + 1, step.id, prompt.title, prompt.id),
+            None => println!("  {}. {} - {} (not in local library)", i + 1, step.id, step.prompt_id),
+        }
+        println!("     {}\n", step.note);
+    }
+
+    if !workflow.when_to_use.is_empty() {
+        println!("When to use:\n");
+        for item in &workflow.when_to_use {
+            println!("  → {}", item);
+        }
+        println!();
+    }
+
+    match unfinished_run {
+        Some(run_id) => println!("Resume run {} with: jfp workflow run {} --resume", run_id, workflow.id),
+        None => println!("Run it with: jfp workflow run {}", workflow.id),
+    }
+
+    ExitCode::SUCCESS
+}
+
+pub fn run_workflow(id: &str, options: RunOptions, use_json: bool) -> ExitCode {
+    // Refuse up front so agents never wait on a form they cannot see
+    if options.fill && !form::available() {
+        return form_error(&FormError::NotATty, use_json);
+    }
+
+    let db = match open_seeded(use_json) {
+        Ok(db) => db,
+        Err(code) => return code,
+    };
+
+    let workflow = match load_workflow(&db, id, use_json) {
+        Ok(w) => w,
+        Err(code) => return code,
+    };
+
+    let provided = match parse_variables(&options.vars, options.fill, use_json) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+
+    let run = match options.resume {
+        None => db.create_workflow_run(&workflow.id, &provided).map(Some),
+        Some(None) => db.latest_unfinished_run(&workflow.id),
+        Some(Some(run_id)) => db
+            .get_workflow_run(run_id)
+            .map(|run| run.filter(|r| r.workflow_id == workflow.id)),
+    };
+    let mut run = match run {
+        Ok(Some(run)) => run,
+        Ok(None) => {
+            let message = match options.resume {
+                Some(Some(run_id)) => format!("No run {} of workflow {}", run_id, workflow.id),
+                _ => format!("No unfinished run of workflow {} to resume", workflow.id),
+            };
+            let hint = format!("Start a new run with: jfp workflow run {}", workflow.id);
+            return fail(JfpError::not_found("no_run_to_resume", message).with("hint", hint), use_json);
+        }
+        Err(e) => return fail(e.into(), use_json),
+    };
+
+    // New values override what the run was started with
+    if options.resume.is_some() && !provided.is_empty() {
+        run.variables.extend(provided);
+        if let Err(e) = db.update_run_variables(run.id, &run.variables) {
+            return fail(e.into(), use_json);
+        }
+    }
+
+    let dynamic = std::env::current_dir()
+        .map(|cwd| template::dynamic_defaults(&cwd))
+        .unwrap_or_default();
+    let interactive = !use_json && std::io::stdin().is_terminal();
+    let limit = options.steps.unwrap_or(usize::MAX);
+
+    let mut rendered_now = 0;
+    for (position, step) in workflow.steps.iter().enumerate() {
+        if run.steps.iter().any(|s| s.step_id == step.id) {
+            continue;
+        }
+        if rendered_now == limit {
+            break;
+        }
+        if interactive && rendered_now > 0 && !next_step_requested() {
+            break;
+        }
+
+        let prompt = match db.get_prompt(&step.prompt_id) {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                let message =
+                    format!("Step {} uses a prompt that is not in the library: {}", step.id, step.prompt_id);
+                let error = JfpError::not_found("prompt_not_found", message)
+                    .with("run_id", run.id)
+                    .with("step", step.id.as_str())
+                    .with("prompt_id", step.prompt_id.as_str());
+                return fail(error, use_json);
+            }
+            Err(e) => return fail(e.into(), use_json),
+        };
+
+        if options.fill {
+            let mut current = dynamic.clone();
+            current.extend(run.variables.clone());
+            let values = match form::fill(&template::unfilled(&prompt, &current)) {
+                Ok(v) => v,
+                Err(e) => return form_error(&e, use_json),
+            };
+            if !values.is_empty() {
+                run.variables.extend(values);
+                if let Err(e) = db.update_run_variables(run.id, &run.variables) {
+                    return fail(e.into(), use_json);
+                }
+            }
+        }
+
+        // Progress so far is saved, so a failure here can be resumed with --var
+        let variables = match template::resolve(&prompt, run.variables.clone(), dynamic.clone()) {
+            Ok(v) => v,
+            Err(e) => return template_error(&e, options.fill, use_json),
+        };
+
+        let completed = CompletedStep {
+            step_id: step.id.clone(),
+            position,
+            prompt_id: prompt.id.clone(),
+            rendered: template::render(&prompt.content, &variables),
+            completed_at: Utc::now().to_rfc3339(),
+        };
+        if let Err(e) = db.record_workflow_step(run.id, &completed) {
+            return fail(e.into(), use_json);
+        }
+
+        if !use_json {
+            println!("── Step {}/{}: {} - {}", position + 1, workflow.steps.len(), step.id, prompt.title);
+            println!("   {}\n", step.note);
+            println!("{}\n", completed.rendered);
+        }
+        run.updated_at = completed.completed_at.clone();
+        run.steps.push(completed);
+        rendered_now += 1;
+    }
+
+    let next_step = workflow
+        .steps
+        .iter()
+        .find(|step| !run.steps.iter().any(|s| s.step_id == step.id));
+    if next_step.is_none() && run.completed_at.is_none() {
+        match db.complete_workflow_run(run.id) {
+            Ok(completed_at) => {
+                run.updated_at = completed_at.clone();
+                run.completed_at = Some(completed_at);
+            }
+            Err(e) => return fail(e.into(), use_json),
+        }
+    }
+
+    if use_json {
+        let titles: Vec<Option<String>> = workflow
+            .steps
+            .iter()
+            .map(|step| db.get_prompt(&step.prompt_id).ok().flatten().map(|p| p.title))
+            .collect();
+        let output = transcript(&workflow, &run, &titles, next_step.map(|s| s.id.as_str()));
+        if let Err(e) = print_output(&output) {
+            return fail(e, use_json);
+        }
+    } else {
+        match next_step {
+            None => println!("Workflow {} complete (run {}).", workflow.id, run.id),
+            Some(step) => {
+                println!("Paused before step {} (run {}).", step.id, run.id);
+                println!("Resume with: jfp workflow run {} --resume {}", workflow.id, run.id);
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Build the run transcript: every workflow step, rendered or still pending
+///
+/// `titles` holds each step's prompt title, in step order.
+fn transcript<'a>(
+    workflow: &'a Workflow,
+    run: &'a WorkflowRun,
+    titles: &'a [Option<String>],
+    next_step: Option<&'a str>,
+) -> Transcript<'a> {
+    let steps = workflow
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(position, step)| {
+            let done = run.steps.iter().find(|s| s.step_id == step.id);
+            TranscriptStep {
+                position,
+                id: &step.id,
+                prompt_id: &step.prompt_id,
+                title: titles.get(position).and_then(|t| t.as_deref()),
+                note: &step.note,
+                status: if done.is_some() { "done" } else { "pending" },
+                rendered: done.map(|s| s.rendered.as_str()),
+                completed_at: done.map(|s| s.completed_at.as_str()),
+            }
+        })
+        .collect();
+
+    Transcript {
+        run_id: run.id,
+        workflow_id: &workflow.id,
+        title: &workflow.title,
+        status: if run.completed_at.is_some() { "completed" } else { "in_progress" },
+        variables: &run.variables,
+        started_at: &run.started_at,
+        updated_at: &run.updated_at,
+        completed_at: run.completed_at.as_deref(),
+        steps,
+        next_step,
+    }
+}
+
+/// Wait for Enter between steps in a terminal; `q` pauses the run
+fn next_step_requested() -> bool {
+    eprint!("Press Enter for the next step, or q to pause: ");
+    let _ = std::io::stderr().flush();
+    let mut line = String::new();
+    match std::io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => false,
+        Ok(_) => !line.trim().eq_ignore_ascii_case("q"),
+    }
+}
+
+/// Look up a workflow, reporting `not_found` with the available ids
+fn load_workflow(db: &Database, id: &str, use_json: bool) -> Result<Workflow, ExitCode> {
+    match db.get_workflow(id) {
+        Ok(Some(w)) => Ok(w),
+        Ok(None) => {
+            let available: Vec<String> = db
+                .list_workflows()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|w| w.id)
+                .collect();
+            let error = JfpError::not_found("not_found", format!("Workflow not found: {}", id)).with("available", available);
+            Err(fail(error, use_json))
+        }
+        Err(e) => Err(fail(e.into(), use_json)),
+    }
+}
+
+/// Open the database, seeded from (or upgraded to) the bundled snapshots
+fn open_seeded(use_json: bool) -> Result<Database, ExitCode> {
+    let db = match Database::open() {
+        Ok(db) => db,
+        Err(e) => return Err(fail(e.into(), use_json)),
+    };
+
+    let _ = registry::seed(&db);
+
+    Ok(db)
+}
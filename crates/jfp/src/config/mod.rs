@@ -2,6 +2,19 @@
 
 use directories::ProjectDirs;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Remote registry used by `jfp refresh` (`registry.url` in the TypeScript CLI)
+pub const DEFAULT_REGISTRY_URL: &str = "https://jeffreysprompts.com/api/prompts";
+
+/// Seconds a fetched registry stays fresh (`registry.cacheTtl`)
+pub const DEFAULT_CACHE_TTL: u64 = 3600;
+
+/// How long to wait for a busy database or the sync lock (seconds)
+pub const DEFAULT_BUSY_TIMEOUT: u64 = 5;
+
+/// Premium site that runs login and token refresh
+pub const DEFAULT_PREMIUM_URL: &str = "https://pro.jeffreysprompts.com";
 
 /// Get the configuration directory path
 pub fn config_dir() -> Option<PathBuf> {
@@ -15,7 +28,55 @@ pub fn config_dir() -> Option<PathBuf> {
         .map(|dirs| dirs.config_dir().to_path_buf())
 }
 
+/// Remote registry URL, overridden by `JFP_REGISTRY_URL`
+pub fn registry_url() -> String {
+    std::env::var("JFP_REGISTRY_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_REGISTRY_URL.to_string())
+}
+
+/// Registry cache lifetime in seconds, overridden by `JFP_CACHE_TTL`
+///
+/// Values that are not a whole number of seconds are ignored.
+pub fn cache_ttl() -> u64 {
+    std::env::var("JFP_CACHE_TTL")
+        .ok()
+        .and_then(|ttl| ttl.trim().parse().ok())
+        .unwrap_or(DEFAULT_CACHE_TTL)
+}
+
+/// Wait for a locked database or sync lock, overridden by
+/// `JFP_BUSY_TIMEOUT` (seconds)
+pub fn busy_timeout() -> Duration {
+    let seconds = std::env::var("JFP_BUSY_TIMEOUT")
+        .ok()
+        .and_then(|secs| secs.trim().parse().ok())
+        .unwrap_or(DEFAULT_BUSY_TIMEOUT);
+    Duration::from_secs(seconds)
+}
+
+/// Premium site URL, overridden by `JFP_PREMIUM_URL`
+pub fn premium_url() -> String {
+    std::env::var("JFP_PREMIUM_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| DEFAULT_PREMIUM_URL.to_string())
+}
+
+/// Premium API base URL, overridden by `JFP_PREMIUM_API_URL`; defaults to
+/// `<premium_url>/api`
+pub fn premium_api_url() -> String {
+    std::env::var("JFP_PREMIUM_API_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| format!("{}/api", premium_url()))
+}
+
 /// Get the cache directory path
+#[allow(dead_code)]
 pub fn cache_dir() -> Option<PathBuf> {
     // Check for JFP_HOME override (same as config_dir for consistency)
     if let Ok(home) = std::env::var("JFP_HOME") {
@@ -0,0 +1,316 @@
+//! Structured command errors
+//!
+//! Every failing command reports one [`JfpError`], rendered by
+//! [`crate::cli::output::print_error`] on stderr: in JSON as
+//! `{ "error": <code>, "message": <message>, ...details }`, otherwise as
+//! `Error: <message>`. The `error` codes are part of the CLI contract (they
+//! follow EXISTING_JFP_STRUCTURE.md) and never change meaning; the variant
+//! picks the process exit code, so scripts can branch on the category
+//! without parsing output.
+//!
+//! | Exit | Variant |
+//! |------|---------|
+//! | 1 | [`JfpError::NotFound`] |
+//! | 2 | [`JfpError::InvalidArgument`] |
+//! | 3 | [`JfpError::Filesystem`] (export and other file writes) |
+//! | 4 | [`JfpError::Network`] |
+//! | 5 | [`JfpError::PermissionDenied`] (sign-in and premium) |
+//! | 6 | [`JfpError::Database`] |
+//! | 7 | [`JfpError::Conflict`] |
+//! | 8 | [`JfpError::Internal`] |
+//! | 130 | [`JfpError::Cancelled`] |
+//!
+//! Commands that report partial failure in their regular output (an export
+//! manifest with failed files, say) exit with the matching code from
+//! [`exit`].
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::auth::AuthError;
+use crate::cli::form::FormError;
+use crate::registry::remote::FetchError;
+use crate::storage::StorageError;
+use crate::sync::SyncError;
+use crate::template::TemplateError;
+use crate::template::context::ContextError;
+
+/// Process exit codes, one per error category
+pub mod exit {
+    pub const NOT_FOUND: u8 = 1;
+    pub const INVALID_ARGUMENT: u8 = 2;
+    pub const FILESYSTEM: u8 = 3;
+    pub const NETWORK: u8 = 4;
+    pub const PERMISSION_DENIED: u8 = 5;
+    pub const DATABASE: u8 = 6;
+    pub const CONFLICT: u8 = 7;
+    pub const INTERNAL: u8 = 8;
+    pub const CANCELLED: u8 = 130;
+}
+
+/// Code, message and extra JSON fields of an error
+#[derive(Debug)]
+pub struct ErrorBody {
+    code: &'static str,
+    message: String,
+    details: Map<String, Value>,
+    /// Leave the message out of the JSON payload
+    terse: bool,
+}
+
+impl fmt::Display for ErrorBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// A command failure, by category
+#[derive(Debug, Error)]
+pub enum JfpError {
+    /// A prompt, bundle, workflow, collection, note or file that does not exist
+    #[error("{0}")]
+    NotFound(ErrorBody),
+
+    /// Arguments or input the user can correct
+    #[error("{0}")]
+    InvalidArgument(ErrorBody),
+
+    /// Reading or writing files outside the database
+    #[error("{0}")]
+    Filesystem(ErrorBody),
+
+    /// The server could not be reached or did not answer as expected
+    #[error("{0}")]
+    Network(ErrorBody),
+
+    /// Sign-in required, expired or without the needed tier
+    #[error("{0}")]
+    PermissionDenied(ErrorBody),
+
+    /// The local SQLite database failed
+    #[error("{0}")]
+    Database(ErrorBody),
+
+    /// Something already exists or is held by another process
+    #[error("{0}")]
+    Conflict(ErrorBody),
+
+    /// The user cancelled an interactive step
+    #[error("{0}")]
+    Cancelled(ErrorBody),
+
+    /// Anything else: serialization, clipboard, terminal failures
+    #[error("{0}")]
+    Internal(ErrorBody),
+}
+
+fn body(code: &'static str, message: impl Into<String>) -> ErrorBody {
+    ErrorBody {
+        code,
+        message: message.into(),
+        details: Map::new(),
+        terse: false,
+    }
+}
+
+impl JfpError {
+    pub fn not_found(code: &'static str, message: impl Into<String>) -> Self {
+        Self::NotFound(body(code, message))
+    }
+
+    pub fn invalid(code: &'static str, message: impl Into<String>) -> Self {
+        Self::InvalidArgument(body(code, message))
+    }
+
+    pub fn filesystem(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Filesystem(body(code, message))
+    }
+
+    pub fn network(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Network(body(code, message))
+    }
+
+    pub fn denied(code: &'static str, message: impl Into<String>) -> Self {
+        Self::PermissionDenied(body(code, message))
+    }
+
+    pub fn database(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Database(body(code, message))
+    }
+
+    pub fn conflict(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Conflict(body(code, message))
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        Self::Cancelled(body("cancelled", message))
+    }
+
+    pub fn internal(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Internal(body(code, message))
+    }
+
+    /// `{ "error": "serialization_error" }` for output that would not encode
+    pub fn serialization(err: &serde_json::Error) -> Self {
+        Self::internal("serialization_error", err.to_string())
+    }
+
+    /// A variable problem, with enough structure for agents to fix it;
+    /// `hint` accompanies missing variables
+    pub fn template(err: &TemplateError, hint: &str) -> Self {
+        let message = err.to_string();
+        match err {
+            TemplateError::InvalidAssignment(_) => Self::invalid("invalid_variable", message),
+            TemplateError::MissingVariables(missing) => Self::invalid("missing_variables", message)
+                .with("missing", missing.clone())
+                .with("hint", hint),
+            TemplateError::InvalidOption { name, value, options } => Self::invalid("invalid_option", message)
+                .with("variable", name.as_str())
+                .with("value", value.as_str())
+                .with("options", options.clone()),
+            TemplateError::FileNotFound { name, .. }
+            | TemplateError::NotAFile { name, .. }
+            | TemplateError::ReadFile { name, .. } => {
+                Self::invalid("variable_error", message).with("variable", name.as_str())
+            }
+        }
+    }
+
+    /// Add a field to the JSON payload
+    pub fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.body_mut().details.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Leave the message out of the JSON payload, for contracts that fix
+    /// it to the code alone
+    pub fn terse(mut self) -> Self {
+        self.body_mut().terse = true;
+        self
+    }
+
+    /// The `hint` field, shown under the message in text mode
+    pub fn hint(&self) -> Option<&str> {
+        self.body().details.get("hint").and_then(Value::as_str)
+    }
+
+    /// Process exit code for this category
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::NotFound(_) => exit::NOT_FOUND,
+            Self::InvalidArgument(_) => exit::INVALID_ARGUMENT,
+            Self::Filesystem(_) => exit::FILESYSTEM,
+            Self::Network(_) => exit::NETWORK,
+            Self::PermissionDenied(_) => exit::PERMISSION_DENIED,
+            Self::Database(_) => exit::DATABASE,
+            Self::Conflict(_) => exit::CONFLICT,
+            Self::Internal(_) => exit::INTERNAL,
+            Self::Cancelled(_) => exit::CANCELLED,
+        }
+    }
+
+    /// The JSON error payload
+    pub fn to_json(&self) -> Value {
+        let body = self.body();
+        let mut payload = Map::new();
+        payload.insert("error".to_string(), Value::from(body.code));
+        if !body.terse {
+            payload.insert("message".to_string(), Value::from(body.message.as_str()));
+        }
+        for (key, value) in &body.details {
+            payload.insert(key.clone(), value.clone());
+        }
+        Value::Object(payload)
+    }
+
+    fn body(&self) -> &ErrorBody {
+        match self {
+            Self::NotFound(b)
+            | Self::InvalidArgument(b)
+            | Self::Filesystem(b)
+            | Self::Network(b)
+            | Self::PermissionDenied(b)
+            | Self::Database(b)
+            | Self::Conflict(b)
+            | Self::Cancelled(b)
+            | Self::Internal(b) => b,
+        }
+    }
+
+    fn body_mut(&mut self) -> &mut ErrorBody {
+        match self {
+            Self::NotFound(b)
+            | Self::InvalidArgument(b)
+            | Self::Filesystem(b)
+            | Self::Network(b)
+            | Self::PermissionDenied(b)
+            | Self::Database(b)
+            | Self::Conflict(b)
+            | Self::Cancelled(b)
+            | Self::Internal(b) => b,
+        }
+    }
+}
+
+impl From<StorageError> for JfpError {
+    fn from(err: StorageError) -> Self {
+        Self::database("database_error", err.to_string())
+    }
+}
+
+impl From<AuthError> for JfpError {
+    fn from(err: AuthError) -> Self {
+        let body = body(err.code(), err.to_string());
+        match err {
+            AuthError::NotAuthenticated | AuthError::SessionExpired(_) => Self::PermissionDenied(body),
+            AuthError::Runtime(_) | AuthError::Request { .. } => Self::Network(body),
+            AuthError::NoConfigDir | AuthError::Io { .. } => Self::Filesystem(body),
+        }
+    }
+}
+
+impl From<SyncError> for JfpError {
+    fn from(err: SyncError) -> Self {
+        let body = body(err.code(), err.to_string());
+        match err {
+            SyncError::Locked { .. } => Self::Conflict(body),
+            SyncError::Storage(_) => Self::Database(body),
+            SyncError::NoConfigDir | SyncError::Io { .. } => Self::Filesystem(body),
+        }
+    }
+}
+
+impl From<&ContextError> for JfpError {
+    fn from(err: &ContextError) -> Self {
+        let body = body(err.code(), err.to_string());
+        match err {
+            ContextError::NotFound(_) => Self::NotFound(body),
+            ContextError::InvalidGlob { .. } | ContextError::StdinTimeout => Self::InvalidArgument(body),
+            ContextError::Read { .. } | ContextError::Stdin(_) => Self::Filesystem(body),
+        }
+    }
+}
+
+impl From<&FetchError> for JfpError {
+    fn from(err: &FetchError) -> Self {
+        let message = err.to_string();
+        match err {
+            FetchError::Runtime(_) | FetchError::Request { .. } => Self::network("network_error", message),
+            FetchError::Status { status, .. } => Self::network("http_error", message).with("status", *status),
+            FetchError::Invalid { .. } => Self::network("invalid_registry", message),
+        }
+    }
+}
+
+impl From<&FormError> for JfpError {
+    fn from(err: &FormError) -> Self {
+        let message = err.to_string();
+        match err {
+            FormError::NotATty => Self::invalid("not_a_tty", message),
+            FormError::Cancelled => Self::cancelled(message),
+            FormError::Io(_) => Self::internal("terminal_error", message),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Registry payload
+//!
+//! Port of `buildRegistryPayload` from packages/core/src/export/json.ts: the
+//! document served to (and consumed by) other registry tooling.
+
+use std::collections::BTreeSet;
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::types::{Bundle, Prompt};
+
+/// Payload format version understood by registry consumers
+const SCHEMA_VERSION: u32 = 1;
+
+/// Registry version stamped on exported payloads
+const REGISTRY_VERSION: &str = "1.0.0";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryPayload<'a> {
+    schema_version: u32,
+    version: &'static str,
+    generated_at: String,
+    prompts: &'a [Prompt],
+    bundles: &'a [Bundle],
+    meta: RegistryMeta,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistryMeta {
+    prompt_count: usize,
+    categories: Vec<String>,
+    tags: Vec<String>,
+}
+
+/// Registry payload for `prompts` and `bundles`, with sorted category and
+/// tag lists
+pub fn registry_payload<'a>(prompts: &'a [Prompt], bundles: &'a [Bundle]) -> RegistryPayload<'a> {
+    let categories: BTreeSet<&String> = prompts.iter().filter_map(|p| p.category.as_ref()).collect();
+    let tags: BTreeSet<&String> = prompts.iter().flat_map(|p| &p.tags).collect();
+
+    RegistryPayload {
+        schema_version: SCHEMA_VERSION,
+        version: REGISTRY_VERSION,
+        generated_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        prompts,
+        bundles,
+        meta: RegistryMeta {
+            prompt_count: prompts.len(),
+            categories: categories.into_iter().cloned().collect(),
+            tags: tags.into_iter().cloned().collect(),
+        },
+    }
+}
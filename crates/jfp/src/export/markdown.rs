@@ -0,0 +1,59 @@
+//! Standalone markdown for a prompt
+//!
+//! Port of `generatePromptMarkdown` from packages/core/src/export/markdown.ts.
+
+use super::{code_fence, prompt_url};
+use crate::types::Prompt;
+
+/// Markdown document for a single prompt, ending in a newline
+pub fn prompt_markdown(prompt: &Prompt) -> String {
+    let mut lines = vec![format!("# {}", prompt.title), String::new()];
+
+    if let Some(description) = &prompt.description {
+        lines.push(format!("> {}", description));
+        lines.push(String::new());
+    }
+    if let Some(category) = &prompt.category {
+        lines.push(format!("**Category:** {}", category));
+    }
+    lines.push(format!("**Tags:** {}", prompt.tags.join(", ")));
+    if let Some(author) = &prompt.author {
+        match &prompt.twitter {
+            Some(twitter) => lines.push(format!("**Author:** {} ({})", author, twitter)),
+            None => lines.push(format!("**Author:** {}", author)),
+        }
+    }
+    if let Some(version) = &prompt.version {
+        lines.push(format!("**Version:** {}", version));
+    }
+
+    lines.push(String::new());
+    lines.push("## Prompt".to_string());
+    lines.push(String::new());
+
+    // A fence longer than any backtick run in the content
+    let fence = code_fence(&prompt.content);
+    lines.push(fence.clone());
+    lines.push(prompt.content.clone());
+    lines.push(fence);
+
+    for (heading, items) in [
+        ("When to Use", &prompt.when_to_use),
+        ("Tips", &prompt.tips),
+        ("Examples", &prompt.examples),
+    ] {
+        if !items.is_empty() {
+            lines.push(String::new());
+            lines.push(format!("## {}", heading));
+            lines.push(String::new());
+            lines.extend(items.iter().map(|item| format!("- {}", item)));
+        }
+    }
+
+    lines.push(String::new());
+    lines.push("---".to_string());
+    lines.push(String::new());
+    lines.push(format!("*From [JeffreysPrompts.com]({})*", prompt_url(&prompt.id)));
+
+    lines.join("\n") + "\n"
+}
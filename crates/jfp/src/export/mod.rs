@@ -0,0 +1,425 @@
+//! Prompt export
+//!
+//! Port of packages/core/src/export. A [`Format`] turns prompts into
+//! [`ExportFile`]s, one per prompt or a single combined file, and
+//! [`write_files`] puts them on disk:
+//!
+//! - File names come from prompt ids, reduced to a safe character set and
+//!   made unique within the export (case-insensitively, with `-2`, `-3`
+//!   suffixes) so no prompt overwrites another.
+//! - Files that already exist are handled per [`OnConflict`].
+//! - Every file is written to a temporary sibling and renamed into place,
+//!   so readers never see a half-written export.
+//!
+//! The returned [`Manifest`] lists each file with its size and SHA-256;
+//! each outcome is also handed to the caller as it happens, as [`Written`].
+
+mod json;
+mod markdown;
+mod skills;
+mod yaml;
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+pub use json::registry_payload;
+pub use markdown::prompt_markdown;
+pub use skills::{bundle_skill_md, skill_md};
+
+use crate::types::{Bundle, Prompt};
+
+const PROMPT_URL_BASE: &str = "https://jeffreysprompts.com/prompts";
+const BUNDLE_URL_BASE: &str = "https://jeffreysprompts.com/bundles";
+
+/// File name used when an id has no safe characters at all
+const FALLBACK_NAME: &str = "prompt";
+
+/// Output format for `jfp export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `<id>.md` standalone markdown
+    Markdown,
+    /// `<id>/SKILL.md` Claude Code skill
+    Skill,
+    /// `<id>.json` prompt object, as stored in the registry
+    Json,
+    /// `<id>.yaml` prompt document
+    Yaml,
+    /// `prompts.jsonl`, one prompt per line
+    Jsonl,
+    /// `registry.json` registry payload with bundles and metadata
+    Registry,
+}
+
+impl Format {
+    pub const NAMES: &[&str] = &["md", "skill", "json", "yaml", "jsonl", "registry"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "md" | "markdown" => Some(Self::Markdown),
+            "skill" => Some(Self::Skill),
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "jsonl" => Some(Self::Jsonl),
+            "registry" => Some(Self::Registry),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Skill => "skill",
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Jsonl => "jsonl",
+            Self::Registry => "registry",
+        }
+    }
+
+    /// Path of the file named `name`, relative to the output directory
+    fn path(self, name: &str) -> PathBuf {
+        match self {
+            Self::Markdown => PathBuf::from(format!("{}.md", name)),
+            Self::Skill => Path::new(name).join("SKILL.md"),
+            Self::Json | Self::Registry => PathBuf::from(format!("{}.json", name)),
+            Self::Yaml => PathBuf::from(format!("{}.yaml", name)),
+            Self::Jsonl => PathBuf::from(format!("{}.jsonl", name)),
+        }
+    }
+}
+
+/// What to do when an export file already exists on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnConflict {
+    /// Replace it (re-exporting is idempotent)
+    Overwrite,
+    /// Leave it alone and report it as skipped
+    Skip,
+    /// Write next to it under the first free `-N` suffix
+    Rename,
+}
+
+impl OnConflict {
+    pub const NAMES: &[&str] = &["overwrite", "skip", "rename"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "overwrite" => Some(Self::Overwrite),
+            "skip" => Some(Self::Skip),
+            "rename" => Some(Self::Rename),
+            _ => None,
+        }
+    }
+}
+
+/// One file of an export, before it is written
+#[derive(Debug)]
+pub struct ExportFile {
+    /// File name without extension (the directory name for skills)
+    name: String,
+    /// Prompts contained in the file
+    ids: Vec<String>,
+    content: String,
+}
+
+/// Files written by [`write_files`]
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Manifest {
+    pub success: bool,
+    pub format: &'static str,
+    pub output_dir: String,
+    pub exported: Vec<ManifestEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedFile>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<FailedFile>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ManifestEntry {
+    pub file: String,
+    pub ids: Vec<String>,
+    pub bytes: usize,
+    pub sha256: String,
+    /// The name the file would have had, when `--on-conflict rename` moved it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renamed_from: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SkippedFile {
+    pub file: String,
+    pub ids: Vec<String>,
+    pub reason: &'static str,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FailedFile {
+    pub file: String,
+    pub ids: Vec<String>,
+    pub error: String,
+}
+
+/// What happened to one file, tagged with its `status`
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Written<'a> {
+    Exported(&'a ManifestEntry),
+    Skipped(&'a SkippedFile),
+    Failed(&'a FailedFile),
+}
+
+/// Build the files for `prompts` in `format`
+///
+/// `bundles` only appear in the `registry` payload.
+pub fn files(prompts: &[Prompt], bundles: &[Bundle], format: Format) -> Vec<ExportFile> {
+    match format {
+        Format::Jsonl => vec![ExportFile {
+            name: "prompts".to_string(),
+            ids: prompts.iter().map(|p| p.id.clone()).collect(),
+            content: jsonl(prompts),
+        }],
+        Format::Registry => vec![ExportFile {
+            name: "registry".to_string(),
+            ids: prompts.iter().map(|p| p.id.clone()).collect(),
+            content: pretty_json(&registry_payload(prompts, bundles)),
+        }],
+        _ => {
+            let mut used = HashSet::new();
+            prompts
+                .iter()
+                .map(|prompt| ExportFile {
+                    name: unique_name(&safe_name(&prompt.id), &mut used),
+                    ids: vec![prompt.id.clone()],
+                    content: prompt_document(prompt, format),
+                })
+                .collect()
+        }
+    }
+}
+
+/// Everything `--stdout` prints for `prompts` in `format`
+///
+/// Per-prompt documents are joined the way each format concatenates: a
+/// rule between markdown documents, `---` separators for YAML, and an array
+/// when more than one JSON prompt is requested.
+pub fn stdout_text(prompts: &[Prompt], bundles: &[Bundle], format: Format) -> String {
+    match format {
+        Format::Jsonl => jsonl(prompts),
+        Format::Registry => pretty_json(&registry_payload(prompts, bundles)) + "\n",
+        Format::Json if prompts.len() == 1 => prompt_document(&prompts[0], format) + "\n",
+        Format::Json => pretty_json(&prompts) + "\n",
+        Format::Yaml => prompts
+            .iter()
+            .map(|p| format!("---\n{}", prompt_document(p, format)))
+            .collect(),
+        Format::Markdown | Format::Skill => prompts
+            .iter()
+            .map(|p| prompt_document(p, format))
+            .collect::<Vec<_>>()
+            .join("\n---\n\n"),
+    }
+}
+
+/// Build the files for a bundle whose members resolved to `prompts`
+///
+/// As a skill, a bundle is one combined `<bundle-id>/SKILL.md`; every other
+/// format exports its member prompts.
+pub fn bundle_files(bundle: &Bundle, prompts: &[Prompt], format: Format) -> Vec<ExportFile> {
+    match format {
+        Format::Skill => vec![ExportFile {
+            name: safe_name(&bundle.id),
+            ids: prompts.iter().map(|p| p.id.clone()).collect(),
+            content: bundle_skill_md(bundle, prompts),
+        }],
+        _ => files(prompts, &[], format),
+    }
+}
+
+fn prompt_document(prompt: &Prompt, format: Format) -> String {
+    match format {
+        Format::Markdown => prompt_markdown(prompt),
+        Format::Skill => skill_md(prompt),
+        Format::Json => pretty_json(prompt),
+        Format::Yaml => yaml::to_yaml(&serde_json::to_value(prompt).unwrap_or_default()),
+        Format::Jsonl | Format::Registry => unreachable!("combined formats have no per-prompt document"),
+    }
+}
+
+fn jsonl(prompts: &[Prompt]) -> String {
+    prompts
+        .iter()
+        .filter_map(|p| serde_json::to_string(p).ok())
+        .map(|line| line + "\n")
+        .collect()
+}
+
+fn pretty_json<T: Serialize + ?Sized>(value: &T) -> String {
+    // Prompts and payloads are plain data; serializing them cannot fail
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// Write `files` under `dir`, which must already exist, telling `progress`
+/// about each one as it is done
+pub fn write_files(
+    dir: &Path,
+    files: Vec<ExportFile>,
+    format: Format,
+    on_conflict: OnConflict,
+    mut progress: impl FnMut(Written<'_>),
+) -> Manifest {
+    let mut manifest = Manifest {
+        success: true,
+        format: format.name(),
+        output_dir: dir.display().to_string(),
+        exported: Vec::new(),
+        skipped: Vec::new(),
+        failed: Vec::new(),
+    };
+    let mut used: HashSet<String> = files.iter().map(|f| f.name.to_lowercase()).collect();
+
+    for file in files {
+        let mut path = dir.join(format.path(&file.name));
+        let mut renamed_from = None;
+
+        if path.exists() {
+            match on_conflict {
+                OnConflict::Overwrite => {}
+                OnConflict::Skip => {
+                    let skipped = SkippedFile {
+                        file: path.display().to_string(),
+                        ids: file.ids,
+                        reason: "exists",
+                    };
+                    progress(Written::Skipped(&skipped));
+                    manifest.skipped.push(skipped);
+                    continue;
+                }
+                OnConflict::Rename => {
+                    let name = free_name(dir, &file.name, format, &mut used);
+                    renamed_from = Some(path.display().to_string());
+                    path = dir.join(format.path(&name));
+                }
+            }
+        }
+
+        match write_atomic(&path, file.content.as_bytes()) {
+            Ok(()) => {
+                let entry = ManifestEntry {
+                    file: path.display().to_string(),
+                    ids: file.ids,
+                    bytes: file.content.len(),
+                    sha256: sha256_hex(&file.content),
+                    renamed_from,
+                };
+                progress(Written::Exported(&entry));
+                manifest.exported.push(entry);
+            }
+            Err(e) => {
+                let failed = FailedFile {
+                    file: path.display().to_string(),
+                    ids: file.ids,
+                    error: e.to_string(),
+                };
+                progress(Written::Failed(&failed));
+                manifest.success = false;
+                manifest.failed.push(failed);
+            }
+        }
+    }
+
+    manifest
+}
+
+/// Write `contents` to `path` via a temporary sibling and a rename
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let parent = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let tmp = parent.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let result = (|| {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Lowercase hex SHA-256 of `content`
+pub fn sha256_hex(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Public page for a prompt
+pub fn prompt_url(id: &str) -> String {
+    format!("{}/{}", PROMPT_URL_BASE, id)
+}
+
+/// Public page for a bundle
+pub fn bundle_url(id: &str) -> String {
+    format!("{}/{}", BUNDLE_URL_BASE, id)
+}
+
+/// A code fence that doesn't occur in `content`
+fn code_fence(content: &str) -> String {
+    let mut fence = "```".to_string();
+    while content.contains(&fence) {
+        fence.push('`');
+    }
+    fence
+}
+
+/// Reduce an id to characters that are safe in a file name
+///
+/// Registry ids are kebab-case already; anything else (path separators,
+/// spaces, leading dots) is replaced so an id can never escape the
+/// output directory.
+fn safe_name(id: &str) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '-' })
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() { FALLBACK_NAME.to_string() } else { name.to_string() }
+}
+
+/// `base`, or `base-N` for the first N not already in `used`
+///
+/// Names are compared case-insensitively so exports stay distinct on
+/// case-insensitive filesystems.
+fn unique_name(base: &str, used: &mut HashSet<String>) -> String {
+    let mut name = base.to_string();
+    let mut n = 1;
+    while !used.insert(name.to_lowercase()) {
+        n += 1;
+        name = format!("{}-{}", base, n);
+    }
+    name
+}
+
+/// First `base-N` that is neither used in this export nor on disk
+fn free_name(dir: &Path, base: &str, format: Format, used: &mut HashSet<String>) -> String {
+    let mut n = 1;
+    loop {
+        n += 1;
+        let name = format!("{}-{}", base, n);
+        if !dir.join(format.path(&name)).exists() && used.insert(name.to_lowercase()) {
+            return name;
+        }
+    }
+}
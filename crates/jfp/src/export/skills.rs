@@ -0,0 +1,132 @@
+//! SKILL.md for Claude Code skills
+//!
+//! Port of `generateSkillMd` from packages/core/src/export/skills.ts and
+//! `generateBundleSkillMd` from prompts/bundles.ts. The `x_jfp_generated`
+//! marker lets installers tell generated skills apart from ones the user
+//! wrote.
+
+use super::yaml::{escape_yaml_array_item, escape_yaml_value};
+use super::{bundle_url, prompt_url};
+use crate::types::{Bundle, Prompt};
+
+/// SKILL.md document (YAML frontmatter plus body) for a prompt
+pub fn skill_md(prompt: &Prompt) -> String {
+    let mut frontmatter = vec!["---".to_string(), format!("name: {}", escape_yaml_value(&prompt.id))];
+    let optional = [
+        ("description", &prompt.description),
+        ("version", &prompt.version),
+        ("author", &prompt.author),
+        ("category", &prompt.category),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            frontmatter.push(format!("{}: {}", key, escape_yaml_value(value)));
+        }
+    }
+    let tags: Vec<String> = prompt
+        .tags
+        .iter()
+        .map(|t| format!("\"{}\"", escape_yaml_array_item(t)))
+        .collect();
+    frontmatter.push(format!("tags: [{}]", tags.join(", ")));
+    frontmatter.push(format!("source: {}", prompt_url(&prompt.id)));
+    frontmatter.push("x_jfp_generated: true".to_string());
+    frontmatter.push("---".to_string());
+    frontmatter.push(String::new());
+
+    let mut content = vec![format!("# {}", prompt.title), String::new(), prompt.content.clone(), String::new()];
+
+    for (heading, items) in [
+        ("When to Use", &prompt.when_to_use),
+        ("Tips", &prompt.tips),
+        ("Examples", &prompt.examples),
+    ] {
+        if !items.is_empty() {
+            content.push(format!("## {}", heading));
+            content.push(String::new());
+            content.extend(items.iter().map(|item| format!("- {}", item)));
+            content.push(String::new());
+        }
+    }
+
+    // Attribution footer
+    content.push("---".to_string());
+    content.push(String::new());
+    content.push(format!("*From [JeffreysPrompts.com]({})*", prompt_url(&prompt.id)));
+    content.push(String::new());
+
+    frontmatter.join("\n") + &content.join("\n")
+}
+
+/// Combined SKILL.md for a bundle, embedding each member prompt in order
+///
+/// `prompts` are the bundle's resolved members; ids that could not be
+/// resolved are simply absent, as in bundles.ts.
+pub fn bundle_skill_md(bundle: &Bundle, prompts: &[Prompt]) -> String {
+    let ids: Vec<String> = prompts
+        .iter()
+        .map(|p| format!("\"{}\"", escape_yaml_array_item(&p.id)))
+        .collect();
+    let frontmatter = [
+        "---".to_string(),
+        format!("name: {}", escape_yaml_value(&bundle.id)),
+        format!("description: {}", escape_yaml_value(&bundle.description)),
+        format!("version: {}", escape_yaml_value(&bundle.version)),
+        format!("author: {}", escape_yaml_value(&bundle.author)),
+        "type: bundle".to_string(),
+        format!("prompts: [{}]", ids.join(", ")),
+        format!("source: {}", bundle_url(&bundle.id)),
+        "x_jfp_generated: true".to_string(),
+        "---".to_string(),
+        String::new(),
+    ];
+
+    let mut content = vec![format!("# {}", bundle.title), String::new(), bundle.description.clone(), String::new()];
+
+    if let Some(workflow) = &bundle.workflow {
+        content.push("## Workflow".to_string());
+        content.push(String::new());
+        content.push(workflow.clone());
+        content.push(String::new());
+    }
+
+    if !bundle.when_to_use.is_empty() {
+        content.push("## When to Use This Bundle".to_string());
+        content.push(String::new());
+        content.extend(bundle.when_to_use.iter().map(|item| format!("- {}", item)));
+        content.push(String::new());
+    }
+
+    content.push("---".to_string());
+    content.push(String::new());
+    content.push("## Included Prompts".to_string());
+    content.push(String::new());
+
+    for prompt in prompts {
+        content.push(format!("### {}", prompt.title));
+        content.push(String::new());
+        if let Some(description) = &prompt.description {
+            content.push(format!("*{}*", description));
+            content.push(String::new());
+        }
+        content.push(prompt.content.clone());
+        content.push(String::new());
+
+        for (heading, items) in [("When to use", &prompt.when_to_use), ("Tips", &prompt.tips)] {
+            if !items.is_empty() {
+                content.push(format!("**{}:**", heading));
+                content.extend(items.iter().map(|item| format!("- {}", item)));
+                content.push(String::new());
+            }
+        }
+
+        content.push("---".to_string());
+        content.push(String::new());
+    }
+
+    // Attribution footer
+    content.push(format!("*Bundle from [JeffreysPrompts.com]({})*", bundle_url(&bundle.id)));
+    content.push(String::new());
+
+    frontmatter.join("\n") + &content.join("\n")
+}
@@ -0,0 +1,209 @@
+//! YAML output
+//!
+//! Scalar escaping is a port of packages/core/src/export/yaml.ts. On top of
+//! it, [`to_yaml`] writes any JSON value as a block-style YAML document, which
+//! is how prompts are exported in the `yaml` format.
+
+use serde_json::{Map, Value};
+
+/// YAML 1.1 boolean and null literals that must be quoted to stay strings
+const RESERVED_WORDS: &[&str] = &[
+    "true", "false", "yes", "no", "on", "off",
+    "True", "False", "Yes", "No", "On", "Off",
+    "TRUE", "FALSE", "YES", "NO", "ON", "OFF",
+    "null", "Null", "NULL", "~",
+];
+
+/// Characters that start YAML syntax when they lead a plain scalar
+const RESERVED_LEADERS: &[char] = &['@', '!', '&', '*', '-', '?', '%', '`'];
+
+/// Characters that are never safe inside a plain scalar
+const SPECIAL_CHARS: &[char] = &[
+    ':', '#', '\n', '\r', '\t', '"', '\'', '[', ']', '{', '}', '>', '|', '\\', ',',
+];
+
+/// Escape a string for use inside a double-quoted YAML scalar
+pub fn escape_yaml_array_item(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render a string as a YAML scalar, quoting it only when needed
+pub fn escape_yaml_value(value: &str) -> String {
+    if value.is_empty() {
+        return "\"\"".to_string();
+    }
+
+    let needs_quotes = value.contains(SPECIAL_CHARS)
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.starts_with(RESERVED_LEADERS)
+        || value.contains(char::is_control)
+        || RESERVED_WORDS.contains(&value)
+        || looks_like_number(value)
+        || looks_like_date(value);
+
+    if needs_quotes {
+        format!("\"{}\"", escape_yaml_array_item(value))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Whether a plain scalar would be read back as a number
+///
+/// Matches the patterns in yaml.ts: decimal ints and floats with optional
+/// sign and exponent, `.5`-style floats, hex (`0x`) and octal (`0o`).
+fn looks_like_number(value: &str) -> bool {
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let is_digits = |s: &str, radix: u32| !s.is_empty() && s.chars().all(|c| c.is_digit(radix));
+
+    if let Some(hex) = unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X")) {
+        return is_digits(hex, 16);
+    }
+    if let Some(octal) = unsigned.strip_prefix("0o").or_else(|| unsigned.strip_prefix("0O")) {
+        return is_digits(octal, 8);
+    }
+    if let Some(fraction) = unsigned.strip_prefix('.') {
+        return is_digits(fraction, 10);
+    }
+
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((m, e)) => (m, Some(e)),
+        None => (unsigned, None),
+    };
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    is_digits(int, 10)
+        && (frac.is_empty() || is_digits(frac, 10))
+        && exponent.is_none_or(|e| is_digits(e.strip_prefix(['-', '+']).unwrap_or(e), 10))
+}
+
+/// Whether a plain scalar starts like a YAML 1.1 timestamp (`2025-01-09`)
+///
+/// yaml.ts leaves these bare, but parsers then read prompt dates back as
+/// date objects instead of the strings the registry stores.
+fn looks_like_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() >= 10
+        && bytes[..10]
+            .iter()
+            .enumerate()
+            .all(|(i, b)| if i == 4 || i == 7 { *b == b'-' } else { b.is_ascii_digit() })
+}
+
+/// Write a JSON value as a block-style YAML document
+pub fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_mapping(&mut out, map, 0),
+        Value::Array(items) if !items.is_empty() => write_sequence(&mut out, items, 0),
+        scalar => {
+            out.push_str(&scalar_text(scalar, 0));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn write_mapping(out: &mut String, map: &Map<String, Value>, indent: usize) {
+    for (key, value) in map {
+        out.push_str(&" ".repeat(indent));
+        out.push_str(&escape_yaml_value(key));
+        out.push(':');
+        write_nested(out, value, indent + 2);
+    }
+}
+
+fn write_sequence(out: &mut String, items: &[Value], indent: usize) {
+    for item in items {
+        match item {
+            // The first key shares the line with the dash
+            Value::Object(map) if !map.is_empty() => {
+                let mut body = String::new();
+                write_mapping(&mut body, map, indent + 2);
+                out.push_str(&" ".repeat(indent));
+                out.push_str("- ");
+                out.push_str(&body[indent + 2..]);
+            }
+            _ => {
+                out.push_str(&" ".repeat(indent));
+                out.push('-');
+                write_nested(out, item, indent + 2);
+            }
+        }
+    }
+}
+
+/// Write the value that follows a `key:` or `-`, nesting collections
+fn write_nested(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            out.push('\n');
+            write_mapping(out, map, indent);
+        }
+        Value::Array(items) if !items.is_empty() => {
+            out.push('\n');
+            write_sequence(out, items, indent);
+        }
+        scalar => {
+            out.push(' ');
+            out.push_str(&scalar_text(scalar, indent));
+            out.push('\n');
+        }
+    }
+}
+
+fn scalar_text(value: &Value, indent: usize) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if is_block_candidate(s) => block_literal(s, indent),
+        Value::String(s) => escape_yaml_value(s),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+/// Multi-line text that a literal block scalar reproduces exactly
+///
+/// Leading indentation on the first line would be taken as the block's own
+/// indentation, and only newlines and tabs survive outside quotes.
+fn is_block_candidate(s: &str) -> bool {
+    s.contains('\n')
+        && !s.trim_start_matches('\n').starts_with(char::is_whitespace)
+        && !s.chars().any(|c| c.is_control() && c != '\n' && c != '\t')
+}
+
+/// Literal block scalar (`|`) with the chomping indicator that keeps the
+/// string's trailing newlines intact
+fn block_literal(s: &str, indent: usize) -> String {
+    let trailing = s.len() - s.trim_end_matches('\n').len();
+    let (chomp, body) = match trailing {
+        0 => ("-", s),
+        1 => ("", &s[..s.len() - 1]),
+        _ => ("+", &s[..s.len() - 1]),
+    };
+
+    let pad = " ".repeat(indent);
+    let mut out = format!("|{}", chomp);
+    for line in body.split('\n') {
+        out.push('\n');
+        if !line.is_empty() {
+            out.push_str(&pad);
+            out.push_str(line);
+        }
+    }
+    out
+}
+
@@ -4,15 +4,27 @@
 //! original Bun/TypeScript implementation while adding robust SQLite storage
 //! and improved performance.
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
 use std::io::IsTerminal;
 use std::process::ExitCode;
 
+use cli::output::OutputFormat;
+
+mod api;
+mod auth;
 mod cli;
+mod clipboard;
 mod commands;
 mod config;
+mod error;
+mod export;
+mod mcp;
 mod registry;
+mod search;
 mod storage;
+mod sync;
+mod template;
 mod types;
 
 /// jfp - Agent-optimized CLI for JeffreysPrompts.com
@@ -24,14 +36,26 @@ mod types;
 #[command(version, about, long_about = None)]
 #[command(propagate_version = true)]
 struct Cli {
-    /// Disable colored output
-    #[arg(long, global = true, env = "NO_COLOR")]
+    /// Disable colored output (also set by NO_COLOR or JFP_NO_COLOR)
+    #[arg(long, global = true)]
     no_color: bool,
 
-    /// Output in JSON format (also enabled when stdout is not a TTY)
+    /// Output in JSON format (also enabled when stdout is not a TTY); same as `--format json`
     #[arg(long, short, global = true)]
     json: bool,
 
+    /// Output format: json, ndjson, table, plain (default: table on a TTY, json otherwise)
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    format: Option<OutputFormat>,
+
+    /// Add `schema_version` and `command` to JSON output (see `jfp schema`)
+    #[arg(long, global = true)]
+    envelope: bool,
+
+    /// Keep only these fields of JSON output: comma-separated dotted paths, e.g. `id,title` or `prompts.id`
+    #[arg(long, global = true, value_name = "PATHS")]
+    fields: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -52,6 +76,10 @@ enum Commands {
         /// Show only featured prompts
         #[arg(long)]
         featured: bool,
+
+        /// Show only prompts in this collection, in collection order
+        #[arg(long, value_name = "NAME")]
+        collection: Option<String>,
     },
 
     /// Search prompts by keyword
@@ -62,6 +90,10 @@ enum Commands {
         /// Maximum number of results
         #[arg(long, short, default_value = "10")]
         limit: usize,
+
+        /// Search only prompts in this collection
+        #[arg(long, value_name = "NAME")]
+        collection: Option<String>,
     },
 
     /// Show details for a specific prompt
@@ -79,9 +111,17 @@ enum Commands {
         /// Prompt ID
         id: String,
 
+        /// Set a template variable (repeatable; `--NAME=VALUE` also works)
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+
         /// Fill template variables interactively
         #[arg(long)]
         fill: bool,
+
+        /// Print the prompt to stdout when no clipboard is available
+        #[arg(long)]
+        print: bool,
     },
 
     /// Render prompt with variable substitution
@@ -89,13 +129,29 @@ enum Commands {
         /// Prompt ID
         id: String,
 
+        /// Set a template variable (repeatable; `--NAME=VALUE` also works)
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+
         /// Fill variables interactively
         #[arg(long)]
         fill: bool,
 
-        /// Context file path for variable substitution
+        /// File, directory or glob to append as context (repeatable)
+        #[arg(long, value_name = "PATH")]
+        context: Vec<String>,
+
+        /// Read context from stdin
         #[arg(long)]
-        context: Option<String>,
+        stdin: bool,
+
+        /// Context budget in bytes
+        #[arg(long, value_name = "BYTES", default_value_t = template::context::DEFAULT_MAX_CONTEXT)]
+        max_context: usize,
+
+        /// Context budget in estimated tokens (applied with --max-context)
+        #[arg(long, value_name = "TOKENS")]
+        max_context_tokens: Option<usize>,
     },
 
     /// Interactive prompt picker (fzf-style)
@@ -107,10 +163,14 @@ enum Commands {
         /// Prompt IDs to export (or 'all')
         ids: Vec<String>,
 
-        /// Output format (md, skill)
-        #[arg(long, short, default_value = "md")]
+        /// File format (md, skill, json, yaml, jsonl, registry)
+        #[arg(long, short, id = "file_format", value_name = "FORMAT", default_value = "md")]
         format: String,
 
+        /// Output format for the report; `--format` picks the file format here
+        #[arg(long, id = "format", value_enum, value_name = "FORMAT")]
+        output_format: Option<OutputFormat>,
+
         /// Output directory
         #[arg(long, short)]
         output_dir: Option<String>,
@@ -118,6 +178,14 @@ enum Commands {
         /// Write to stdout instead of files
         #[arg(long)]
         stdout: bool,
+
+        /// When a file already exists: overwrite, skip, rename
+        #[arg(long, default_value = "overwrite")]
+        on_conflict: String,
+
+        /// Export a bundle's prompts (one combined SKILL.md with --format skill)
+        #[arg(long, value_name = "ID", conflicts_with = "ids")]
+        bundle: Option<String>,
     },
 
     /// Suggest prompts for a task
@@ -149,6 +217,21 @@ enum Commands {
         id: String,
     },
 
+    /// List available workflows
+    Workflows,
+
+    /// Show or run a workflow
+    Workflow {
+        #[command(subcommand)]
+        action: WorkflowAction,
+    },
+
+    /// List, edit, export or sync your collections
+    Collections {
+        #[command(subcommand)]
+        action: Option<CollectionAction>,
+    },
+
     /// Get a random prompt
     Random {
         /// Filter by category
@@ -181,7 +264,61 @@ enum Commands {
     Status,
 
     /// Refresh local registry cache
-    Refresh,
+    Refresh {
+        /// Download the registry even if the cached copy is still current
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Sign in to JeffreysPrompts Premium
+    Login {
+        /// Use the device code flow (for SSH and headless machines)
+        #[arg(long)]
+        remote: bool,
+
+        /// Print the sign-in URL instead of opening a browser
+        #[arg(long, conflicts_with = "remote")]
+        no_browser: bool,
+
+        /// Seconds to wait for sign-in to complete
+        #[arg(long, default_value_t = 120)]
+        timeout: u64,
+    },
+
+    /// Sign out and remove stored credentials
+    Logout {
+        /// Also revoke the token on the server
+        #[arg(long)]
+        revoke: bool,
+    },
+
+    /// Show the signed-in user
+    Whoami,
+
+    /// Show, add or delete your notes on a prompt
+    Notes {
+        /// Prompt ID
+        id: String,
+
+        /// Add a note with this text
+        #[arg(long, value_name = "TEXT")]
+        add: Option<String>,
+
+        /// Delete the note with this ID
+        #[arg(long, value_name = "NOTE_ID", conflicts_with = "add")]
+        delete: Option<String>,
+    },
+
+    /// Sync your premium library for offline use
+    Sync {
+        /// Download the whole library instead of changes since the last sync
+        #[arg(long)]
+        force: bool,
+
+        /// Show sync status instead of syncing
+        #[arg(long, conflicts_with = "force")]
+        status: bool,
+    },
 
     /// Check for CLI updates
     #[command(name = "update-cli")]
@@ -202,9 +339,34 @@ enum Commands {
         shell: String,
     },
 
+    /// Print the JSON Schema of a command's --json output
+    Schema {
+        /// Command name, e.g. `list` or `workflow show` (omit to list them)
+        command: Vec<String>,
+    },
+
     /// Run environment diagnostics
     Doctor,
 
+    /// Run an MCP server on stdio for agents, or a local HTTP API
+    Serve {
+        /// Print the MCP client configuration snippet and exit
+        #[arg(long)]
+        config: bool,
+
+        /// Serve the JSON HTTP API on 127.0.0.1 instead of MCP
+        #[arg(long)]
+        http: bool,
+
+        /// Port for --http (0 picks a free one)
+        #[arg(long, default_value_t = 8765, requires = "http")]
+        port: u16,
+
+        /// File holding the bearer token for --http (created if missing)
+        #[arg(long, value_name = "PATH", requires = "http")]
+        token_file: Option<std::path::PathBuf>,
+    },
+
     /// Open prompt in browser
     Open {
         /// Prompt ID
@@ -215,14 +377,142 @@ enum Commands {
     About,
 }
 
-fn main() -> ExitCode {
-    let cli = Cli::parse();
+#[derive(Subcommand, Debug)]
+enum WorkflowAction {
+    /// Show workflow steps
+    Show {
+        /// Workflow ID
+        id: String,
+    },
+
+    /// Render each step's prompt in order, saving progress as it goes
+    Run {
+        /// Workflow ID
+        id: String,
 
-    // Handle no-color globally (will be used when color output is implemented)
-    let _no_color = cli.no_color || std::env::var("JFP_NO_COLOR").is_ok() || std::env::var("NO_COLOR").is_ok();
+        /// Set a variable shared by every step (repeatable; `--NAME=VALUE` also works)
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
 
-    // Determine if JSON output should be used
-    let use_json = cli.json || !std::io::stdout().is_terminal();
+        /// Fill missing variables interactively
+        #[arg(long)]
+        fill: bool,
+
+        /// Stop after rendering this many steps
+        #[arg(long, value_name = "N")]
+        steps: Option<usize>,
+
+        /// Continue the latest unfinished run, or the given run
+        #[arg(long, value_name = "RUN_ID")]
+        resume: Option<Option<i64>>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CollectionAction {
+    /// Create an empty collection
+    Create {
+        /// Collection name
+        name: String,
+
+        /// What the collection is for
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Add prompts to the end of a collection
+    Add {
+        /// Collection name
+        name: String,
+
+        /// Prompt IDs
+        #[arg(required = true)]
+        prompt_ids: Vec<String>,
+    },
+
+    /// Remove prompts from a collection
+    Remove {
+        /// Collection name
+        name: String,
+
+        /// Prompt IDs
+        #[arg(required = true)]
+        prompt_ids: Vec<String>,
+    },
+
+    /// Show a collection and its prompts
+    Show {
+        /// Collection name
+        name: String,
+    },
+
+    /// Export a collection's prompts as markdown or skills
+    Export {
+        /// Collection name
+        name: String,
+
+        /// File format (md, skill)
+        #[arg(long, short, id = "file_format", value_name = "FORMAT", default_value = "md")]
+        format: String,
+
+        /// Output format for the report; `--format` picks the file format here
+        #[arg(long, id = "format", value_enum, value_name = "FORMAT")]
+        output_format: Option<OutputFormat>,
+
+        /// Output directory
+        #[arg(long, short)]
+        output_dir: Option<String>,
+
+        /// Write to stdout instead of files
+        #[arg(long)]
+        stdout: bool,
+
+        /// When a file already exists: overwrite, skip, rename
+        #[arg(long, default_value = "overwrite")]
+        on_conflict: String,
+    },
+
+    /// Push collections to your premium account
+    Sync {
+        /// Collection name (default: all collections)
+        name: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let matches = Cli::command().get_matches_from(cli::args::expand_variable_flags(std::env::args_os()));
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let command_name = command_path(&matches);
+    if cli.envelope {
+        cli::output::enable_envelope(command_name.clone());
+    }
+
+    // `--format` wins, then `--json`; otherwise JSON unless stdout is a TTY
+    let format = match cli.format {
+        Some(format) => format,
+        None if cli.json || !std::io::stdout().is_terminal() => OutputFormat::Json,
+        None => OutputFormat::Table,
+    };
+    cli::output::set_format(format);
+    let use_json = format.is_json();
+
+    cli::output::set_color(cli::output::color_enabled(
+        format,
+        cli.no_color,
+        std::env::var_os("NO_COLOR").as_deref(),
+        std::env::var_os("JFP_NO_COLOR").as_deref(),
+        std::io::stdout().is_terminal(),
+    ));
+
+    // Checked before the command runs, so a typo has no side effects
+    if let Some(spec) = &cli.fields {
+        let schema = commands::schema::published(&command_name);
+        let records = format == OutputFormat::Ndjson;
+        match cli::fields::Fields::parse(spec, schema.as_ref().map(|s| s.as_value()), cli.envelope, records) {
+            Ok(fields) => cli::output::select_fields(fields),
+            Err(e) => return cli::output::fail(e, use_json),
+        }
+    }
 
     // If no command, show help
     let Some(command) = cli.command else {
@@ -237,11 +527,11 @@ fn main() -> ExitCode {
 
     // Dispatch to command handlers
     match command {
-        Commands::List { category, tag, featured } => {
-            commands::list::run(category, tag, featured, use_json)
+        Commands::List { category, tag, featured, collection } => {
+            commands::list::run(category, tag, featured, collection, use_json)
         }
-        Commands::Search { query, limit } => {
-            commands::search::run(&query, limit, use_json)
+        Commands::Search { query, limit, collection } => {
+            commands::search::run(&query, limit, collection.as_deref(), use_json)
         }
         Commands::Show { id, raw } => {
             commands::show::run(&id, raw, use_json)
@@ -261,11 +551,18 @@ fn main() -> ExitCode {
         Commands::Open { id } => {
             commands::open::run(&id, use_json)
         }
+        Commands::Serve { config, http, port, token_file } => {
+            let http = http.then_some(commands::serve::HttpArgs { port, token_file });
+            commands::serve::run(config, http, use_json)
+        }
         Commands::Doctor => {
             commands::doctor::run(use_json)
         }
         Commands::Completion { shell } => {
-            commands::completion::run(&shell, Cli::command())
+            commands::completion::run(&shell, Cli::command(), use_json)
+        }
+        Commands::Schema { command } => {
+            commands::schema::run(&command, use_json)
         }
         Commands::Config { action, key, value } => {
             commands::config::run(&action, key, value, use_json)
@@ -273,17 +570,38 @@ fn main() -> ExitCode {
         Commands::Status => {
             commands::status::run(use_json)
         }
-        Commands::Copy { id, fill } => {
-            commands::copy::run(&id, fill, use_json)
+        Commands::Copy { id, vars, fill, print } => {
+            commands::copy::run(&id, vars, fill, print, use_json)
+        }
+        Commands::Export { ids, format, output_dir, stdout, on_conflict, bundle, .. } => {
+            commands::export::run(ids, &format, output_dir, stdout, &on_conflict, bundle, use_json)
+        }
+        Commands::Refresh { force } => {
+            commands::refresh::run(force, use_json)
         }
-        Commands::Export { ids, format, output_dir, stdout } => {
-            commands::export::run(ids, &format, output_dir, stdout, use_json)
+        Commands::Login { remote, no_browser, timeout } => {
+            commands::login::run(commands::login::LoginOptions { remote, no_browser, timeout }, use_json)
         }
-        Commands::Refresh => {
-            commands::refresh::run(use_json)
+        Commands::Logout { revoke } => {
+            commands::auth::logout(revoke, use_json)
         }
-        Commands::Render { id, fill, context } => {
-            commands::render::run(&id, fill, context, use_json)
+        Commands::Whoami => {
+            commands::auth::whoami(use_json)
+        }
+        Commands::Notes { id, add, delete } => {
+            commands::notes::run(&id, add, delete, use_json)
+        }
+        Commands::Sync { force, status } => {
+            commands::sync::run(force, status, use_json)
+        }
+        Commands::Render { id, vars, fill, context, stdin, max_context, max_context_tokens } => {
+            let context = commands::render::ContextArgs {
+                paths: context,
+                stdin,
+                max_bytes: max_context,
+                max_tokens: max_context_tokens,
+            };
+            commands::render::run(&id, vars, fill, context, use_json)
         }
         Commands::Suggest { task, limit, semantic } => {
             commands::suggest::run(&task, limit, semantic, use_json)
@@ -294,6 +612,39 @@ fn main() -> ExitCode {
         Commands::Bundle { id } => {
             commands::bundles::show_bundle(&id, use_json)
         }
+        Commands::Collections { action: None } => {
+            commands::collections::list(use_json)
+        }
+        Commands::Collections { action: Some(action) } => match action {
+            CollectionAction::Create { name, description } => {
+                commands::collections::create(&name, description, use_json)
+            }
+            CollectionAction::Add { name, prompt_ids } => {
+                commands::collections::add(&name, prompt_ids, use_json)
+            }
+            CollectionAction::Remove { name, prompt_ids } => {
+                commands::collections::remove(&name, prompt_ids, use_json)
+            }
+            CollectionAction::Show { name } => {
+                commands::collections::show(&name, use_json)
+            }
+            CollectionAction::Export { name, format, output_dir, stdout, on_conflict, .. } => {
+                commands::collections::export(&name, &format, output_dir, stdout, &on_conflict, use_json)
+            }
+            CollectionAction::Sync { name } => {
+                commands::collections::sync(name.as_deref(), use_json)
+            }
+        },
+        Commands::Workflows => {
+            commands::workflows::list_workflows(use_json)
+        }
+        Commands::Workflow { action: WorkflowAction::Show { id } } => {
+            commands::workflows::show_workflow(&id, use_json)
+        }
+        Commands::Workflow { action: WorkflowAction::Run { id, vars, fill, steps, resume } } => {
+            let options = commands::workflows::RunOptions { vars, fill, steps, resume };
+            commands::workflows::run_workflow(&id, options, use_json)
+        }
         Commands::Interactive => {
             commands::interactive::run(use_json)
         }
@@ -302,3 +653,26 @@ fn main() -> ExitCode {
         }
     }
 }
+
+/// Flags that switch a command to a different payload, named like a
+/// subcommand: `sync --status` reports as `sync status`
+const PAYLOAD_FLAGS: &[(&str, &str)] = &[("sync", "status"), ("serve", "config"), ("notes", "add"), ("notes", "delete")];
+
+/// Subcommand names as typed, without aliases: `list`, `workflow show`,
+/// plus any [`PAYLOAD_FLAGS`] given
+fn command_path(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
+    }
+    let mut path = names.join(" ");
+    let flag = PAYLOAD_FLAGS
+        .iter()
+        .find(|(command, flag)| *command == path && current.value_source(flag) == Some(ValueSource::CommandLine));
+    if let Some((_, flag)) = flag {
+        path = format!("{} {}", path, flag);
+    }
+    path
+}
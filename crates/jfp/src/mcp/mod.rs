@@ -0,0 +1,394 @@
+//! Model Context Protocol server
+//!
+//! Port of packages/cli/src/commands/serve.ts: the local library over MCP's
+//! stdio transport (newline-delimited JSON-RPC 2.0 on stdin/stdout).
+//!
+//! - Resources: `prompt://<id>` with the raw prompt content
+//! - Tools: `search_prompts` (query, category, tags, limit) and
+//!   `render_prompt` (id, variables, context)
+//! - Prompts: every library prompt, with its template variables as
+//!   arguments, so clients can offer them as slash-prompts
+//!
+//! Logging goes to stderr; stdout carries protocol messages only.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::{Value, json};
+
+use crate::storage::Database;
+use crate::template::{self, TemplateError, Variables};
+use crate::types::Prompt;
+
+/// Protocol revisions this server speaks, newest first
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const SERVER_NAME: &str = "jeffreysprompts";
+
+const RESOURCE_SCHEME: &str = "prompt://";
+
+/// Results `search_prompts` returns unless asked otherwise
+const DEFAULT_SEARCH_LIMIT: usize = 5;
+
+/// Candidates fetched before category/tag filtering
+const SEARCH_CANDIDATES: usize = 50;
+
+// JSON-RPC and MCP error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// A JSON-RPC error response
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<crate::storage::StorageError> for RpcError {
+    fn from(e: crate::storage::StorageError) -> Self {
+        Self::new(INTERNAL_ERROR, format!("Database error: {}", e))
+    }
+}
+
+type RpcResult = Result<Value, RpcError>;
+
+/// MCP request handler over the local library
+pub struct Server {
+    db: Database,
+}
+
+impl Server {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Answer requests from `input` on `output` until `input` closes
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&line) {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Response to one raw message; notifications get none
+    fn handle_message(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(m) => m,
+            Err(e) => return Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))),
+        };
+
+        let id = message.get("id").cloned();
+        let method = message.get("method").and_then(Value::as_str);
+        let (Some(method), true) = (method, message.get("jsonrpc") == Some(&json!("2.0"))) else {
+            // Responses to server requests are never sent, so anything else is malformed
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                RpcError::new(INVALID_REQUEST, "Expected a JSON-RPC 2.0 request"),
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let result = self.dispatch(method, &params);
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error_response(id, e),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: &Value) -> RpcResult {
+        match method {
+            "initialize" => Ok(initialize(params)),
+            "ping" => Ok(json!({})),
+            "resources/list" => self.list_resources(),
+            "resources/read" => self.read_resource(params),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => self.call_tool(params),
+            "prompts/list" => self.list_prompts(),
+            "prompts/get" => self.get_prompt(params),
+            method if method.starts_with("notifications/") => Ok(Value::Null),
+            method => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        }
+    }
+
+    fn all_prompts(&self) -> Result<Vec<Prompt>, RpcError> {
+        Ok(self.db.list_prompts_filtered(None, None, false, None)?)
+    }
+
+    fn list_resources(&self) -> RpcResult {
+        let resources: Vec<Value> = self
+            .all_prompts()?
+            .iter()
+            .map(|p| {
+                json!({
+                    "uri": format!("{}{}", RESOURCE_SCHEME, p.id),
+                    "name": p.id,
+                    "title": p.title,
+                    "description": p.description,
+                    "mimeType": "text/plain",
+                })
+            })
+            .collect();
+        Ok(json!({ "resources": resources }))
+    }
+
+    fn read_resource(&self, params: &Value) -> RpcResult {
+        let uri = string_param(params, "uri")?;
+        let Some(id) = uri.strip_prefix(RESOURCE_SCHEME) else {
+            return Err(RpcError::new(INVALID_PARAMS, format!("Unknown resource URI scheme: {}", uri)));
+        };
+        let Some(prompt) = self.db.get_prompt(id)? else {
+            return Err(RpcError::new(RESOURCE_NOT_FOUND, format!("Prompt not found: {}", id)));
+        };
+        Ok(json!({
+            "contents": [{ "uri": uri, "mimeType": "text/plain", "text": prompt.content }],
+        }))
+    }
+
+    fn call_tool(&self, params: &Value) -> RpcResult {
+        let name = string_param(params, "name")?;
+        let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+        // Tool failures are results the model can see, not protocol errors
+        let outcome = match name {
+            "search_prompts" => self.search_prompts(&args),
+            "render_prompt" => self.render_prompt(&args),
+            name => Err(format!("Unknown tool: {}", name)),
+        };
+        Ok(match outcome {
+            Ok(text) => json!({ "content": [{ "type": "text", "text": text }] }),
+            Err(message) => json!({
+                "content": [{ "type": "text", "text": format!("Error: {}", message) }],
+                "isError": true,
+            }),
+        })
+    }
+
+    fn search_prompts(&self, args: &Value) -> Result<String, String> {
+        let query = args.get("query").and_then(Value::as_str).unwrap_or_default().trim();
+        let category = args.get("category").and_then(Value::as_str);
+        let tags: Vec<&str> = args
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let limit = args
+            .get("limit")
+            .and_then(Value::as_f64)
+            .map_or(DEFAULT_SEARCH_LIMIT, |l| l.clamp(1.0, 100.0) as usize);
+
+        // Support category/tag filters without a query
+        let candidates: Vec<(Prompt, f64)> = if query.is_empty() {
+            self.db
+                .list_prompts_filtered(None, None, false, None)
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(|p| (p, 0.0))
+                .collect()
+        } else {
+            self.db
+                .search(query, SEARCH_CANDIDATES, None)
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(|r| (r.prompt, r.score))
+                .collect()
+        };
+
+        let results: Vec<Value> = candidates
+            .into_iter()
+            .filter(|(p, _)| category.is_none_or(|c| p.category.as_deref() == Some(c)))
+            .filter(|(p, _)| tags.is_empty() || tags.iter().any(|t| p.tags.iter().any(|pt| pt == t)))
+            .take(limit)
+            .map(|(p, score)| {
+                json!({
+                    "id": p.id,
+                    "title": p.title,
+                    "description": p.description,
+                    "category": p.category,
+                    "tags": p.tags,
+                    "score": (score * 100.0).round() / 100.0,
+                })
+            })
+            .collect();
+
+        let output = json!({ "total": results.len(), "results": results });
+        serde_json::to_string_pretty(&output).map_err(|e| e.to_string())
+    }
+
+    fn render_prompt(&self, args: &Value) -> Result<String, String> {
+        let id = args.get("id").and_then(Value::as_str).unwrap_or_default();
+        if id.is_empty() {
+            return Err("id is required".to_string());
+        }
+        let prompt = self
+            .db
+            .get_prompt(id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Prompt not found: {}", id))?;
+
+        let mut rendered = render(&prompt, string_map(args.get("variables"))).map_err(|e| e.to_string())?;
+        if let Some(context) = args.get("context").and_then(Value::as_str).filter(|c| !c.is_empty()) {
+            rendered.push_str("\n\n---\n\n**Context:**\n");
+            rendered.push_str(context);
+        }
+        Ok(rendered)
+    }
+
+    fn list_prompts(&self) -> RpcResult {
+        let prompts: Vec<Value> = self
+            .all_prompts()?
+            .iter()
+            .map(|p| {
+                let used = template::placeholders(&p.content);
+                let arguments: Vec<Value> = p
+                    .variables
+                    .iter()
+                    .filter(|v| used.contains(&v.name))
+                    .map(|v| {
+                        json!({
+                            "name": v.name,
+                            "description": v.description.as_deref().unwrap_or(&v.label),
+                            // Defaults make a declared-required variable optional here
+                            "required": v.required && v.default.as_deref().is_none_or(str::is_empty),
+                        })
+                    })
+                    .collect();
+                json!({
+                    "name": p.id,
+                    "title": p.title,
+                    "description": p.description,
+                    "arguments": arguments,
+                })
+            })
+            .collect();
+        Ok(json!({ "prompts": prompts }))
+    }
+
+    fn get_prompt(&self, params: &Value) -> RpcResult {
+        let name = string_param(params, "name")?;
+        let Some(prompt) = self.db.get_prompt(name)? else {
+            return Err(RpcError::new(INVALID_PARAMS, format!("Prompt not found: {}", name)));
+        };
+        let text = render(&prompt, string_map(params.get("arguments")))
+            .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+        Ok(json!({
+            "description": prompt.description.as_deref().unwrap_or(&prompt.title),
+            "messages": [{ "role": "user", "content": { "type": "text", "text": text } }],
+        }))
+    }
+}
+
+/// `initialize` result, agreeing on the client's revision when we speak it
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| Some(**v) == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "resources": {}, "tools": {}, "prompts": {} },
+        "serverInfo": { "name": SERVER_NAME, "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "search_prompts",
+            "description": "Search JeffreysPrompts library by query, category, or tags. Returns matching prompts with relevance scores.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search query (natural language description of what you need)",
+                    },
+                    "category": {
+                        "type": "string",
+                        "description": "Filter by category (ideation, documentation, automation, etc.)",
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Filter by tags",
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum results to return (default: 5)",
+                        "minimum": 1,
+                        "maximum": 100,
+                    },
+                },
+            },
+        },
+        {
+            "name": "render_prompt",
+            "description": "Render a prompt with variable substitution and optional context. Returns the fully rendered prompt text.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Prompt ID to render" },
+                    "variables": {
+                        "type": "object",
+                        "description": "Variable values to substitute (e.g., {PROJECT_NAME: 'my-app'})",
+                    },
+                    "context": {
+                        "type": "string",
+                        "description": "Additional context to append to the prompt",
+                    },
+                },
+                "required": ["id"],
+            },
+        },
+    ])
+}
+
+/// Render `prompt` the way `jfp render` does, minus the interactive form
+fn render(prompt: &Prompt, provided: Variables) -> Result<String, TemplateError> {
+    let dynamic = std::env::current_dir()
+        .map(|cwd| template::dynamic_defaults(&cwd))
+        .unwrap_or_default();
+    let variables = template::resolve(prompt, provided, dynamic)?;
+    Ok(template::render(&prompt.content, &variables))
+}
+
+/// Variable values from an optional JSON object
+fn string_map(value: Option<&Value>) -> Variables {
+    value
+        .and_then(Value::as_object)
+        .map(template::variables_from_json)
+        .unwrap_or_default()
+}
+
+fn string_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing string parameter: {}", name)))
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
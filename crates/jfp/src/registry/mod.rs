@@ -0,0 +1,55 @@
+//! Prompt registry
+//!
+//! Bundled prompts, bundles and workflows are JSON snapshots of
+//! `packages/core/src/prompts/registry.ts`, `bundles.ts` and `workflows.ts`
+//! embedded in the binary. The prompt snapshot is compressed at build time
+//! (see `build.rs`), and all three are stamped with one content hash;
+//! [`seed`] applies them to the database whenever that hash changes. [`remote`] fetches the live
+//! registry for `jfp refresh`.
+
+pub mod remote;
+
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+use crate::storage::{self, Database};
+use crate::types::{Bundle, Prompt, Workflow};
+
+const BUNDLED_REGISTRY_GZ: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/registry.json.gz"));
+const BUNDLED_REGISTRY_SHA256: &str = include_str!(concat!(env!("OUT_DIR"), "/registry.sha256"));
+const BUNDLED_BUNDLES: &str = include_str!("../../data/bundles.json");
+const BUNDLED_WORKFLOWS: &str = include_str!("../../data/workflows.json");
+
+/// Prompts shipped inside the binary
+pub fn bundled_prompts() -> Vec<Prompt> {
+    // The snapshot is compiled into the binary, so a failure here is a
+    // packaging bug rather than a user error.
+    let mut json = String::new();
+    GzDecoder::new(BUNDLED_REGISTRY_GZ)
+        .read_to_string(&mut json)
+        .expect("bundled registry snapshot decompresses");
+    serde_json::from_str(&json).expect("bundled registry snapshot is valid JSON")
+}
+
+/// Bring the library up to date with the bundled snapshots
+///
+/// Cheap when these snapshots were already applied; otherwise new prompts
+/// are added and changed ones upgraded, and bundles and workflows replaced
+/// (see [`Database::seed_bundled`]). Returns the number of prompts written.
+pub fn seed(db: &Database) -> storage::Result<usize> {
+    if db.bundled_snapshot()?.as_deref() == Some(BUNDLED_REGISTRY_SHA256) {
+        return Ok(0);
+    }
+    db.seed_bundled(BUNDLED_REGISTRY_SHA256, &bundled_prompts(), &bundled_bundles(), &bundled_workflows())
+}
+
+/// Bundles shipped inside the binary
+pub fn bundled_bundles() -> Vec<Bundle> {
+    serde_json::from_str(BUNDLED_BUNDLES).expect("bundled bundles snapshot is valid JSON")
+}
+
+/// Workflows shipped inside the binary
+pub fn bundled_workflows() -> Vec<Workflow> {
+    serde_json::from_str(BUNDLED_WORKFLOWS).expect("bundled workflows snapshot is valid JSON")
+}
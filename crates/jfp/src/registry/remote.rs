@@ -0,0 +1,179 @@
+//! Remote registry fetcher
+//!
+//! `jfp refresh` downloads the registry from [`config::registry_url`]
+//! (`JFP_REGISTRY_URL`), sending the validators of the last download as
+//! `If-None-Match` / `If-Modified-Since` so an unchanged registry costs a
+//! single 304. A downloaded payload - either a bare prompt array or an
+//! object with a `prompts` array - is validated in full before anything is
+//! written.
+//!
+//! [`config::registry_url`]: crate::config::registry_url
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::header::{ACCEPT, ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT};
+use thiserror::Error;
+
+use crate::types::Prompt;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Validators from the previous download of the same URL
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Validators<'a> {
+    pub etag: Option<&'a str>,
+    pub last_modified: Option<&'a str>,
+}
+
+/// Result of a conditional registry request
+#[derive(Debug)]
+pub enum Fetched {
+    /// The server confirmed the cached copy (HTTP 304)
+    NotModified,
+
+    /// A new, validated registry
+    Updated {
+        prompts: Vec<Prompt>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+/// Errors raised while fetching the remote registry
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("failed to start the HTTP client: {0}")]
+    Runtime(#[from] std::io::Error),
+
+    #[error("request to {url} failed: {source}")]
+    Request { url: String, source: reqwest::Error },
+
+    #[error("{url} responded with HTTP {status}")]
+    Status { url: String, status: u16 },
+
+    #[error("invalid registry from {url}: {reason}")]
+    Invalid { url: String, reason: String },
+}
+
+/// Fetch the registry at `url`, revalidating with `validators`
+pub fn fetch(url: &str, validators: Validators<'_>) -> Result<Fetched, FetchError> {
+    // Everything else in jfp is synchronous, so the runtime lives only as
+    // long as this request
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    runtime.block_on(fetch_async(url, validators))
+}
+
+async fn fetch_async(url: &str, validators: Validators<'_>) -> Result<Fetched, FetchError> {
+    let request_error = |source| FetchError::Request {
+        url: url.to_string(),
+        source,
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(request_error)?;
+    let mut request = client
+        .get(url)
+        .header(ACCEPT, "application/json")
+        .header(USER_AGENT, concat!("jfp/", env!("CARGO_PKG_VERSION")));
+    if let Some(etag) = validators.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = validators.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = request.send().await.map_err(request_error)?;
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
+    if !status.is_success() {
+        return Err(FetchError::Status {
+            url: url.to_string(),
+            status: status.as_u16(),
+        });
+    }
+
+    let etag = header(response.headers(), ETAG);
+    let last_modified = header(response.headers(), LAST_MODIFIED);
+    let body = response.bytes().await.map_err(request_error)?;
+    let prompts = validate(&body).map_err(|reason| FetchError::Invalid {
+        url: url.to_string(),
+        reason,
+    })?;
+    Ok(Fetched::Updated {
+        prompts,
+        etag,
+        last_modified,
+    })
+}
+
+fn header(headers: &HeaderMap, name: reqwest::header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Parse a registry payload and check every prompt against the schema
+///
+/// The whole payload is rejected on the first problem: a partial registry
+/// would silently drop prompts on the next swap.
+fn validate(body: &[u8]) -> Result<Vec<Prompt>, String> {
+    let payload: serde_json::Value = serde_json::from_slice(body).map_err(|e| format!("not JSON: {}", e))?;
+    let entries = match &payload {
+        serde_json::Value::Array(entries) => entries,
+        serde_json::Value::Object(object) => match object.get("prompts") {
+            Some(serde_json::Value::Array(entries)) => entries,
+            _ => return Err("expected an object with a `prompts` array".to_string()),
+        },
+        _ => return Err("expected an array of prompts".to_string()),
+    };
+    if entries.is_empty() {
+        return Err("the registry has no prompts".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    let mut prompts = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        let label = match entry.get("id").and_then(|id| id.as_str()) {
+            Some(id) => format!("prompt #{} ({})", i, id),
+            None => format!("prompt #{}", i),
+        };
+        let prompt: Prompt = serde_json::from_value(entry.clone()).map_err(|e| format!("{}: {}", label, e))?;
+        if let Some(problem) = schema_problem(&prompt) {
+            return Err(format!("{}: {}", label, problem));
+        }
+        if !seen.insert(prompt.id.clone()) {
+            return Err(format!("{}: duplicate id", label));
+        }
+        prompts.push(prompt);
+    }
+    Ok(prompts)
+}
+
+/// Constraints serde cannot express (PromptSchema in packages/core)
+fn schema_problem(prompt: &Prompt) -> Option<&'static str> {
+    let kebab_case = !prompt.id.is_empty()
+        && !prompt.id.starts_with('-')
+        && !prompt.id.ends_with('-')
+        && prompt
+            .id
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    if !kebab_case {
+        Some("id must be kebab-case")
+    } else if prompt.title.trim().is_empty() {
+        Some("title is empty")
+    } else if prompt.content.trim().is_empty() {
+        Some("content is empty")
+    } else if prompt.variables.iter().any(|v| v.name.trim().is_empty()) {
+        Some("a variable has no name")
+    } else {
+        None
+    }
+}
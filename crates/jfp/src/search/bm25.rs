@@ -0,0 +1,97 @@
+//! BM25 ranking (Okapi BM25)
+//!
+//! From packages/core/src/search/bm25.ts. Each prompt is one document: its
+//! fields repeated by weight (ID 5x, title 3x, description and tags 2x,
+//! content once), joined and tokenized. Scores are computed exactly as the
+//! TS engine does, so results come back in the same order.
+
+use std::collections::HashMap;
+
+use super::tokenize;
+use crate::types::Prompt;
+
+/// Term frequency saturation
+const K1: f64 = 1.2;
+/// Length normalization
+const B: f64 = 0.75;
+
+/// How many times each field goes into a prompt's document
+const ID_REPEATS: usize = 5;
+const TITLE_REPEATS: usize = 3;
+const DESCRIPTION_REPEATS: usize = 2;
+const TAGS_REPEATS: usize = 2;
+/// The user's notes count as much as the description (the TS engine has none)
+const NOTES_REPEATS: usize = 2;
+
+/// A prompt's tokens, counted
+struct Document {
+    length: usize,
+    term_freq: HashMap<String, usize>,
+}
+
+/// Corpus statistics plus per-document term frequencies
+pub struct Bm25Index {
+    documents: Vec<Document>,
+    avg_doc_length: f64,
+    term_doc_freq: HashMap<String, usize>,
+}
+
+impl Bm25Index {
+    /// Index prompts with their notes text, in library order
+    pub fn build<'a>(prompts: impl IntoIterator<Item = (&'a Prompt, &'a str)>) -> Self {
+        let mut documents = Vec::new();
+        let mut term_doc_freq: HashMap<String, usize> = HashMap::new();
+        let mut total_length = 0;
+
+        for (prompt, notes) in prompts {
+            let description = prompt.description.as_deref().unwrap_or("");
+            let mut parts = Vec::new();
+            parts.extend([prompt.id.as_str(); ID_REPEATS]);
+            parts.extend([prompt.title.as_str(); TITLE_REPEATS]);
+            parts.extend([description; DESCRIPTION_REPEATS]);
+            for _ in 0..TAGS_REPEATS {
+                parts.extend(prompt.tags.iter().map(String::as_str));
+            }
+            parts.push(&prompt.content);
+            parts.extend([notes; NOTES_REPEATS]);
+
+            let tokens = tokenize(&parts.join(" "));
+            let mut term_freq: HashMap<String, usize> = HashMap::new();
+            for token in &tokens {
+                *term_freq.entry(token.clone()).or_default() += 1;
+            }
+            for term in term_freq.keys() {
+                *term_doc_freq.entry(term.clone()).or_default() += 1;
+            }
+            total_length += tokens.len();
+            documents.push(Document { length: tokens.len(), term_freq });
+        }
+
+        let avg_doc_length = if documents.is_empty() { 1.0 } else { total_length as f64 / documents.len() as f64 };
+        Self {
+            avg_doc_length: avg_doc_length.max(1.0),
+            documents,
+            term_doc_freq,
+        }
+    }
+
+    /// Score of the `doc`th indexed prompt for the query terms; 0 when
+    /// none of them occur
+    pub fn score(&self, doc: usize, terms: &[String]) -> f64 {
+        let doc_count = self.documents.len() as f64;
+        let doc = &self.documents[doc];
+        let mut score = 0.0;
+        for term in terms {
+            let Some(&tf) = doc.term_freq.get(term) else {
+                continue;
+            };
+            let tf = tf as f64;
+            let df = self.term_doc_freq.get(term).copied().unwrap_or(0) as f64;
+            let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+            let numerator = tf * (K1 + 1.0);
+            let denominator = tf + K1 * (1.0 - B + B * (doc.length as f64 / self.avg_doc_length));
+            score += idf * (numerator / denominator);
+        }
+        score
+    }
+}
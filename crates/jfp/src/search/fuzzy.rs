@@ -0,0 +1,103 @@
+//! Fuzzy matching for the interactive picker
+//!
+//! fzf-style subsequence matching: every pattern character must appear in
+//! order (case-insensitively). Matches score higher when characters are
+//! consecutive, start a word, or appear early in the text.
+
+/// Score for each matched character
+const MATCH: i64 = 16;
+
+/// Bonus when a match directly follows the previous one
+const CONSECUTIVE_BONUS: i64 = 24;
+
+/// Bonus when a match starts a word (after a separator or at the start)
+const WORD_START_BONUS: i64 = 20;
+
+/// Penalty per skipped character between matches
+const GAP_PENALTY: i64 = 1;
+
+/// Score `text` against `pattern`, or `None` when it doesn't match
+///
+/// Whitespace in the pattern separates terms that must all match, in any
+/// order; the score is their sum. An empty pattern matches everything.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    pattern
+        .split_whitespace()
+        .map(|term| score_term(&term.to_lowercase().chars().collect::<Vec<_>>(), &text))
+        .sum()
+}
+
+/// Best greedy alignment of one term, trying every start position
+fn score_term(term: &[char], text: &[char]) -> Option<i64> {
+    let first = *term.first()?;
+    text.iter()
+        .enumerate()
+        .filter(|(_, c)| **c == first)
+        .filter_map(|(start, _)| align(term, text, start))
+        .max()
+}
+
+/// Greedily match `term` from `start`, scoring the alignment
+fn align(term: &[char], text: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut pos = start;
+    let mut prev: Option<usize> = None;
+
+    for (i, ch) in term.iter().enumerate() {
+        let found = if i == 0 {
+            start
+        } else {
+            pos + text[pos..].iter().position(|c| c == ch)?
+        };
+
+        score += MATCH;
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += WORD_START_BONUS;
+        }
+        match prev {
+            Some(p) if found == p + 1 => score += CONSECUTIVE_BONUS,
+            Some(p) => score -= (found - p - 1) as i64 * GAP_PENALTY,
+            None => score -= found as i64 / 4,
+        }
+
+        prev = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consecutive_early_word_start_matches_score_higher() {
+        let score = |pattern, text| fuzzy_score(pattern, text).unwrap();
+        // Consecutive beats scattered
+        assert!(score("wiz", "wizard") > score("wiz", "w-i-z"));
+        // A word start beats the middle of a word
+        assert!(score("bug", "bug hunter") > score("bug", "debugger"));
+        // Earlier beats later
+        assert!(score("idea", "idea wizard") > score("idea", "the wizard of many ideas"));
+        // Case does not matter
+        assert_eq!(score("IDEA", "idea"), score("idea", "IDEA"));
+    }
+
+    #[test]
+    fn characters_must_appear_in_order() {
+        assert!(fuzzy_score("rdw", "wizard").is_none());
+        assert!(fuzzy_score("wzd", "wizard").is_some());
+        assert!(fuzzy_score("wizards", "wizard").is_none());
+    }
+
+    #[test]
+    fn every_term_must_match_in_any_order() {
+        let text = "The Idea Wizard ideation brainstorm";
+        let both = fuzzy_score("wizard idea", text).unwrap();
+        assert_eq!(both, fuzzy_score("wizard", text).unwrap() + fuzzy_score("idea", text).unwrap());
+        assert_eq!(fuzzy_score("idea wizard", text), Some(both));
+        assert!(fuzzy_score("wizard banana", text).is_none());
+        assert_eq!(fuzzy_score("  ", text), Some(0));
+    }
+}
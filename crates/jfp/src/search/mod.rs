@@ -0,0 +1,54 @@
+//! Query preprocessing and ranking for search
+//!
+//! Port of packages/core/src/search (tokenize.ts, synonyms.ts, bm25.ts).
+//! `storage::Database::search` tokenizes and synonym-expands the query,
+//! finds candidates in the FTS5 index with an OR of quoted terms, and ranks
+//! them with [`Bm25Index`] so the order matches the TS engine. [`semantic`]
+//! reranks those results offline with hashed embeddings, and
+//! [`fuzzy_score`] drives the interactive picker's incremental matching.
+
+mod bm25;
+mod fuzzy;
+pub mod semantic;
+mod synonyms;
+mod tokenize;
+
+pub use bm25::Bm25Index;
+pub use fuzzy::fuzzy_score;
+pub use synonyms::expand_query;
+pub use tokenize::tokenize;
+
+/// Build an FTS5 MATCH expression from query terms
+///
+/// Every term is quoted, so user input can never produce an FTS5 syntax
+/// error. Returns `None` when nothing searchable is left after stopword
+/// removal (the TS engine returns no results in that case too).
+pub fn fts_query(terms: &[String]) -> Option<String> {
+    if terms.is_empty() {
+        return None;
+    }
+    let quoted: Vec<String> = terms
+        .iter()
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect();
+    Some(quoted.join(" OR "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Query expansion of the golden cases generated by the TS engine
+    /// (tests/fixtures/generate-search-golden.ts); the ranking itself is
+    /// checked end to end in tests/search.rs
+    #[test]
+    fn expansion_matches_typescript_engine() {
+        let golden: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/search_golden.json")).unwrap();
+        for case in golden["cases"].as_array().unwrap() {
+            let query = case["query"].as_str().unwrap();
+            let expected: Vec<&str> = case["expanded"].as_array().unwrap().iter().map(|t| t.as_str().unwrap()).collect();
+            assert_eq!(expand_query(&tokenize(query)), expected, "expansion of {query:?}");
+        }
+    }
+}
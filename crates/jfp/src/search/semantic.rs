@@ -0,0 +1,118 @@
+//! Offline semantic reranking with hashed embeddings
+//!
+//! From packages/core/src/search/hash-embedder.ts (`hashEmbed`) and
+//! semantic.ts (`semanticRerankHash`): deterministic locality-sensitive
+//! hashing of tokens and character trigrams into a fixed-size vector. No
+//! model download, network or GPU is involved, so results are reproducible.
+
+use super::tokenize;
+
+/// Identifier stored alongside cached vectors, bumped if the scheme changes
+pub const EMBEDDER: &str = "hash-v1";
+
+/// Embedding dimensions
+pub const DIMENSIONS: usize = 128;
+
+/// Share of the combined score taken from normalized BM25 (recall stage)
+pub const BM25_WEIGHT: f64 = 0.4;
+
+/// Share of the combined score taken from cosine similarity (precision stage)
+pub const SEMANTIC_WEIGHT: f64 = 0.6;
+
+/// How a candidate's final score was derived
+#[derive(Debug, Clone, Copy)]
+pub struct Blend {
+    /// Raw BM25 score from search
+    pub bm25: f64,
+    /// BM25 divided by the best BM25 score among candidates (at least 1)
+    pub bm25_normalized: f64,
+    /// Cosine similarity between task and prompt embeddings
+    pub semantic: f64,
+    /// `BM25_WEIGHT * bm25_normalized + SEMANTIC_WEIGHT * semantic`
+    pub combined: f64,
+}
+
+/// Embed text as a unit-length vector of [`DIMENSIONS`] floats
+///
+/// Hashes operate on UTF-16 code units so vectors match `hashEmbed` exactly.
+pub fn hash_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0f64; DIMENSIONS];
+
+    for token in tokenize(text) {
+        let units: Vec<u16> = token.encode_utf16().collect();
+
+        // Whole token, with extra weight
+        let token_hash = fnv1a(&units);
+        vector[token_hash as usize % DIMENSIONS] += 2.0 * sign_of(token_hash);
+
+        // Character trigrams for robustness against typos and morphology,
+        // each projected with three hash functions
+        for gram in units.windows(3) {
+            let hash = fnv1a(gram);
+            for k in 1..=3u32 {
+                let h = hash.wrapping_mul(k);
+                vector[h as usize % DIMENSIONS] += sign_of(h);
+            }
+        }
+    }
+
+    let magnitude = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if magnitude > 0.0 {
+        for v in &mut vector {
+            *v /= magnitude;
+        }
+    }
+    vector.into_iter().map(|v| v as f32).collect()
+}
+
+/// Cosine similarity of two unit vectors (0 on dimension mismatch)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).map(|(x, y)| f64::from(*x) * f64::from(*y)).sum()
+}
+
+/// Rerank BM25 candidates by blending in semantic similarity
+///
+/// Each candidate is `(item, bm25_score, embedding)`. Returns candidates
+/// sorted by combined score, best first.
+pub fn rerank<T>(query_embedding: &[f32], candidates: Vec<(T, f64, Vec<f32>)>) -> Vec<(T, Blend)> {
+    let max_bm25 = candidates
+        .iter()
+        .map(|(_, score, _)| *score)
+        .fold(1.0, f64::max);
+
+    let mut reranked: Vec<(T, Blend)> = candidates
+        .into_iter()
+        .map(|(item, bm25, embedding)| {
+            let bm25_normalized = bm25 / max_bm25;
+            let semantic = cosine_similarity(query_embedding, &embedding);
+            let blend = Blend {
+                bm25,
+                bm25_normalized,
+                semantic,
+                combined: bm25_normalized * BM25_WEIGHT + semantic * SEMANTIC_WEIGHT,
+            };
+            (item, blend)
+        })
+        .collect();
+
+    reranked.sort_by(|a, b| b.1.combined.total_cmp(&a.1.combined));
+    reranked
+}
+
+/// LSH projection sign taken from bit 16 of the hash
+fn sign_of(hash: u32) -> f64 {
+    if (hash >> 16) & 1 == 1 { 1.0 } else { -1.0 }
+}
+
+/// 32-bit FNV-1a over UTF-16 code units
+fn fnv1a(units: &[u16]) -> u32 {
+    let mut hash: u32 = 2_166_136_261;
+    for unit in units {
+        hash ^= u32::from(*unit);
+        hash = hash.wrapping_mul(16_777_619);
+    }
+    hash
+}
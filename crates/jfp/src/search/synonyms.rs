@@ -0,0 +1,63 @@
+//! Synonym expansion for improved search recall
+//!
+//! From packages/core/src/search/synonyms.ts. Expansion is bidirectional:
+//! a term pulls in its listed synonyms and every key that lists it.
+
+/// Map of terms to their synonyms for query expansion
+pub const SYNONYMS: &[(&str, &[&str])] = &[
+    // Common abbreviations and alternatives
+    ("fix", &["repair", "resolve", "debug", "patch", "correct"]),
+    ("docs", &["documentation", "readme", "doc", "guide"]),
+    ("perf", &["performance", "speed", "optimize", "fast"]),
+    ("cli", &["command-line", "terminal", "shell", "console"]),
+    ("api", &["interface", "endpoint", "service"]),
+    // Concept synonyms
+    ("brainstorm", &["ideate", "generate", "create", "think"]),
+    ("improve", &["enhance", "optimize", "upgrade", "better"]),
+    ("refactor", &["restructure", "clean", "reorganize", "rewrite"]),
+    ("test", &["testing", "spec", "unit", "integration"]),
+    ("debug", &["troubleshoot", "diagnose", "fix", "investigate"]),
+    // Action synonyms
+    ("add", &["create", "insert", "include", "implement"]),
+    ("remove", &["delete", "drop", "eliminate", "clear"]),
+    ("update", &["modify", "change", "edit", "revise"]),
+    // Domain terms
+    ("agent", &["bot", "assistant", "ai", "llm"]),
+    ("prompt", &["instruction", "query", "request"]),
+    ("code", &["programming", "software", "implementation"]),
+    // Technology specific
+    ("nodejs", &["node", "js"]),
+    ("reactjs", &["react"]),
+];
+
+/// Expand query tokens with synonyms, preserving first-seen order
+pub fn expand_query(tokens: &[String]) -> Vec<String> {
+    let mut expanded: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut push = |term: &str| {
+        if !expanded.iter().any(|t| t == term) {
+            expanded.push(term.to_string());
+        }
+    };
+
+    for token in tokens {
+        push(token);
+    }
+
+    for token in tokens {
+        // Direct synonyms
+        if let Some((_, synonyms)) = SYNONYMS.iter().find(|(key, _)| key == token) {
+            for synonym in *synonyms {
+                push(synonym);
+            }
+        }
+
+        // Reverse synonyms (keys that list this token)
+        for (key, synonyms) in SYNONYMS {
+            if synonyms.contains(&token.as_str()) {
+                push(key);
+            }
+        }
+    }
+
+    expanded
+}
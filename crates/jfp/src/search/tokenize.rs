@@ -0,0 +1,44 @@
+//! Tokenizer and stopwords
+//!
+//! From packages/core/src/search/tokenize.ts:
+//! - NFC normalize, lowercase, replace everything except letters, numbers,
+//!   whitespace, `+` and `#` with spaces, split on whitespace
+//! - Drop stopwords and single-character tokens (except language names)
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Common stopwords to exclude from search
+///
+/// Kept minimal because BM25's IDF handles common words, and many
+/// "stopwords" (it, do, for, while) are keywords in programming.
+pub const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "of", "is", "are", "was", "were", "be", "been",
+];
+
+/// Single-letter words to preserve (e.g. programming languages)
+const ALLOWLIST: &[&str] = &["c", "r", "v", "x", "k"];
+
+/// Tokenize text into lowercase words, removing stopwords and punctuation
+pub fn tokenize(text: &str) -> Vec<String> {
+    let cleaned: String = text
+        .nfc()
+        .collect::<String>()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c.is_whitespace() || c == '+' || c == '#' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+
+    cleaned
+        .split_whitespace()
+        .filter(|word| {
+            (word.chars().count() > 1 || ALLOWLIST.contains(word)) && !STOPWORDS.contains(word)
+        })
+        .map(str::to_string)
+        .collect()
+}
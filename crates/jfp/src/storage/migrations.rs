@@ -0,0 +1,366 @@
+//! Forward-only schema migrations
+//!
+//! Each migration runs exactly once, inside its own transaction, and is
+//! recorded in `schema_migrations`. Migrations are never edited or removed
+//! once released: schema changes are always a new entry appended to
+//! [`MIGRATIONS`], so upgrading jfp never drops a user's library.
+
+use chrono::Utc;
+use rusqlite::{Connection, params};
+
+use super::{Result, StorageError};
+
+/// A single schema step
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// All migrations, in strictly increasing version order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: r#"
+        CREATE TABLE prompts (
+            id          TEXT PRIMARY KEY,
+            title       TEXT NOT NULL,
+            description TEXT,
+            category    TEXT,
+            tags_text   TEXT NOT NULL DEFAULT '',
+            featured    INTEGER NOT NULL DEFAULT 0,
+            content     TEXT NOT NULL,
+            data        TEXT NOT NULL,
+            stored_at   TEXT NOT NULL
+        );
+
+        CREATE INDEX idx_prompts_category ON prompts(category);
+
+        CREATE TABLE prompt_tags (
+            prompt_id TEXT NOT NULL REFERENCES prompts(id) ON DELETE CASCADE,
+            tag       TEXT NOT NULL,
+            PRIMARY KEY (prompt_id, tag)
+        );
+
+        CREATE INDEX idx_prompt_tags_tag ON prompt_tags(tag);
+
+        CREATE VIRTUAL TABLE prompts_fts USING fts5(
+            id, title, description, tags_text, content,
+            content = 'prompts',
+            content_rowid = 'rowid'
+        );
+
+        CREATE TRIGGER prompts_fts_insert AFTER INSERT ON prompts BEGIN
+            INSERT INTO prompts_fts(rowid, id, title, description, tags_text, content)
+            VALUES (new.rowid, new.id, new.title, new.description, new.tags_text, new.content);
+        END;
+
+        CREATE TRIGGER prompts_fts_delete AFTER DELETE ON prompts BEGIN
+            INSERT INTO prompts_fts(prompts_fts, rowid, id, title, description, tags_text, content)
+            VALUES ('delete', old.rowid, old.id, old.title, old.description, old.tags_text, old.content);
+        END;
+
+        CREATE TRIGGER prompts_fts_update AFTER UPDATE ON prompts BEGIN
+            INSERT INTO prompts_fts(prompts_fts, rowid, id, title, description, tags_text, content)
+            VALUES ('delete', old.rowid, old.id, old.title, old.description, old.tags_text, old.content);
+            INSERT INTO prompts_fts(rowid, id, title, description, tags_text, content)
+            VALUES (new.rowid, new.id, new.title, new.description, new.tags_text, new.content);
+        END;
+
+        -- Version markers from SYNC_STRATEGY.md (single row)
+        CREATE TABLE sync_meta (
+            id              INTEGER PRIMARY KEY CHECK (id = 1),
+            schema_version  INTEGER NOT NULL,
+            last_synced_at  TEXT,
+            source_of_truth TEXT NOT NULL DEFAULT 'sqlite',
+            jsonl_sha256    TEXT,
+            record_count    INTEGER NOT NULL DEFAULT 0
+        );
+
+        INSERT INTO sync_meta (id, schema_version) VALUES (1, 0);
+    "#,
+    },
+    Migration {
+        version: 2,
+        name: "prompt_embeddings",
+        sql: r#"
+        -- Cached embedding vectors (little-endian f32) per prompt and embedder
+        CREATE TABLE prompt_embeddings (
+            prompt_id  TEXT NOT NULL REFERENCES prompts(id) ON DELETE CASCADE,
+            embedder   TEXT NOT NULL,
+            dimensions INTEGER NOT NULL,
+            vector     BLOB NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (prompt_id, embedder)
+        );
+    "#,
+    },
+    Migration {
+        version: 3,
+        name: "bundles",
+        sql: r#"
+        -- Member prompts live in `data` (promptIds) so bundles may name
+        -- prompts that are not in the local library yet
+        CREATE TABLE bundles (
+            id        TEXT PRIMARY KEY,
+            title     TEXT NOT NULL,
+            featured  INTEGER NOT NULL DEFAULT 0,
+            data      TEXT NOT NULL,
+            stored_at TEXT NOT NULL
+        );
+    "#,
+    },
+    Migration {
+        version: 4,
+        name: "workflows",
+        sql: r#"
+        CREATE TABLE workflows (
+            id        TEXT PRIMARY KEY,
+            title     TEXT NOT NULL,
+            data      TEXT NOT NULL,
+            stored_at TEXT NOT NULL
+        );
+
+        -- Runs outlive workflow definitions, so there is no foreign key;
+        -- `variables` is a JSON object shared by every step
+        CREATE TABLE workflow_runs (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            workflow_id  TEXT NOT NULL,
+            variables    TEXT NOT NULL DEFAULT '{}',
+            started_at   TEXT NOT NULL,
+            updated_at   TEXT NOT NULL,
+            completed_at TEXT
+        );
+
+        CREATE INDEX idx_workflow_runs_workflow ON workflow_runs(workflow_id, completed_at);
+
+        -- Steps are keyed by step id so progress survives reordering
+        CREATE TABLE workflow_run_steps (
+            run_id       INTEGER NOT NULL REFERENCES workflow_runs(id) ON DELETE CASCADE,
+            step_id      TEXT NOT NULL,
+            position     INTEGER NOT NULL,
+            prompt_id    TEXT NOT NULL,
+            rendered     TEXT NOT NULL,
+            completed_at TEXT NOT NULL,
+            PRIMARY KEY (run_id, step_id)
+        );
+    "#,
+    },
+    Migration {
+        version: 5,
+        name: "bundled_snapshot",
+        sql: r#"
+        -- Content hash of the embedded registry snapshot last applied
+        ALTER TABLE sync_meta ADD COLUMN bundled_sha256 TEXT;
+
+        -- Hash of each prompt as last written from the snapshot, so upgrades
+        -- only replace prompts that nothing else has changed since
+        CREATE TABLE seeded_prompts (
+            id     TEXT PRIMARY KEY,
+            sha256 TEXT NOT NULL
+        );
+    "#,
+    },
+    Migration {
+        version: 6,
+        name: "registry_sources",
+        sql: r#"
+        -- Where each prompt came from: bundled, remote or local. Everything
+        -- stored before this migration was seeded or added by hand.
+        ALTER TABLE prompts ADD COLUMN source TEXT NOT NULL DEFAULT 'local';
+        UPDATE prompts SET source = 'bundled' WHERE id IN (SELECT id FROM seeded_prompts);
+
+        CREATE INDEX idx_prompts_source ON prompts(source);
+
+        -- Last successful remote registry fetch (single row); the validators
+        -- make the next `jfp refresh` a conditional request
+        CREATE TABLE registry_cache (
+            id            INTEGER PRIMARY KEY CHECK (id = 1),
+            url           TEXT NOT NULL,
+            etag          TEXT,
+            last_modified TEXT,
+            fetched_at    TEXT NOT NULL,
+            checked_at    TEXT NOT NULL,
+            prompt_count  INTEGER NOT NULL
+        );
+    "#,
+    },
+    Migration {
+        version: 7,
+        name: "premium_library",
+        sql: r#"
+        -- Prompts synced from the user's premium library; `data` is the
+        -- SyncedPrompt JSON as received. Sync markers live in sync_meta.
+        CREATE TABLE library_prompts (
+            id        TEXT PRIMARY KEY,
+            title     TEXT NOT NULL,
+            data      TEXT NOT NULL,
+            synced_at TEXT NOT NULL
+        );
+    "#,
+    },
+    Migration {
+        version: 8,
+        name: "notes",
+        sql: r#"
+        -- Personal notes, kept even when their prompt goes away. `remote_id`
+        -- is the server's id once uploaded; `pending` is the change still to
+        -- upload: 'add', or 'delete' for a note that is gone locally.
+        CREATE TABLE notes (
+            id         TEXT PRIMARY KEY,
+            prompt_id  TEXT NOT NULL,
+            content    TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            remote_id  TEXT UNIQUE,
+            pending    TEXT CHECK (pending IN ('add', 'delete'))
+        );
+
+        CREATE INDEX idx_notes_prompt ON notes(prompt_id, created_at);
+
+        -- Notes are indexed with their prompt: a denormalized `notes_text`
+        -- column, and an FTS table rebuilt to include it
+        ALTER TABLE prompts ADD COLUMN notes_text TEXT NOT NULL DEFAULT '';
+
+        DROP TRIGGER prompts_fts_insert;
+        DROP TRIGGER prompts_fts_delete;
+        DROP TRIGGER prompts_fts_update;
+        DROP TABLE prompts_fts;
+
+        CREATE VIRTUAL TABLE prompts_fts USING fts5(
+            id, title, description, tags_text, content, notes_text,
+            content = 'prompts',
+            content_rowid = 'rowid'
+        );
+
+        CREATE TRIGGER prompts_fts_insert AFTER INSERT ON prompts BEGIN
+            INSERT INTO prompts_fts(rowid, id, title, description, tags_text, content, notes_text)
+            VALUES (new.rowid, new.id, new.title, new.description, new.tags_text, new.content, new.notes_text);
+        END;
+
+        CREATE TRIGGER prompts_fts_delete AFTER DELETE ON prompts BEGIN
+            INSERT INTO prompts_fts(prompts_fts, rowid, id, title, description, tags_text, content, notes_text)
+            VALUES ('delete', old.rowid, old.id, old.title, old.description, old.tags_text, old.content, old.notes_text);
+        END;
+
+        CREATE TRIGGER prompts_fts_update AFTER UPDATE ON prompts BEGIN
+            INSERT INTO prompts_fts(prompts_fts, rowid, id, title, description, tags_text, content, notes_text)
+            VALUES ('delete', old.rowid, old.id, old.title, old.description, old.tags_text, old.content, old.notes_text);
+            INSERT INTO prompts_fts(rowid, id, title, description, tags_text, content, notes_text)
+            VALUES (new.rowid, new.id, new.title, new.description, new.tags_text, new.content, new.notes_text);
+        END;
+
+        INSERT INTO prompts_fts(prompts_fts) VALUES ('rebuild');
+
+        CREATE TRIGGER notes_text_insert AFTER INSERT ON notes BEGIN
+            UPDATE prompts SET notes_text = (
+                SELECT COALESCE(group_concat(content, ' '), '') FROM notes
+                WHERE prompt_id = new.prompt_id AND pending IS NOT 'delete'
+            ) WHERE id = new.prompt_id;
+        END;
+
+        CREATE TRIGGER notes_text_update AFTER UPDATE ON notes BEGIN
+            UPDATE prompts SET notes_text = (
+                SELECT COALESCE(group_concat(content, ' '), '') FROM notes
+                WHERE prompt_id = new.prompt_id AND pending IS NOT 'delete'
+            ) WHERE id = new.prompt_id;
+        END;
+
+        CREATE TRIGGER notes_text_delete AFTER DELETE ON notes BEGIN
+            UPDATE prompts SET notes_text = (
+                SELECT COALESCE(group_concat(content, ' '), '') FROM notes
+                WHERE prompt_id = old.prompt_id AND pending IS NOT 'delete'
+            ) WHERE id = old.prompt_id;
+        END;
+    "#,
+    },
+    Migration {
+        version: 9,
+        name: "collections",
+        sql: r#"
+        -- Named, ordered lists of prompt ids. Members are not foreign keys:
+        -- a collection outlives a prompt dropped by a registry refresh.
+        -- `synced_at` is when the collection was last pushed to premium.
+        CREATE TABLE collections (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            name        TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            description TEXT,
+            created_at  TEXT NOT NULL,
+            updated_at  TEXT NOT NULL,
+            synced_at   TEXT
+        );
+
+        CREATE TABLE collection_prompts (
+            collection_id INTEGER NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+            prompt_id     TEXT NOT NULL,
+            position      INTEGER NOT NULL,
+            added_at      TEXT NOT NULL,
+            PRIMARY KEY (collection_id, prompt_id)
+        );
+
+        CREATE INDEX idx_collection_prompts_prompt ON collection_prompts(prompt_id);
+    "#,
+    },
+];
+
+/// Latest schema version known to this binary
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Apply all pending migrations
+///
+/// Refuses to touch a database written by a newer jfp rather than guessing
+/// at a downgrade.
+pub fn run(conn: &mut Connection) -> Result<()> {
+    debug_assert!(
+        MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version),
+        "migrations must be in strictly increasing version order"
+    );
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version    INTEGER PRIMARY KEY,
+            name       TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        );",
+    )?;
+
+    let current = current_version(conn)?;
+    let latest = latest_version();
+    if current > latest {
+        return Err(StorageError::SchemaTooNew {
+            found: current,
+            supported: latest,
+        });
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql)
+            .map_err(|source| StorageError::Migration {
+                version: migration.version,
+                name: migration.name,
+                source,
+            })?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            params![migration.version, migration.name, Utc::now().to_rfc3339()],
+        )?;
+        tx.execute(
+            "UPDATE sync_meta SET schema_version = ?1 WHERE id = 1",
+            params![migration.version],
+        )?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Highest applied migration version (0 for a fresh database)
+pub fn current_version(conn: &Connection) -> Result<u32> {
+    let version: Option<u32> =
+        conn.query_row("SELECT MAX(version) FROM schema_migrations", [], |row| row.get(0))?;
+    Ok(version.unwrap_or(0))
+}
@@ -0,0 +1,1200 @@
+//! SQLite storage
+//!
+//! SQLite is the source of truth for the local library (see SYNC_STRATEGY.md).
+//! The database lives at `<config_dir>/jfp.db` rather than under the cache
+//! directory because it holds user data that must survive cache cleanup.
+//!
+//! - WAL mode with a busy timeout (5s, `JFP_BUSY_TIMEOUT`) for concurrent
+//!   CLI invocations
+//! - FTS5 external-content table over prompts for search recall and
+//!   snippets; ranking is the TS engine's BM25 ([`search::Bm25Index`]),
+//!   built once per library version
+//! - Forward-only migrations (see [`migrations`])
+
+mod migrations;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use chrono::Utc;
+use rand::Rng;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::config;
+use crate::search::{self, Bm25Index};
+use crate::types::{
+    Bundle, Collection, CompletedStep, FieldPriority, HIGHLIGHT_START, LibrarySyncState, Note, NoteChange, Prompt,
+    RegistryCache, RemoteSwap, SearchResult, Snippet, SyncedPrompt, Workflow, WorkflowRun,
+};
+
+const DB_FILE_NAME: &str = "jfp.db";
+
+/// `prompts.source` values
+pub const SOURCE_BUNDLED: &str = "bundled";
+pub const SOURCE_REMOTE: &str = "remote";
+pub const SOURCE_LOCAL: &str = "local";
+
+/// FTS5 columns in table order, with the field name reported to users
+const FTS_COLUMNS: [&str; 6] = ["id", "title", "description", "tags", "content", "notes"];
+
+/// Field preference for the displayed snippet: body text first, since the
+/// title and tags are already part of every result summary
+const SNIPPET_PREFERENCE: [&str; 6] = ["description", "content", "notes", "title", "tags", "id"];
+
+/// Approximate snippet length in tokens
+const SNIPPET_TOKENS: u32 = 16;
+
+/// Errors raised by the storage layer
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("could not determine a configuration directory for the jfp database")]
+    NoDataDir,
+
+    #[error("failed to create {}: {source}", path.display())]
+    CreateDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("invalid stored prompt: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("migration {version} ({name}) failed: {source}")]
+    Migration {
+        version: u32,
+        name: &'static str,
+        source: rusqlite::Error,
+    },
+
+    #[error("database schema version {found} is newer than this jfp supports ({supported}); upgrade jfp")]
+    SchemaTooNew { found: u32, supported: u32 },
+}
+
+pub type Result<T> = std::result::Result<T, StorageError>;
+
+/// Handle to the local jfp database
+pub struct Database {
+    conn: Connection,
+    /// Search statistics, kept while the library is unchanged
+    corpus: RefCell<Option<Rc<Corpus>>>,
+}
+
+/// The whole library as BM25 sees it, in rowid order
+struct Corpus {
+    /// `PRAGMA data_version` (writes by other connections) and this
+    /// connection's total changes when it was read
+    version: (i64, u64),
+    rowids: Vec<i64>,
+    prompts: Vec<Prompt>,
+    index: Bm25Index,
+}
+
+impl Database {
+    /// Open (and migrate) the default database
+    pub fn open() -> Result<Self> {
+        Self::open_at(&Self::default_path()?)
+    }
+
+    /// Default database location
+    pub fn default_path() -> Result<PathBuf> {
+        config::config_dir()
+            .map(|dir| dir.join(DB_FILE_NAME))
+            .ok_or(StorageError::NoDataDir)
+    }
+
+    /// Open (and migrate) a database at an explicit path
+    pub fn open_at(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|source| StorageError::CreateDir {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+
+        let mut conn = Connection::open(path)?;
+        conn.busy_timeout(config::busy_timeout())?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        migrations::run(&mut conn)?;
+
+        Ok(Self {
+            conn,
+            corpus: RefCell::new(None),
+        })
+    }
+
+    /// Content hash of the bundled snapshot last applied, if any
+    pub fn bundled_snapshot(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT bundled_sha256 FROM sync_meta WHERE id = 1", [], |row| row.get(0))?)
+    }
+
+    /// Apply the bundled snapshots in one transaction
+    ///
+    /// Each prompt is inserted when the library has never had it, replaced
+    /// when it differs and the stored copy is still the one last seeded, and
+    /// otherwise left alone: a prompt changed since seeding (or deleted) is
+    /// the user's or the remote registry's, not ours. Prompts fetched from
+    /// the remote registry are never touched. Bundles and workflows
+    /// only ever come from the snapshot, so they are always replaced.
+    /// Returns the number of prompts written.
+    pub fn seed_bundled(
+        &self,
+        snapshot_sha256: &str,
+        prompts: &[Prompt],
+        bundles: &[Bundle],
+        workflows: &[Workflow],
+    ) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut written = 0;
+        for prompt in prompts {
+            let data = serde_json::to_string(prompt)?;
+            let hash = sha256_hex(&data);
+            let stored: Option<(String, String)> = tx
+                .query_row(
+                    "SELECT data, source FROM prompts WHERE id = ?1",
+                    params![prompt.id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            // The remote registry's copy stands until `jfp refresh` drops it
+            if stored.as_ref().is_some_and(|(_, source)| source == SOURCE_REMOTE) {
+                continue;
+            }
+            let seeded: Option<String> = tx
+                .query_row("SELECT sha256 FROM seeded_prompts WHERE id = ?1", params![prompt.id], |row| row.get(0))
+                .optional()?;
+
+            let stored_hash = stored.as_ref().map(|(data, _)| sha256_hex(data));
+            let write = match (&stored_hash, &seeded) {
+                (Some(current), _) if *current == hash => false,
+                // Deleted after seeding
+                (None, Some(_)) => false,
+                (None, None) => true,
+                // Seeded before snapshots were tracked (stored as local then)
+                (Some(_), None) => stored.as_ref().is_some_and(|(_, source)| source == SOURCE_LOCAL),
+                (Some(current), Some(seeded)) => current == seeded,
+            };
+            if write {
+                write_prompt(&tx, prompt, &data, SOURCE_BUNDLED)?;
+                written += 1;
+            }
+            if write || stored_hash.as_deref() == Some(hash.as_str()) {
+                tx.execute(
+                    "INSERT INTO seeded_prompts (id, sha256) VALUES (?1, ?2)
+                     ON CONFLICT(id) DO UPDATE SET sha256 = excluded.sha256",
+                    params![prompt.id, hash],
+                )?;
+            }
+        }
+        for bundle in bundles {
+            write_bundle(&tx, bundle)?;
+        }
+        for workflow in workflows {
+            write_workflow(&tx, workflow)?;
+        }
+        tx.execute(
+            "UPDATE sync_meta SET bundled_sha256 = ?1 WHERE id = 1",
+            params![snapshot_sha256],
+        )?;
+        tx.commit()?;
+        Ok(written)
+    }
+
+    /// Last remote registry fetch, if any
+    pub fn registry_cache(&self) -> Result<Option<RegistryCache>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT url, etag, last_modified, fetched_at, checked_at, prompt_count
+                 FROM registry_cache WHERE id = 1",
+                [],
+                |row| {
+                    Ok(RegistryCache {
+                        url: row.get(0)?,
+                        etag: row.get(1)?,
+                        last_modified: row.get(2)?,
+                        fetched_at: row.get(3)?,
+                        checked_at: row.get(4)?,
+                        prompt_count: row.get::<_, i64>(5)? as usize,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Record that the cached remote registry is still current (HTTP 304)
+    pub fn touch_registry_cache(&self) -> Result<()> {
+        self.conn.execute(
+            "UPDATE registry_cache SET checked_at = ?1 WHERE id = 1",
+            params![Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Swap in a validated remote registry in one transaction
+    ///
+    /// Remote prompts replace bundled and previously fetched copies, but never
+    /// a local prompt with the same id. Remote prompts missing from the new
+    /// registry are removed, and the fetch validators are recorded for the
+    /// next conditional request.
+    pub fn replace_remote_prompts(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+        prompts: &[Prompt],
+    ) -> Result<RemoteSwap> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut swap = RemoteSwap::default();
+        for prompt in prompts {
+            let data = serde_json::to_string(prompt)?;
+            let stored: Option<(String, String)> = tx
+                .query_row(
+                    "SELECT data, source FROM prompts WHERE id = ?1",
+                    params![prompt.id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            match stored {
+                None => {
+                    write_prompt(&tx, prompt, &data, SOURCE_REMOTE)?;
+                    swap.added += 1;
+                }
+                Some((_, source)) if source == SOURCE_LOCAL => swap.kept_local.push(prompt.id.clone()),
+                Some((current, _)) if current == data => {
+                    tx.execute(
+                        "UPDATE prompts SET source = ?2 WHERE id = ?1",
+                        params![prompt.id, SOURCE_REMOTE],
+                    )?;
+                }
+                Some(_) => {
+                    write_prompt(&tx, prompt, &data, SOURCE_REMOTE)?;
+                    swap.updated += 1;
+                }
+            }
+        }
+
+        let stale: Vec<String> = {
+            let mut stmt = tx.prepare("SELECT id FROM prompts WHERE source = ?1")?;
+            stmt.query_map(params![SOURCE_REMOTE], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+                .into_iter()
+                .filter(|id| !prompts.iter().any(|p| p.id == *id))
+                .collect()
+        };
+        for id in &stale {
+            // Tags and embeddings cascade
+            tx.execute("DELETE FROM prompts WHERE id = ?1", params![id])?;
+        }
+        swap.removed = stale.len();
+
+        let now = Utc::now().to_rfc3339();
+        tx.execute(
+            "INSERT INTO registry_cache (id, url, etag, last_modified, fetched_at, checked_at, prompt_count)
+             VALUES (1, ?1, ?2, ?3, ?4, ?4, ?5)
+             ON CONFLICT(id) DO UPDATE SET
+                url = excluded.url,
+                etag = excluded.etag,
+                last_modified = excluded.last_modified,
+                fetched_at = excluded.fetched_at,
+                checked_at = excluded.checked_at,
+                prompt_count = excluded.prompt_count",
+            params![url, etag, last_modified, now, prompts.len() as i64],
+        )?;
+        tx.commit()?;
+        Ok(swap)
+    }
+
+    /// Number of stored prompts per source (`bundled`, `remote`, `local`)
+    pub fn source_counts(&self) -> Result<Vec<(String, usize)>> {
+        collect_counts(|each| self.counts("SELECT source, COUNT(*) FROM prompts GROUP BY source ORDER BY source", each))
+    }
+
+    /// Sync markers for the premium library
+    pub fn library_sync_state(&self) -> Result<LibrarySyncState> {
+        Ok(self.conn.query_row(
+            "SELECT last_synced_at, jsonl_sha256, record_count FROM sync_meta WHERE id = 1",
+            [],
+            |row| {
+                Ok(LibrarySyncState {
+                    last_synced_at: row.get(0)?,
+                    jsonl_sha256: row.get(1)?,
+                    record_count: row.get::<_, i64>(2)? as usize,
+                })
+            },
+        )?)
+    }
+
+    /// Apply a library sync in one transaction
+    ///
+    /// Synced prompts replace stored ones with the same id; with
+    /// `replace_all` (a full sync) everything else goes too. Returns the
+    /// library size afterwards.
+    pub fn apply_library_sync(&self, prompts: &[SyncedPrompt], replace_all: bool, synced_at: &str) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        if replace_all {
+            tx.execute("DELETE FROM library_prompts", [])?;
+        }
+        for prompt in prompts {
+            tx.execute(
+                "INSERT INTO library_prompts (id, title, data, synced_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(id) DO UPDATE SET
+                    title = excluded.title,
+                    data = excluded.data,
+                    synced_at = excluded.synced_at",
+                params![prompt.id, prompt.title, serde_json::to_string(prompt)?, synced_at],
+            )?;
+        }
+        let count: i64 = tx.query_row("SELECT COUNT(*) FROM library_prompts", [], |row| row.get(0))?;
+        tx.execute(
+            "UPDATE sync_meta SET last_synced_at = ?1, record_count = ?2 WHERE id = 1",
+            params![synced_at, count],
+        )?;
+        tx.commit()?;
+        Ok(count as usize)
+    }
+
+    /// The premium library, ordered by id
+    pub fn library_prompts(&self) -> Result<Vec<SyncedPrompt>> {
+        let mut stmt = self.conn.prepare("SELECT data FROM library_prompts ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?)).collect()
+    }
+
+    /// Record the hash of the JSONL mirror just written
+    pub fn set_library_mirror_hash(&self, sha256: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE sync_meta SET jsonl_sha256 = ?1 WHERE id = 1",
+            params![sha256],
+        )?;
+        Ok(())
+    }
+
+    /// Whether `id` is in the premium library
+    pub fn has_library_prompt(&self, id: &str) -> Result<bool> {
+        Ok(self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM library_prompts WHERE id = ?1)",
+            params![id],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Notes on a prompt, oldest first; notes deleted locally are left out
+    pub fn notes(&self, prompt_id: &str) -> Result<Vec<Note>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, prompt_id, content, created_at, updated_at, remote_id, pending FROM notes
+             WHERE prompt_id = ?1 AND pending IS NOT 'delete'
+             ORDER BY created_at, id",
+        )?;
+        let rows = stmt.query_map(params![prompt_id], note_from_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Add a note, queued for upload
+    pub fn add_note(&self, prompt_id: &str, content: &str) -> Result<Note> {
+        let now = Utc::now().to_rfc3339();
+        let note = Note {
+            id: format!("note-{:08x}", rand::rng().random::<u32>()),
+            prompt_id: prompt_id.to_string(),
+            content: content.to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+            remote_id: None,
+            synced: false,
+        };
+        self.conn.execute(
+            "INSERT INTO notes (id, prompt_id, content, created_at, updated_at, pending)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'add')",
+            params![note.id, note.prompt_id, note.content, note.created_at, note.updated_at],
+        )?;
+        Ok(note)
+    }
+
+    /// Delete a note by its local or server id; `false` when there is none
+    ///
+    /// A note the server already has stays behind as a queued delete.
+    pub fn delete_note(&self, prompt_id: &str, note_id: &str) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        let removed = tx.execute(
+            "DELETE FROM notes WHERE prompt_id = ?1 AND (id = ?2 OR remote_id = ?2) AND remote_id IS NULL",
+            params![prompt_id, note_id],
+        )? + tx.execute(
+            "UPDATE notes SET pending = 'delete', updated_at = ?3
+             WHERE prompt_id = ?1 AND (id = ?2 OR remote_id = ?2) AND pending IS NOT 'delete'",
+            params![prompt_id, note_id, Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+
+    /// Note changes waiting for upload, oldest first, for one prompt or all
+    pub fn pending_notes(&self, prompt_id: Option<&str>) -> Result<Vec<(Note, NoteChange)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, prompt_id, content, created_at, updated_at, remote_id, pending FROM notes
+             WHERE pending IS NOT NULL AND (?1 IS NULL OR prompt_id = ?1)
+             ORDER BY updated_at, id",
+        )?;
+        let rows = stmt.query_map(params![prompt_id], |row| {
+            let change = match row.get::<_, String>(6)?.as_str() {
+                "delete" => NoteChange::Delete,
+                _ => NoteChange::Add,
+            };
+            Ok((note_from_row(row)?, change))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Record that the server stored a note as `remote_id`
+    pub fn note_uploaded(&self, id: &str, remote_id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE notes SET remote_id = ?2, pending = NULL WHERE id = ?1 AND pending = 'add'",
+            params![id, remote_id],
+        )?;
+        Ok(())
+    }
+
+    /// Drop a note whose deletion has reached the server
+    pub fn forget_note(&self, id: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Bring a prompt's uploaded notes in line with the server's list
+    ///
+    /// Notes new on the server are added and uploaded notes it no longer has
+    /// are dropped; changes still queued locally win.
+    pub fn merge_remote_notes(&self, prompt_id: &str, remote: &[Note]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for note in remote {
+            tx.execute(
+                "UPDATE notes SET content = ?2, updated_at = ?3 WHERE remote_id = ?1 AND pending IS NULL",
+                params![note.id, note.content, note.updated_at],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO notes (id, prompt_id, content, created_at, updated_at, remote_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?1)",
+                params![note.id, prompt_id, note.content, note.created_at, note.updated_at],
+            )?;
+        }
+        let remote_ids = serde_json::to_string(&remote.iter().map(|n| &n.id).collect::<Vec<_>>())?;
+        tx.execute(
+            "DELETE FROM notes
+             WHERE prompt_id = ?1 AND pending IS NULL AND remote_id IS NOT NULL
+               AND remote_id NOT IN (SELECT value FROM json_each(?2))",
+            params![prompt_id, remote_ids],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Create an empty collection; `None` when the name is taken (ignoring case)
+    pub fn create_collection(&self, name: &str, description: Option<&str>) -> Result<Option<Collection>> {
+        let now = Utc::now().to_rfc3339();
+        let created = self.conn.execute(
+            "INSERT OR IGNORE INTO collections (name, description, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?3)",
+            params![name, description, now],
+        )?;
+        if created == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Collection {
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            prompt_ids: Vec::new(),
+            created_at: now.clone(),
+            updated_at: now,
+            synced_at: None,
+        }))
+    }
+
+    /// Look up a collection by name, ignoring case
+    pub fn get_collection(&self, name: &str) -> Result<Option<Collection>> {
+        Ok(self
+            .load_collections("WHERE name = ?1", params![name])?
+            .into_iter()
+            .next())
+    }
+
+    /// List collections by name
+    pub fn list_collections(&self) -> Result<Vec<Collection>> {
+        self.load_collections("", [])
+    }
+
+    /// Append prompts to a collection, skipping members; returns the ids added
+    pub fn add_to_collection(&self, name: &str, prompt_ids: &[String]) -> Result<Vec<String>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut added = Vec::new();
+        for prompt_id in prompt_ids {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO collection_prompts (collection_id, prompt_id, position, added_at)
+                 SELECT c.id, ?2, COALESCE(MAX(cp.position), 0) + 1, ?3
+                 FROM collections c LEFT JOIN collection_prompts cp ON cp.collection_id = c.id
+                 WHERE c.name = ?1
+                 GROUP BY c.id",
+                params![name, prompt_id, Utc::now().to_rfc3339()],
+            )?;
+            if inserted > 0 {
+                added.push(prompt_id.clone());
+            }
+        }
+        if !added.is_empty() {
+            touch_collection(&tx, name)?;
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Remove prompts from a collection; returns the ids that were members
+    pub fn remove_from_collection(&self, name: &str, prompt_ids: &[String]) -> Result<Vec<String>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut removed = Vec::new();
+        for prompt_id in prompt_ids {
+            let deleted = tx.execute(
+                "DELETE FROM collection_prompts
+                 WHERE prompt_id = ?2 AND collection_id = (SELECT id FROM collections WHERE name = ?1)",
+                params![name, prompt_id],
+            )?;
+            if deleted > 0 {
+                removed.push(prompt_id.clone());
+            }
+        }
+        if !removed.is_empty() {
+            touch_collection(&tx, name)?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Record that a collection was pushed to premium
+    pub fn mark_collection_synced(&self, name: &str, synced_at: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE collections SET synced_at = ?2 WHERE name = ?1",
+            params![name, synced_at],
+        )?;
+        Ok(())
+    }
+
+    /// Look up a prompt by ID
+    pub fn get_prompt(&self, id: &str) -> Result<Option<Prompt>> {
+        let data: Option<String> = self
+            .conn
+            .query_row("SELECT data FROM prompts WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()?;
+        data.map(|d| serde_json::from_str(&d).map_err(StorageError::from))
+            .transpose()
+    }
+
+    /// Full-text search, best match first
+    ///
+    /// `query` is free text, tokenized and synonym-expanded as in the TS
+    /// engine, so nothing in it is FTS5 syntax. Prompts containing a term
+    /// are ranked by [`Bm25Index`] over the whole library, ties in library
+    /// order; scores are positive, higher is better. `collection` limits
+    /// results to that collection's members.
+    pub fn search(&self, query: &str, limit: usize, collection: Option<&str>) -> Result<Vec<SearchResult>> {
+        let mut results = Vec::new();
+        self.search_each(query, limit, collection, |result| {
+            results.push(result);
+            Ok::<_, StorageError>(())
+        })?;
+        Ok(results)
+    }
+
+    /// [`search`](Self::search), handing each result to `each` once ranked
+    pub fn search_each<E: From<StorageError>>(
+        &self,
+        query: &str,
+        limit: usize,
+        collection: Option<&str>,
+        mut each: impl FnMut(SearchResult) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        let terms = search::expand_query(&search::tokenize(query));
+        let Some(fts_query) = search::fts_query(&terms) else {
+            return Ok(());
+        };
+
+        // Candidates, with an excerpt of every column
+        let snippets: Vec<String> = (0..FTS_COLUMNS.len())
+            .map(|col| {
+                format!(
+                    "snippet(prompts_fts, {col}, char(2), char(3), '…', {SNIPPET_TOKENS})"
+                )
+            })
+            .collect();
+        let sql = format!(
+            "SELECT p.rowid, {}
+             FROM prompts_fts
+             JOIN prompts p ON p.rowid = prompts_fts.rowid
+             WHERE prompts_fts MATCH ?1
+               AND (?2 IS NULL OR EXISTS (
+                    SELECT 1 FROM collection_prompts cp JOIN collections c ON c.id = cp.collection_id
+                    WHERE cp.prompt_id = p.id AND c.name = ?2))",
+            snippets.join(", "),
+        );
+        let mut stmt = self.conn.prepare(&sql).map_err(StorageError::from)?;
+        let mut candidates: HashMap<i64, Vec<String>> = stmt
+            .query_map(params![fts_query, collection], |row| {
+                let mut excerpts = Vec::with_capacity(FTS_COLUMNS.len());
+                for col in 0..FTS_COLUMNS.len() {
+                    excerpts.push(row.get::<_, Option<String>>(col + 1)?.unwrap_or_default());
+                }
+                Ok((row.get(0)?, excerpts))
+            })
+            .and_then(Iterator::collect)
+            .map_err(StorageError::from)?;
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        let corpus = self.corpus()?;
+        let mut ranked: Vec<(usize, f64)> = corpus
+            .rowids
+            .iter()
+            .enumerate()
+            .filter(|(_, rowid)| candidates.contains_key(rowid))
+            .map(|(doc, _)| (doc, corpus.index.score(doc, &terms)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        // Stable, so ties keep library order
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (doc, score) in ranked.into_iter().take(limit) {
+            let excerpts = candidates.remove(&corpus.rowids[doc]).unwrap_or_default();
+            let (matched_fields, snippet) = match_details(excerpts);
+            each(SearchResult {
+                prompt: corpus.prompts[doc].clone(),
+                score,
+                matched_fields,
+                snippet,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// The library's BM25 statistics (document frequencies and lengths
+    /// depend on every prompt), rebuilt only after something was written
+    fn corpus(&self) -> Result<Rc<Corpus>> {
+        let data_version: i64 = self.conn.query_row("PRAGMA data_version", [], |row| row.get(0))?;
+        let version = (data_version, self.conn.total_changes());
+        if let Some(corpus) = self.corpus.borrow().as_ref().filter(|c| c.version == version) {
+            return Ok(Rc::clone(corpus));
+        }
+
+        let mut stmt = self.conn.prepare("SELECT rowid, data, notes_text FROM prompts ORDER BY rowid")?;
+        let library: Vec<(i64, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        let mut prompts = Vec::with_capacity(library.len());
+        for (_, data, _) in &library {
+            prompts.push(serde_json::from_str::<Prompt>(data)?);
+        }
+        let index = Bm25Index::build(prompts.iter().zip(library.iter().map(|(_, _, notes)| notes.as_str())));
+        let corpus = Rc::new(Corpus {
+            version,
+            rowids: library.iter().map(|(rowid, _, _)| *rowid).collect(),
+            prompts,
+            index,
+        });
+        *self.corpus.borrow_mut() = Some(Rc::clone(&corpus));
+        Ok(corpus)
+    }
+
+    /// List prompts, featured first then by title
+    ///
+    /// Limited to a collection's members, they come in collection order.
+    pub fn list_prompts_filtered(
+        &self,
+        category: Option<&str>,
+        tag: Option<&str>,
+        featured_only: bool,
+        collection: Option<&str>,
+    ) -> Result<Vec<Prompt>> {
+        let mut prompts = Vec::new();
+        self.each_prompt_filtered(category, tag, featured_only, collection, |prompt| {
+            prompts.push(prompt);
+            Ok::<_, StorageError>(())
+        })?;
+        Ok(prompts)
+    }
+
+    /// [`list_prompts_filtered`](Self::list_prompts_filtered), handing each
+    /// prompt to `each` as it is read
+    pub fn each_prompt_filtered<E: From<StorageError>>(
+        &self,
+        category: Option<&str>,
+        tag: Option<&str>,
+        featured_only: bool,
+        collection: Option<&str>,
+        mut each: impl FnMut(Prompt) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.data FROM prompts p
+             LEFT JOIN collection_prompts cp ON cp.prompt_id = p.id
+                AND cp.collection_id = (SELECT id FROM collections WHERE name = ?4)
+             WHERE (?1 IS NULL OR p.category = ?1)
+               AND (?2 IS NULL OR EXISTS (
+                    SELECT 1 FROM prompt_tags t WHERE t.prompt_id = p.id AND t.tag = ?2))
+               AND (?3 = 0 OR p.featured = 1)
+               AND (?4 IS NULL OR cp.prompt_id IS NOT NULL)
+             ORDER BY cp.position, p.featured DESC, p.title COLLATE NOCASE",
+        ).map_err(StorageError::from)?;
+        let rows = stmt
+            .query_map(params![category, tag, featured_only, collection], |row| row.get::<_, String>(0))
+            .map_err(StorageError::from)?;
+
+        for row in rows {
+            let data = row.map_err(StorageError::from)?;
+            each(serde_json::from_str(&data).map_err(StorageError::from)?)?;
+        }
+        Ok(())
+    }
+
+    /// Prompt counts per category, sorted by name
+    pub fn category_counts(&self) -> Result<Vec<(String, usize)>> {
+        collect_counts(|each| self.each_category_count(each))
+    }
+
+    /// [`category_counts`](Self::category_counts), handing each to `each`
+    /// as it is read
+    pub fn each_category_count<E: From<StorageError>>(
+        &self,
+        each: impl FnMut(String, usize) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        self.counts(
+            "SELECT category, COUNT(*) FROM prompts
+             WHERE category IS NOT NULL
+             GROUP BY category
+             ORDER BY category",
+            each,
+        )
+    }
+
+    /// Prompt counts per tag, sorted by count descending then name
+    pub fn tag_counts(&self) -> Result<Vec<(String, usize)>> {
+        collect_counts(|each| self.each_tag_count(each))
+    }
+
+    /// [`tag_counts`](Self::tag_counts), handing each to `each` as it is read
+    pub fn each_tag_count<E: From<StorageError>>(
+        &self,
+        each: impl FnMut(String, usize) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        self.counts(
+            "SELECT tag, COUNT(*) AS n FROM prompt_tags
+             GROUP BY tag
+             ORDER BY n DESC, tag",
+            each,
+        )
+    }
+
+    /// Cached embedding for a prompt, if one was stored by this embedder
+    pub fn get_embedding(&self, prompt_id: &str, embedder: &str) -> Result<Option<Vec<f32>>> {
+        let blob: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT vector FROM prompt_embeddings WHERE prompt_id = ?1 AND embedder = ?2",
+                params![prompt_id, embedder],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(blob.map(|bytes| {
+            bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        }))
+    }
+
+    /// Store (or replace) a prompt's embedding
+    pub fn put_embedding(&self, prompt_id: &str, embedder: &str, vector: &[f32]) -> Result<()> {
+        let bytes: Vec<u8> = vector.iter().flat_map(|v| v.to_le_bytes()).collect();
+        self.conn.execute(
+            "INSERT OR REPLACE INTO prompt_embeddings (prompt_id, embedder, dimensions, vector, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![prompt_id, embedder, vector.len() as i64, bytes, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Look up a bundle by ID
+    pub fn get_bundle(&self, id: &str) -> Result<Option<Bundle>> {
+        let data: Option<String> = self
+            .conn
+            .query_row("SELECT data FROM bundles WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()?;
+        data.map(|d| serde_json::from_str(&d).map_err(StorageError::from))
+            .transpose()
+    }
+
+    /// List bundles, featured first then by title
+    pub fn list_bundles(&self) -> Result<Vec<Bundle>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM bundles ORDER BY featured DESC, title COLLATE NOCASE")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut bundles = Vec::new();
+        for row in rows {
+            bundles.push(serde_json::from_str(&row?)?);
+        }
+        Ok(bundles)
+    }
+
+    /// Look up a workflow by ID
+    pub fn get_workflow(&self, id: &str) -> Result<Option<Workflow>> {
+        let data: Option<String> = self
+            .conn
+            .query_row("SELECT data FROM workflows WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()?;
+        data.map(|d| serde_json::from_str(&d).map_err(StorageError::from))
+            .transpose()
+    }
+
+    /// List workflows by title
+    pub fn list_workflows(&self) -> Result<Vec<Workflow>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM workflows ORDER BY title COLLATE NOCASE")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut workflows = Vec::new();
+        for row in rows {
+            workflows.push(serde_json::from_str(&row?)?);
+        }
+        Ok(workflows)
+    }
+
+    /// Start a new run of a workflow
+    pub fn create_workflow_run(&self, workflow_id: &str, variables: &BTreeMap<String, String>) -> Result<WorkflowRun> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            "INSERT INTO workflow_runs (workflow_id, variables, started_at, updated_at)
+             VALUES (?1, ?2, ?3, ?3)",
+            params![workflow_id, serde_json::to_string(variables)?, now],
+        )?;
+        Ok(WorkflowRun {
+            id: self.conn.last_insert_rowid(),
+            workflow_id: workflow_id.to_string(),
+            variables: variables.clone(),
+            started_at: now.clone(),
+            updated_at: now,
+            completed_at: None,
+            steps: Vec::new(),
+        })
+    }
+
+    /// Look up a workflow run, with its completed steps, by ID
+    pub fn get_workflow_run(&self, id: i64) -> Result<Option<WorkflowRun>> {
+        self.load_workflow_run(
+            "SELECT id, workflow_id, variables, started_at, updated_at, completed_at
+             FROM workflow_runs WHERE id = ?1",
+            params![id],
+        )
+    }
+
+    /// Most recently updated run of a workflow that has steps left
+    pub fn latest_unfinished_run(&self, workflow_id: &str) -> Result<Option<WorkflowRun>> {
+        self.load_workflow_run(
+            "SELECT id, workflow_id, variables, started_at, updated_at, completed_at
+             FROM workflow_runs
+             WHERE workflow_id = ?1 AND completed_at IS NULL
+             ORDER BY updated_at DESC, id DESC LIMIT 1",
+            params![workflow_id],
+        )
+    }
+
+    /// Replace the variables shared by a run's steps
+    pub fn update_run_variables(&self, run_id: i64, variables: &BTreeMap<String, String>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE workflow_runs SET variables = ?2, updated_at = ?3 WHERE id = ?1",
+            params![run_id, serde_json::to_string(variables)?, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Save a rendered step; re-rendering a step replaces it
+    pub fn record_workflow_step(&self, run_id: i64, step: &CompletedStep) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO workflow_run_steps (run_id, step_id, position, prompt_id, rendered, completed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![run_id, step.step_id, step.position as i64, step.prompt_id, step.rendered, step.completed_at],
+        )?;
+        tx.execute(
+            "UPDATE workflow_runs SET updated_at = ?2 WHERE id = ?1",
+            params![run_id, step.completed_at],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Mark a run finished
+    pub fn complete_workflow_run(&self, run_id: i64) -> Result<String> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            "UPDATE workflow_runs SET completed_at = ?2, updated_at = ?2 WHERE id = ?1",
+            params![run_id, now],
+        )?;
+        Ok(now)
+    }
+
+    fn load_workflow_run(&self, sql: &str, params: impl rusqlite::Params) -> Result<Option<WorkflowRun>> {
+        let row = self
+            .conn
+            .query_row(sql, params, |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                ))
+            })
+            .optional()?;
+        let Some((id, workflow_id, variables, started_at, updated_at, completed_at)) = row else {
+            return Ok(None);
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT step_id, position, prompt_id, rendered, completed_at
+             FROM workflow_run_steps WHERE run_id = ?1 ORDER BY position",
+        )?;
+        let steps = stmt
+            .query_map(params![id], |row| {
+                Ok(CompletedStep {
+                    step_id: row.get(0)?,
+                    position: row.get::<_, i64>(1)? as usize,
+                    prompt_id: row.get(2)?,
+                    rendered: row.get(3)?,
+                    completed_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(Some(WorkflowRun {
+            id,
+            workflow_id,
+            variables: serde_json::from_str(&variables)?,
+            started_at,
+            updated_at,
+            completed_at,
+            steps,
+        }))
+    }
+
+    /// Collections matching `filter` (a `WHERE` clause or empty), by name
+    fn load_collections(&self, filter: &str, params: impl rusqlite::Params) -> Result<Vec<Collection>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, name, description, created_at, updated_at, synced_at FROM collections
+             {filter} ORDER BY name COLLATE NOCASE"
+        ))?;
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    Collection {
+                        name: row.get(1)?,
+                        description: row.get(2)?,
+                        prompt_ids: Vec::new(),
+                        created_at: row.get(3)?,
+                        updated_at: row.get(4)?,
+                        synced_at: row.get(5)?,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut members = self
+            .conn
+            .prepare("SELECT prompt_id FROM collection_prompts WHERE collection_id = ?1 ORDER BY position")?;
+        let mut collections = Vec::with_capacity(rows.len());
+        for (id, mut collection) in rows {
+            collection.prompt_ids = members
+                .query_map(params![id], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            collections.push(collection);
+        }
+        Ok(collections)
+    }
+
+    fn counts<E: From<StorageError>>(
+        &self,
+        sql: &str,
+        mut each: impl FnMut(String, usize) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        let mut stmt = self.conn.prepare(sql).map_err(StorageError::from)?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))
+            .map_err(StorageError::from)?;
+        for row in rows {
+            let (name, count) = row.map_err(StorageError::from)?;
+            each(name, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Gather the counts a streaming count query hands out
+fn collect_counts(
+    query: impl FnOnce(&mut dyn FnMut(String, usize) -> Result<()>) -> Result<()>,
+) -> Result<Vec<(String, usize)>> {
+    let mut counts = Vec::new();
+    query(&mut |name, count| {
+        counts.push((name, count));
+        Ok(())
+    })?;
+    Ok(counts)
+}
+
+/// Bump a collection's `updated_at` after its members change
+fn touch_collection(conn: &Connection, name: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE collections SET updated_at = ?2 WHERE name = ?1",
+        params![name, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Insert or replace a prompt, keeping tags and the FTS index in step and
+/// dropping stale embeddings
+///
+/// A prompt that comes back after being removed picks up the notes written
+/// on it meanwhile. Runs inside the caller's transaction.
+fn write_prompt(conn: &Connection, prompt: &Prompt, data: &str, source: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO prompts
+            (id, title, description, category, tags_text, featured, content, data, stored_at, source, notes_text)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, (
+            SELECT COALESCE(group_concat(content, ' '), '') FROM notes
+            WHERE prompt_id = ?1 AND pending IS NOT 'delete'))
+         ON CONFLICT(id) DO UPDATE SET
+            title = excluded.title,
+            description = excluded.description,
+            category = excluded.category,
+            tags_text = excluded.tags_text,
+            featured = excluded.featured,
+            content = excluded.content,
+            data = excluded.data,
+            stored_at = excluded.stored_at,
+            source = excluded.source",
+        params![
+            prompt.id,
+            prompt.title,
+            prompt.description,
+            prompt.category,
+            prompt.tags.join(" "),
+            prompt.featured,
+            prompt.content,
+            data,
+            Utc::now().to_rfc3339(),
+            source,
+        ],
+    )?;
+
+    // Cached vectors were computed from the old text
+    conn.execute("DELETE FROM prompt_embeddings WHERE prompt_id = ?1", params![prompt.id])?;
+
+    conn.execute("DELETE FROM prompt_tags WHERE prompt_id = ?1", params![prompt.id])?;
+    for tag in &prompt.tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO prompt_tags (prompt_id, tag) VALUES (?1, ?2)",
+            params![prompt.id, tag],
+        )?;
+    }
+    Ok(())
+}
+
+/// Insert or replace a bundle; runs inside the caller's transaction
+fn write_bundle(conn: &Connection, bundle: &Bundle) -> Result<()> {
+    conn.execute(
+        "INSERT INTO bundles (id, title, featured, data, stored_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(id) DO UPDATE SET
+            title = excluded.title,
+            featured = excluded.featured,
+            data = excluded.data,
+            stored_at = excluded.stored_at",
+        params![
+            bundle.id,
+            bundle.title,
+            bundle.featured,
+            serde_json::to_string(bundle)?,
+            Utc::now().to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Insert or replace a workflow; runs inside the caller's transaction
+fn write_workflow(conn: &Connection, workflow: &Workflow) -> Result<()> {
+    conn.execute(
+        "INSERT INTO workflows (id, title, data, stored_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(id) DO UPDATE SET
+            title = excluded.title,
+            data = excluded.data,
+            stored_at = excluded.stored_at",
+        params![
+            workflow.id,
+            workflow.title,
+            serde_json::to_string(workflow)?,
+            Utc::now().to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// `id, prompt_id, content, created_at, updated_at, remote_id, pending`
+fn note_from_row(row: &Row) -> rusqlite::Result<Note> {
+    let remote_id: Option<String> = row.get(5)?;
+    let pending: Option<String> = row.get(6)?;
+    Ok(Note {
+        id: row.get(0)?,
+        prompt_id: row.get(1)?,
+        content: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+        synced: remote_id.is_some() && pending.is_none(),
+        remote_id,
+    })
+}
+
+fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Work out which fields matched and pick the excerpt to show
+fn match_details(excerpts: Vec<String>) -> (Vec<&'static str>, Option<Snippet>) {
+    let matched: Vec<(&'static str, String)> = FTS_COLUMNS
+        .into_iter()
+        .zip(excerpts)
+        .filter(|(_, text)| text.contains(HIGHLIGHT_START))
+        .collect();
+
+    let priority = FieldPriority::default();
+    let mut matched_fields: Vec<&'static str> = matched.iter().map(|(f, _)| *f).collect();
+    matched_fields.sort_by(|a, b| priority.for_field(b).total_cmp(&priority.for_field(a)));
+
+    let snippet = SNIPPET_PREFERENCE.iter().find_map(|preferred| {
+        matched
+            .iter()
+            .find(|(field, _)| field == preferred)
+            .map(|(field, text)| Snippet {
+                field,
+                text: text.trim().to_string(),
+            })
+    });
+
+    (matched_fields, snippet)
+}
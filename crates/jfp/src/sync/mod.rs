@@ -0,0 +1,183 @@
+//! Premium library sync
+//!
+//! From SYNC_STRATEGY.md:
+//! - SQLite (`library_prompts` plus the `sync_meta` markers) is the source
+//!   of truth; `<config_dir>/library/library.jsonl` is a mirror rewritten
+//!   after every sync, with `library.meta.json` beside it
+//! - The mirror is safe to commit to Git: one compact JSON object per line,
+//!   sorted by id, LF endings and no credentials, so an unchanged library
+//!   produces a byte-identical file
+//! - One sync at a time: an exclusive lock on `<config_dir>/locks/sync.lock`,
+//!   retried until the busy timeout (`JFP_BUSY_TIMEOUT`, default 5s)
+//! - Personal notes travel separately, through the queue in [`notes`]
+
+pub mod notes;
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config;
+use crate::export::{sha256_hex, write_atomic};
+use crate::storage::{Database, StorageError};
+use crate::types::SyncedPrompt;
+
+const LIBRARY_DIR_NAME: &str = "library";
+const LIBRARY_FILE_NAME: &str = "library.jsonl";
+const META_FILE_NAME: &str = "library.meta.json";
+const LOCK_FILE_NAME: &str = "locks/sync.lock";
+
+/// Version of the mirror format written to `library.meta.json`
+pub const MIRROR_SCHEMA_VERSION: u32 = 1;
+
+/// Pause between attempts to take the sync lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Errors raised while syncing the premium library
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("could not determine a configuration directory for the library")]
+    NoConfigDir,
+
+    #[error("another jfp sync is running (lock held on {} for {}s)", path.display(), waited.as_secs())]
+    Locked { path: PathBuf, waited: Duration },
+
+    #[error("failed to write {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+impl SyncError {
+    /// Stable error code for JSON output
+    pub fn code(&self) -> &'static str {
+        match self {
+            SyncError::Locked { .. } => "sync_locked",
+            SyncError::Storage(_) => "database_error",
+            SyncError::NoConfigDir | SyncError::Io { .. } => "io_error",
+        }
+    }
+}
+
+/// `library.meta.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorMeta {
+    pub schema_version: u32,
+    pub last_synced_at: Option<String>,
+    pub source_of_truth: String,
+    /// SHA-256 of the JSONL rendering of the SQLite library
+    pub sqlite_sha256: String,
+    pub record_count: usize,
+}
+
+/// Where the mirror lives
+pub fn library_dir() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join(LIBRARY_DIR_NAME))
+}
+
+pub fn library_path() -> Option<PathBuf> {
+    library_dir().map(|dir| dir.join(LIBRARY_FILE_NAME))
+}
+
+fn meta_path() -> Option<PathBuf> {
+    library_dir().map(|dir| dir.join(META_FILE_NAME))
+}
+
+/// Exclusive hold on the sync lock file, released on drop
+pub struct SyncLock {
+    _file: File,
+}
+
+/// Take the sync lock, waiting up to `timeout` for another sync to finish
+pub fn lock(timeout: Duration) -> Result<SyncLock, SyncError> {
+    let path = config::config_dir().ok_or(SyncError::NoConfigDir)?.join(LOCK_FILE_NAME);
+    let io_error = |source| SyncError::Io {
+        path: path.clone(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let mut file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .map_err(io_error)?;
+
+    let started = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => break,
+            Err(fs::TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                std::thread::sleep(LOCK_RETRY_INTERVAL);
+            }
+            Err(fs::TryLockError::WouldBlock) => {
+                return Err(SyncError::Locked {
+                    path,
+                    waited: started.elapsed(),
+                });
+            }
+            Err(fs::TryLockError::Error(source)) => return Err(io_error(source)),
+        }
+    }
+
+    // The holder's pid, for anyone inspecting a stuck lock
+    let _ = file.set_len(0).and_then(|()| writeln!(file, "{}", std::process::id()));
+    Ok(SyncLock { _file: file })
+}
+
+/// The library as JSONL: one compact object per line, ordered by id
+pub fn render_jsonl(prompts: &[SyncedPrompt]) -> Result<String, SyncError> {
+    let mut sorted: Vec<&SyncedPrompt> = prompts.iter().collect();
+    sorted.sort_by(|a, b| a.id.cmp(&b.id));
+    let mut jsonl = String::new();
+    for prompt in sorted {
+        jsonl.push_str(&serde_json::to_string(prompt).map_err(StorageError::from)?);
+        jsonl.push('\n');
+    }
+    Ok(jsonl)
+}
+
+/// Rewrite `library.jsonl` and `library.meta.json` from the database and
+/// record the new hash in `sync_meta`; returns the mirror path and hash
+pub fn write_mirror(db: &Database) -> Result<(PathBuf, String), SyncError> {
+    let (Some(path), Some(meta_path)) = (library_path(), meta_path()) else {
+        return Err(SyncError::NoConfigDir);
+    };
+    let jsonl = render_jsonl(&db.library_prompts()?)?;
+    let sha256 = sha256_hex(&jsonl);
+    let state = db.library_sync_state()?;
+
+    let meta = MirrorMeta {
+        schema_version: MIRROR_SCHEMA_VERSION,
+        last_synced_at: state.last_synced_at,
+        source_of_truth: "sqlite".to_string(),
+        sqlite_sha256: sha256.clone(),
+        record_count: state.record_count,
+    };
+    let mut meta_json = serde_json::to_string_pretty(&meta).map_err(StorageError::from)?;
+    meta_json.push('\n');
+
+    write_atomic(&path, jsonl.as_bytes()).map_err(|source| SyncError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    write_atomic(&meta_path, meta_json.as_bytes()).map_err(|source| SyncError::Io {
+        path: meta_path.clone(),
+        source,
+    })?;
+    db.set_library_mirror_hash(&sha256)?;
+    Ok((path, sha256))
+}
+
+/// SHA-256 of the mirror on disk, if there is one
+pub fn mirror_sha256(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|content| sha256_hex(&content))
+}
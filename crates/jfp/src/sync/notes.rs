@@ -0,0 +1,101 @@
+//! Note upload queue
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (notes): notes are written to
+//! SQLite first and reach the server when a premium session can: POST
+//! `/cli/notes/<promptId>` with { content } for new notes, DELETE
+//! `/cli/notes/<promptId>/<noteId>` for deleted ones. A change the server
+//! does not take stays queued for the next attempt.
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::auth::ApiClient;
+use crate::storage::{Database, Result};
+use crate::types::{Note, NoteChange};
+
+/// Outcome of one round of note syncing
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoteSync {
+    /// The server answered
+    pub online: bool,
+    /// Changes the server accepted
+    pub uploaded: usize,
+    /// Changes still queued
+    pub pending: usize,
+}
+
+/// Body of `GET /cli/notes/<promptId>`
+#[derive(Deserialize)]
+struct NotesResponse {
+    notes: Vec<Note>,
+}
+
+/// Upload queued note changes, for one prompt or all of them
+pub fn push(db: &Database, client: &ApiClient, prompt_id: Option<&str>) -> Result<NoteSync> {
+    let mut sync = NoteSync {
+        online: true,
+        ..NoteSync::default()
+    };
+    for (note, change) in db.pending_notes(prompt_id)? {
+        let response = match (change, &note.remote_id) {
+            (NoteChange::Add, _) => client.post(
+                &format!("/cli/notes/{}", note.prompt_id),
+                &json!({ "content": note.content }),
+            ),
+            (NoteChange::Delete, Some(remote_id)) => {
+                client.delete(&format!("/cli/notes/{}/{}", note.prompt_id, remote_id))
+            }
+            // Deleted before it was ever uploaded: nothing to tell the server
+            (NoteChange::Delete, None) => {
+                db.forget_note(&note.id)?;
+                continue;
+            }
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(_) => {
+                sync.online = false;
+                break;
+            }
+        };
+        // Signed out or not premium: nothing else will get through either
+        if response.status == 401 || response.status == 403 {
+            break;
+        }
+
+        match change {
+            NoteChange::Add if response.ok() => {
+                let remote_id = response
+                    .data
+                    .as_ref()
+                    .and_then(|data| data.get("note")?.get("id")?.as_str().map(str::to_string));
+                if let Some(remote_id) = remote_id {
+                    db.note_uploaded(&note.id, &remote_id)?;
+                    sync.uploaded += 1;
+                }
+            }
+            // Already gone on the server counts as deleted
+            NoteChange::Delete if response.ok() || response.status == 404 => {
+                db.forget_note(&note.id)?;
+                sync.uploaded += 1;
+            }
+            _ => {}
+        }
+    }
+    sync.pending = db.pending_notes(prompt_id)?.len();
+    Ok(sync)
+}
+
+/// Merge the server's notes on a prompt; `false` when it could not be asked
+pub fn pull(db: &Database, client: &ApiClient, prompt_id: &str) -> Result<bool> {
+    let notes = client
+        .get(&format!("/cli/notes/{}", prompt_id), &[])
+        .ok()
+        .filter(|response| response.ok())
+        .and_then(|response| serde_json::from_value::<NotesResponse>(response.data?).ok());
+    let Some(NotesResponse { notes }) = notes else {
+        return Ok(false);
+    };
+    db.merge_remote_notes(prompt_id, &notes)?;
+    Ok(true)
+}
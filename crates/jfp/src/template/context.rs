@@ -0,0 +1,345 @@
+//! Context injection for rendered prompts
+//!
+//! Agents feed source files into a prompt with `--context` (files,
+//! directories or glob patterns, repeatable) and `--stdin`. Everything is
+//! gathered in a fixed order under one byte budget:
+//!
+//! 1. stdin, if requested
+//! 2. each `--context` argument in command-line order; directories and globs
+//!    expand to their files sorted by path, hidden entries skipped
+//!
+//! Sources are included whole until the budget runs out. The source that
+//! crosses the budget is cut at a UTF-8 boundary and everything after it is
+//! omitted, so the same inputs always produce the same context. Binary files
+//! are skipped wherever they come, and invalid UTF-8 is replaced; sizes
+//! count the bytes of the source, included bytes those of the text.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Default context budget in bytes (200KB, as in the TS CLI)
+pub const DEFAULT_MAX_CONTEXT: usize = 204_800;
+
+/// Rough bytes-per-token ratio used for token budgets and estimates
+pub const BYTES_PER_TOKEN: usize = 4;
+
+/// How long to wait for piped input before giving up
+const STDIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Bytes inspected when deciding whether a file is binary
+const BINARY_SNIFF_LEN: usize = 8192;
+
+/// Errors raised while gathering context
+#[derive(Debug, Error)]
+pub enum ContextError {
+    #[error("Context file not found: {0}")]
+    NotFound(String),
+
+    #[error("invalid glob pattern \"{pattern}\": {source}")]
+    InvalidGlob {
+        pattern: String,
+        source: glob::PatternError,
+    },
+
+    #[error("Failed to read context file {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to read stdin: {0}")]
+    Stdin(std::io::Error),
+
+    #[error("Timed out waiting for stdin input ({}s)", STDIN_TIMEOUT.as_secs())]
+    StdinTimeout,
+}
+
+impl ContextError {
+    /// Stable error code for JSON output
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "file_not_found",
+            Self::InvalidGlob { .. } => "invalid_glob",
+            Self::Read { .. } | Self::Stdin(_) => "read_error",
+            Self::StdinTimeout => "stdin_timeout",
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ContextError>;
+
+/// One context source and how much of it made it into the prompt
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ContextPart {
+    /// `stdin` or the file path
+    pub source: String,
+    /// Size of the source in bytes
+    pub bytes: usize,
+    /// Bytes of text included after applying the budget
+    pub included_bytes: usize,
+    pub truncated: bool,
+    /// Why the source was left out entirely, if it was
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<&'static str>,
+    #[serde(skip)]
+    text: String,
+}
+
+/// Context gathered for a render
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Context {
+    pub budget_bytes: usize,
+    pub total_bytes: usize,
+    pub included_bytes: usize,
+    pub estimated_tokens: usize,
+    pub truncated: bool,
+    pub sources: Vec<ContextPart>,
+}
+
+impl Context {
+    /// Gather stdin (when `stdin` is set) and `paths` under `budget` bytes
+    pub fn gather(stdin: bool, paths: &[String], budget: usize) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut remaining = budget;
+
+        if stdin {
+            let (bytes, size) = read_stdin(budget)?;
+            parts.push(take_part("stdin".to_string(), size, bytes, &mut remaining));
+        }
+
+        for path in expand_paths(paths)? {
+            let source = path.display().to_string();
+            let size = std::fs::metadata(&path)
+                .map_err(|source| ContextError::Read {
+                    path: path.clone(),
+                    source,
+                })?
+                .len() as usize;
+            // Enough to tell binary files apart even once the budget is spent
+            let bytes = read_prefix(&path, remaining.max(BINARY_SNIFF_LEN).min(size))?;
+            if is_binary(&bytes) {
+                parts.push(ContextPart {
+                    source,
+                    bytes: size,
+                    included_bytes: 0,
+                    truncated: false,
+                    skipped: Some("binary"),
+                    text: String::new(),
+                });
+                continue;
+            }
+            parts.push(take_part(source, size, bytes, &mut remaining));
+        }
+
+        let total_bytes = parts.iter().map(|p| p.bytes).sum();
+        let included_bytes = parts.iter().map(|p| p.included_bytes).sum();
+        Ok(Self {
+            budget_bytes: budget,
+            total_bytes,
+            included_bytes,
+            estimated_tokens: included_bytes.div_ceil(BYTES_PER_TOKEN),
+            truncated: parts.iter().any(|p| p.truncated),
+            sources: parts,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.iter().all(|p| p.text.is_empty())
+    }
+
+    /// Markdown appended to the rendered prompt
+    ///
+    /// Stdin alone is appended as-is (as in the TS CLI); files get a heading
+    /// and a fenced block each.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("\n\n---\n\n## Context\n\n");
+        let included: Vec<&ContextPart> = self.sources.iter().filter(|p| !p.text.is_empty()).collect();
+
+        if let [part] = included.as_slice()
+            && part.source == "stdin"
+        {
+            out.push_str(&part.text);
+        } else {
+            let blocks: Vec<String> = included
+                .iter()
+                .map(|part| {
+                    let fence = fence_for(&part.text);
+                    let newline = if part.text.ends_with('\n') { "" } else { "\n" };
+                    format!("### {}\n\n{fence}\n{}{newline}{fence}", part.source, part.text)
+                })
+                .collect();
+            out.push_str(&blocks.join("\n\n"));
+        }
+
+        if self.truncated {
+            let omitted = self
+                .sources
+                .iter()
+                .filter(|p| p.truncated && p.included_bytes == 0)
+                .count();
+            out.push_str(&format!(
+                "\n\n[Context truncated to {} of {} bytes",
+                self.included_bytes, self.total_bytes
+            ));
+            if omitted > 0 {
+                out.push_str(&format!("; {} source(s) omitted", omitted));
+            }
+            out.push(']');
+        }
+        out
+    }
+}
+
+/// Expand `--context` arguments into an ordered, de-duplicated file list
+pub fn expand_paths(args: &[String]) -> Result<Vec<PathBuf>> {
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+
+    for arg in args {
+        let mut expanded = Vec::new();
+        let path = Path::new(arg);
+        if path.is_file() {
+            expanded.push(path.to_path_buf());
+        } else if path.is_dir() {
+            walk_dir(path, &mut expanded)?;
+        } else if is_glob(arg) {
+            let matches = glob::glob(arg).map_err(|source| ContextError::InvalidGlob {
+                pattern: arg.clone(),
+                source,
+            })?;
+            let mut matched: Vec<PathBuf> = matches.filter_map(|m| m.ok()).collect();
+            matched.sort();
+            for m in matched {
+                if m.is_dir() {
+                    walk_dir(&m, &mut expanded)?;
+                } else if m.is_file() && !is_hidden(&m) {
+                    expanded.push(m);
+                }
+            }
+        }
+
+        if expanded.is_empty() {
+            return Err(ContextError::NotFound(arg.clone()));
+        }
+        files.extend(expanded.into_iter().filter(|f| seen.insert(f.clone())));
+    }
+
+    Ok(files)
+}
+
+/// Append the files under `dir` in path order, skipping hidden entries
+fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let read_error = |source| ContextError::Read {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(read_error)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| !is_hidden(p))
+        .collect();
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            walk_dir(&entry, files)?;
+        } else if entry.is_file() {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+fn is_glob(arg: &str) -> bool {
+    arg.contains(['*', '?', '['])
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+/// Read at most `limit` bytes from the start of a file
+fn read_prefix(path: &Path, limit: usize) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(limit);
+    File::open(path)
+        .and_then(|f| f.take(limit as u64).read_to_end(&mut bytes))
+        .map_err(|source| ContextError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    Ok(bytes)
+}
+
+/// Read piped stdin, keeping the first `limit` bytes and counting the rest;
+/// gives up after [`STDIN_TIMEOUT`]
+fn read_stdin(limit: usize) -> Result<(Vec<u8>, usize)> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut bytes = Vec::new();
+        let result = (&mut stdin)
+            .take(limit as u64)
+            .read_to_end(&mut bytes)
+            .and_then(|kept| Ok(kept + std::io::copy(&mut stdin, &mut std::io::sink())? as usize))
+            .map(|size| (bytes, size));
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(STDIN_TIMEOUT) {
+        Ok(result) => result.map_err(ContextError::Stdin),
+        Err(_) => Err(ContextError::StdinTimeout),
+    }
+}
+
+/// Charge a source's text against the remaining budget, cutting at a char
+/// boundary
+///
+/// `bytes` is the start of a source of `size` bytes.
+fn take_part(source: String, size: usize, bytes: Vec<u8>, remaining: &mut usize) -> ContextPart {
+    // A character cut off where the read stopped is dropped, not replaced
+    let mut complete = bytes.len();
+    if let Some(last) = bytes.utf8_chunks().last()
+        && std::str::from_utf8(last.invalid()).is_err_and(|e| e.error_len().is_none())
+    {
+        complete -= last.invalid().len();
+    }
+    // Invalid UTF-8 in the middle is kept, lossily
+    let text = String::from_utf8_lossy(&bytes[..complete]);
+    let mut end = text.len().min(*remaining);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    *remaining -= end;
+
+    ContextPart {
+        source,
+        bytes: size,
+        included_bytes: end,
+        truncated: end < text.len() || complete < size,
+        skipped: None,
+        text: text[..end].to_string(),
+    }
+}
+
+/// Code fence longer than any backtick run in `text`
+fn fence_for(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
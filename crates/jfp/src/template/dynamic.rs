@@ -0,0 +1,60 @@
+//! Dynamic variable defaults
+//!
+//! From packages/core/src/template/variables.ts (`getDynamicDefaults`), plus
+//! the `GIT_BRANCH` default the TS core leaves to the CLI layer. The branch is
+//! read straight from `.git/HEAD` so rendering never shells out to git.
+
+use std::path::{Path, PathBuf};
+
+use super::Variables;
+
+/// Length of the abbreviated commit shown for a detached HEAD
+const SHORT_SHA_LEN: usize = 7;
+
+/// Defaults derived from the working directory: `CWD`, `PROJECT_NAME` and,
+/// inside a git checkout, `GIT_BRANCH`
+pub fn dynamic_defaults(cwd: &Path) -> Variables {
+    let mut defaults = Variables::new();
+    defaults.insert("CWD".to_string(), cwd.display().to_string());
+    if let Some(name) = cwd.file_name() {
+        defaults.insert("PROJECT_NAME".to_string(), name.to_string_lossy().into_owned());
+    }
+    if let Some(branch) = git_branch(cwd) {
+        defaults.insert("GIT_BRANCH".to_string(), branch);
+    }
+    defaults
+}
+
+/// Current branch of the repository containing `cwd`
+///
+/// A detached HEAD yields the abbreviated commit instead.
+fn git_branch(cwd: &Path) -> Option<String> {
+    let head = cwd.ancestors().find_map(head_file)?;
+    let head = std::fs::read_to_string(head).ok()?;
+    let head = head.trim();
+
+    match head.strip_prefix("ref:") {
+        Some(reference) => {
+            let reference = reference.trim();
+            let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+            (!branch.is_empty()).then(|| branch.to_string())
+        }
+        None if head.len() >= SHORT_SHA_LEN && head.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Some(head[..SHORT_SHA_LEN].to_string())
+        }
+        None => None,
+    }
+}
+
+/// `HEAD` of the git directory at `dir`, following `gitdir:` files used by
+/// worktrees and submodules
+fn head_file(dir: &Path) -> Option<PathBuf> {
+    let dot_git = dir.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git.join("HEAD")).filter(|head| head.is_file());
+    }
+
+    let pointer = std::fs::read_to_string(&dot_git).ok()?;
+    let git_dir = pointer.trim().strip_prefix("gitdir:")?.trim();
+    Some(dir.join(git_dir).join("HEAD")).filter(|head| head.is_file())
+}
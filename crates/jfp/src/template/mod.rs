@@ -0,0 +1,240 @@
+//! Prompt templates
+//!
+//! From packages/core/src/template: `{{VARIABLE}}` placeholders are replaced
+//! by plain string substitution (no logic, no eval). Placeholders without a
+//! value are left in place so the caller can see what is still open.
+//!
+//! Values are resolved against the prompt's declared [`PromptVariable`]s:
+//! `file` values are read from disk, `select` values must be one of the
+//! declared options, and required variables must end up non-empty.
+
+pub mod context;
+mod dynamic;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::types::{Prompt, PromptVariable, VariableType};
+
+pub use dynamic::dynamic_defaults;
+
+/// Max bytes read for a `file` variable (100KB, as in the TS CLI)
+pub const MAX_FILE_VAR_SIZE: u64 = 102_400;
+
+/// Variable name -> value
+pub type Variables = BTreeMap<String, String>;
+
+/// Errors raised while resolving template variables
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("invalid variable \"{0}\"; expected NAME=VALUE")]
+    InvalidAssignment(String),
+
+    #[error("Missing required variables: {}", .0.join(", "))]
+    MissingVariables(Vec<String>),
+
+    #[error("Invalid value \"{value}\" for {name}; expected one of: {}", .options.join(", "))]
+    InvalidOption {
+        name: String,
+        value: String,
+        options: Vec<String>,
+    },
+
+    #[error("File not found for variable {name}: {}", path.display())]
+    FileNotFound { name: String, path: PathBuf },
+
+    #[error("Path is not a file for variable {name}: {}", path.display())]
+    NotAFile { name: String, path: PathBuf },
+
+    #[error("failed to read {} for variable {name}: {source}", path.display())]
+    ReadFile {
+        name: String,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, TemplateError>;
+
+/// Parse a `NAME=VALUE` assignment
+pub fn parse_assignment(arg: &str) -> Result<(String, String)> {
+    match arg.split_once('=') {
+        Some((name, value)) if is_variable_name(name) => Ok((name.to_string(), value.to_string())),
+        _ => Err(TemplateError::InvalidAssignment(arg.to_string())),
+    }
+}
+
+/// Variable values from a JSON object, coercing non-strings to text
+///
+/// `null` becomes an empty string; numbers, booleans, arrays and objects
+/// are written as JSON.
+pub fn variables_from_json(object: &serde_json::Map<String, serde_json::Value>) -> Variables {
+    object
+        .iter()
+        .map(|(name, value)| {
+            let text = match value {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            (name.clone(), text)
+        })
+        .collect()
+}
+
+/// Whether `name` can appear in a placeholder: a letter, then letters,
+/// digits or underscores
+pub fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Variable names referenced by `content`, in order of first appearance
+pub fn placeholders(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for_each_placeholder(content, |name| {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        None
+    });
+    names
+}
+
+/// Substitute `{{NAME}}` placeholders, keeping those without a value
+pub fn render(content: &str, vars: &Variables) -> String {
+    for_each_placeholder(content, |name| vars.get(name).cloned())
+}
+
+/// Declared variables the content uses that have no value in `current`,
+/// in declaration order (what `--fill` asks for)
+pub fn unfilled<'a>(prompt: &'a Prompt, current: &Variables) -> Vec<&'a PromptVariable> {
+    let used = placeholders(&prompt.content);
+    prompt
+        .variables
+        .iter()
+        .filter(|v| used.contains(&v.name))
+        .filter(|v| current.get(&v.name).is_none_or(|value| value.is_empty()))
+        .collect()
+}
+
+/// Resolve the final value of every variable for `prompt`
+///
+/// Precedence is `provided`, then `dynamic` (see [`dynamic_defaults`]), then
+/// the declared default. Provided and dynamic values are processed by type;
+/// declared defaults are used verbatim. Extra values the prompt does not
+/// declare are passed through untouched.
+pub fn resolve(prompt: &Prompt, provided: Variables, dynamic: Variables) -> Result<Variables> {
+    let mut resolved = dynamic;
+    resolved.extend(provided);
+
+    let mut missing = Vec::new();
+    for var in &prompt.variables {
+        match resolved.get(&var.name).filter(|v| !v.is_empty()) {
+            Some(value) => {
+                let value = process_value(var, value)?;
+                resolved.insert(var.name.clone(), value);
+            }
+            None => match var.default.as_ref().filter(|d| !d.is_empty()) {
+                Some(default) => {
+                    resolved.insert(var.name.clone(), default.clone());
+                }
+                None if var.required => missing.push(var.name.clone()),
+                None => {}
+            },
+        }
+    }
+
+    if missing.is_empty() {
+        Ok(resolved)
+    } else {
+        Err(TemplateError::MissingVariables(missing))
+    }
+}
+
+/// Apply type-specific handling to a supplied value
+fn process_value(var: &PromptVariable, value: &str) -> Result<String> {
+    match var.kind {
+        VariableType::Select if !var.options.is_empty() && !var.options.iter().any(|o| o == value) => {
+            Err(TemplateError::InvalidOption {
+                name: var.name.clone(),
+                value: value.to_string(),
+                options: var.options.clone(),
+            })
+        }
+        VariableType::File => read_file_variable(&var.name, value),
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// Read a `file` variable, truncating at [`MAX_FILE_VAR_SIZE`] with a note
+fn read_file_variable(name: &str, path: &str) -> Result<String> {
+    let path = PathBuf::from(path);
+    let metadata = std::fs::metadata(&path).map_err(|_| TemplateError::FileNotFound {
+        name: name.to_string(),
+        path: path.clone(),
+    })?;
+    if !metadata.is_file() {
+        return Err(TemplateError::NotAFile {
+            name: name.to_string(),
+            path,
+        });
+    }
+
+    let read_error = |source| TemplateError::ReadFile {
+        name: name.to_string(),
+        path: path.clone(),
+        source,
+    };
+    let mut bytes = Vec::new();
+    File::open(&path)
+        .and_then(|f| f.take(MAX_FILE_VAR_SIZE).read_to_end(&mut bytes))
+        .map_err(read_error)?;
+
+    let mut content = String::from_utf8_lossy(&bytes).into_owned();
+    if metadata.len() > MAX_FILE_VAR_SIZE {
+        content.push_str(&format!(
+            "\n\n[File truncated to {} bytes from {} bytes]",
+            MAX_FILE_VAR_SIZE,
+            metadata.len()
+        ));
+    }
+    Ok(content)
+}
+
+/// Walk `{{ NAME }}` placeholders, replacing each with `f(name)` when it
+/// returns a value (the TS `/\{\{\s*([a-zA-Z0-9_]+)\s*\}\}/g`)
+fn for_each_placeholder<F>(content: &str, mut f: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let parsed = candidate[2..].find("}}").and_then(|end| {
+            let name = candidate[2..2 + end].trim_matches(|c: char| c.is_ascii_whitespace());
+            let is_name = !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            is_name.then(|| (&candidate[..end + 4], name))
+        });
+        match parsed {
+            Some((placeholder, name)) => {
+                out.push_str(&f(name).unwrap_or_else(|| placeholder.to_string()));
+                rest = &candidate[placeholder.len()..];
+            }
+            None => {
+                out.push('{');
+                rest = &candidate[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
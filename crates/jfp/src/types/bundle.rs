@@ -0,0 +1,49 @@
+//! Bundle types
+//!
+//! From packages/core/src/prompts/bundles.ts: a curated, ordered collection
+//! of prompts with an optional workflow describing how to use them together.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A bundle as stored in the registry and the local database
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    /// Unique identifier (kebab-case)
+    pub id: String,
+
+    /// Human-readable title
+    pub title: String,
+
+    /// One-line description
+    pub description: String,
+
+    /// Semantic version
+    pub version: String,
+
+    /// Last update date (ISO 8601)
+    pub updated_at: String,
+
+    /// Member prompts, in the order they are meant to be used
+    pub prompt_ids: Vec<String>,
+
+    /// How to use the prompts together (markdown)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>,
+
+    /// When to use this bundle
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when_to_use: Vec<String>,
+
+    /// Author attribution
+    pub author: String,
+
+    /// Featured on homepage
+    #[serde(default)]
+    pub featured: bool,
+
+    /// Lucide icon name (sparkles, rocket, code, file-text, brain, zap, package, star)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
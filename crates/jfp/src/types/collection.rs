@@ -0,0 +1,23 @@
+//! Collection types
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (collections): a named, ordered
+//! list of prompt ids, kept locally and optionally pushed to premium.
+
+use serde::{Deserialize, Serialize};
+
+/// A named, ordered list of prompt ids
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collection {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Member prompt ids, in the order they were added
+    pub prompt_ids: Vec<String>,
+    /// ISO 8601
+    pub created_at: String,
+    /// ISO 8601
+    pub updated_at: String,
+    /// When the collection was last pushed to premium (ISO 8601)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synced_at: Option<String>,
+}
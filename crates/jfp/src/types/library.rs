@@ -0,0 +1,47 @@
+//! Premium library types
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 7 (offline.ts): prompts synced
+//! from the user's premium library by `jfp sync`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A prompt from the user's premium library (`SyncedPrompt`)
+///
+/// Fields the server adds beyond these are kept, so the JSONL mirror is a
+/// faithful copy of what was synced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncedPrompt {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// When the user saved the prompt (ISO 8601)
+    pub saved_at: String,
+
+    /// Any other fields, in sorted key order
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Sync markers from `sync_meta` (see SYNC_STRATEGY.md)
+#[derive(Debug, Clone, Default)]
+pub struct LibrarySyncState {
+    /// Server timestamp of the last sync; the `since` of the next one
+    pub last_synced_at: Option<String>,
+
+    /// SHA-256 of the JSONL mirror as last written
+    pub jsonl_sha256: Option<String>,
+
+    pub record_count: usize,
+}
@@ -0,0 +1,22 @@
+//! Core data types shared across commands, storage and the registry
+//!
+//! Mirrors `packages/core/src/prompts/types.ts` so registry payloads produced
+//! by the TypeScript tooling deserialize without translation.
+
+mod bundle;
+mod collection;
+mod library;
+mod note;
+mod prompt;
+mod registry;
+mod search;
+mod workflow;
+
+pub use bundle::*;
+pub use collection::*;
+pub use library::*;
+pub use note::*;
+pub use prompt::*;
+pub use registry::*;
+pub use search::*;
+pub use workflow::*;
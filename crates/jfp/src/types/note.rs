@@ -0,0 +1,35 @@
+//! Personal note types
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 10 (notes): `Note` as returned by
+//! `/cli/notes/<promptId>`, plus what jfp tracks to upload it.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A personal note on a prompt
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+pub struct Note {
+    pub id: String,
+    pub prompt_id: String,
+    pub content: String,
+    /// ISO 8601
+    pub created_at: String,
+    /// ISO 8601
+    pub updated_at: String,
+
+    /// The server's id, once uploaded
+    #[serde(skip)]
+    pub remote_id: Option<String>,
+
+    /// Uploaded and unchanged since
+    #[serde(default)]
+    pub synced: bool,
+}
+
+/// A local change to a note that the server has not seen yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteChange {
+    Add,
+    Delete,
+}
+
@@ -0,0 +1,159 @@
+//! Prompt types
+//!
+//! From packages/core/src/prompts/types.ts:
+//! - Prompt extends PromptMeta with content, variables, whenToUse, tips, examples, changelog
+//! - PromptVariableType: text | multiline | select | file | path
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A prompt as stored in the registry and the local database
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Prompt {
+    /// Unique identifier (kebab-case, stable)
+    pub id: String,
+
+    /// Human-readable title
+    pub title: String,
+
+    /// One-line description for cards and search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Category for filtering (ideation, documentation, automation, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    /// Tags for search and filtering
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Author attribution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Twitter handle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub twitter: Option<String>,
+
+    /// Semantic version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Featured on homepage
+    #[serde(default)]
+    pub featured: bool,
+
+    /// Difficulty level (beginner, intermediate, advanced)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<String>,
+
+    /// Approximate input token count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_tokens: Option<u32>,
+
+    /// Creation date (ISO 8601)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    /// Last update date (ISO 8601)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+
+    /// The actual prompt content
+    pub content: String,
+
+    /// Variables for templated prompts ({{VARS}})
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<PromptVariable>,
+
+    /// When to use this prompt (for skills export)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when_to_use: Vec<String>,
+
+    /// Usage tips (for skills export)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tips: Vec<String>,
+
+    /// Example scenarios
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+
+    /// Changelog entries for updates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changelog: Vec<PromptChange>,
+}
+
+/// Template variable declared by a prompt
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+pub struct PromptVariable {
+    /// Variable name, UPPER_SNAKE_CASE (e.g. PROJECT_NAME)
+    pub name: String,
+
+    /// Human-readable label
+    pub label: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(rename = "type")]
+    pub kind: VariableType,
+
+    #[serde(default)]
+    pub required: bool,
+
+    /// Allowed values for `select` variables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+/// Input kind of a template variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableType {
+    Text,
+    Multiline,
+    Select,
+    File,
+    Path,
+}
+
+/// Changelog entry for a prompt
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+pub struct PromptChange {
+    pub version: String,
+    pub date: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub summary: String,
+}
+
+/// Compact prompt representation used in list and search output
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PromptSummary {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub featured: bool,
+}
+
+impl From<&Prompt> for PromptSummary {
+    fn from(p: &Prompt) -> Self {
+        Self {
+            id: p.id.clone(),
+            title: p.title.clone(),
+            description: p.description.clone(),
+            category: p.category.clone(),
+            tags: p.tags.clone(),
+            featured: p.featured,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Remote registry cache types
+//!
+//! From EXISTING_JFP_STRUCTURE.md section 6 (`RegistryMeta`): the validators
+//! and fetch time of the last download, kept in SQLite instead of
+//! `registry.meta.json`.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// The last successful remote registry fetch
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct RegistryCache {
+    /// Registry URL the prompts were fetched from
+    pub url: String,
+
+    /// `ETag` response header, sent back as `If-None-Match`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+
+    /// `Last-Modified` response header, sent back as `If-Modified-Since`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+
+    /// When the registry was last downloaded (RFC 3339)
+    pub fetched_at: String,
+
+    /// When the registry was last confirmed current, by a download or a 304
+    pub checked_at: String,
+
+    /// Prompts in the downloaded registry
+    pub prompt_count: usize,
+}
+
+/// What swapping in a remote registry changed
+#[derive(Debug, Default)]
+pub struct RemoteSwap {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+
+    /// Remote prompt ids skipped because a local prompt already has them
+    pub kept_local: Vec<String>,
+}
@@ -0,0 +1,88 @@
+//! Search types
+
+use super::Prompt;
+
+/// Marks the start of a highlighted match inside [`Snippet::text`]
+pub const HIGHLIGHT_START: char = '\u{2}';
+/// Marks the end of a highlighted match inside [`Snippet::text`]
+pub const HIGHLIGHT_END: char = '\u{3}';
+
+/// How much each field matters to a reader, used to order a result's
+/// matched fields
+///
+/// Title outranks tags, which outrank description, which outranks content;
+/// the ID comes first and notes count as much as a description. Ranking
+/// does not use these: it repeats fields exactly as the TS engine does
+/// (see `search::Bm25Index`), where tags and description both count twice,
+/// so both engines return results in the same order.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldPriority {
+    pub id: f64,
+    pub title: f64,
+    pub tags: f64,
+    pub description: f64,
+    pub content: f64,
+    pub notes: f64,
+}
+
+impl Default for FieldPriority {
+    fn default() -> Self {
+        Self {
+            id: 5.0,
+            title: 3.0,
+            tags: 2.5,
+            description: 2.0,
+            content: 1.0,
+            notes: 2.0,
+        }
+    }
+}
+
+impl FieldPriority {
+    /// Priority of a named field (`id`, `title`, `tags`, `description`,
+    /// `content`, `notes`)
+    pub fn for_field(&self, field: &str) -> f64 {
+        match field {
+            "id" => self.id,
+            "title" => self.title,
+            "tags" => self.tags,
+            "description" => self.description,
+            "notes" => self.notes,
+            _ => self.content,
+        }
+    }
+}
+
+/// A ranked search hit
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub prompt: Prompt,
+    /// BM25 score, higher is better
+    pub score: f64,
+    /// Fields containing a query term, highest weight first
+    pub matched_fields: Vec<&'static str>,
+    pub snippet: Option<Snippet>,
+}
+
+/// Excerpt of the field a match came from
+#[derive(Debug, Clone)]
+pub struct Snippet {
+    pub field: &'static str,
+    /// Excerpt with matches wrapped in [`HIGHLIGHT_START`] / [`HIGHLIGHT_END`]
+    pub text: String,
+}
+
+impl Snippet {
+    /// Render the excerpt with the given highlight markers
+    pub fn render(&self, start: &str, end: &str) -> String {
+        let mut out = String::with_capacity(self.text.len());
+        for c in self.text.chars() {
+            match c {
+                HIGHLIGHT_START => out.push_str(start),
+                HIGHLIGHT_END => out.push_str(end),
+                c => out.push(c),
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,77 @@
+//! Workflow types
+//!
+//! From packages/core/src/prompts/workflows.ts: an ordered chain of prompts
+//! with a handoff note for each step. [`WorkflowRun`] is the Rust side's
+//! saved progress through one run of a workflow.
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A workflow as stored in the registry and the local database
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workflow {
+    /// Unique identifier (kebab-case)
+    pub id: String,
+
+    /// Human-readable title
+    pub title: String,
+
+    /// One-line description
+    pub description: String,
+
+    /// Steps, in the order they run
+    pub steps: Vec<WorkflowStep>,
+
+    /// When to use this workflow
+    #[serde(default)]
+    pub when_to_use: Vec<String>,
+}
+
+/// One prompt in a workflow
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStep {
+    /// Step identifier, unique within the workflow
+    pub id: String,
+
+    /// Prompt rendered for this step
+    pub prompt_id: String,
+
+    /// Handoff note: what this step is for
+    pub note: String,
+}
+
+/// Saved progress through one run of a workflow
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowRun {
+    pub id: i64,
+    pub workflow_id: String,
+
+    /// Variables shared by every step of the run
+    pub variables: BTreeMap<String, String>,
+
+    pub started_at: String,
+    pub updated_at: String,
+
+    /// Set once every step has been rendered
+    pub completed_at: Option<String>,
+
+    /// Steps rendered so far, in run order
+    pub steps: Vec<CompletedStep>,
+}
+
+/// A workflow step that has been rendered in a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletedStep {
+    pub step_id: String,
+
+    /// Index of the step in the workflow when it was rendered
+    pub position: usize,
+
+    pub prompt_id: String,
+    pub rendered: String,
+    pub completed_at: String,
+}
@@ -0,0 +1,236 @@
+//! Premium login, logout and whoami against a stand-in OAuth server
+
+mod common;
+
+use std::io::{BufRead, BufReader, Read};
+use std::net::TcpStream;
+use std::process::Stdio;
+
+use chrono::{Duration, Utc};
+use common::TestHome;
+use common::premium::{
+    PremiumServer, credentials_path, jfp, run, stored_credentials, token_response, write_credentials,
+};
+use serde_json::{Value, json};
+
+#[test]
+fn device_code_login_saves_private_credentials() {
+    let home = TestHome::new();
+    let server = PremiumServer::start(|path, _| match path {
+        "/api/cli/device-code" => (
+            200,
+            json!({
+                "device_code": "device-1",
+                "user_code": "ABCD1234",
+                "verification_url": "https://example.com/device",
+                "expires_in": 600,
+                "interval": 1
+            }),
+        ),
+        "/api/cli/device-token" => (200, token_response("new-token", Some("refresh-1"))),
+        _ => (404, json!({ "error": "not_found" })),
+    });
+
+    let (ok, out, err) = run(jfp(&home, &server, &["login", "--remote"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["status"], "pending");
+    assert_eq!(out[0]["user_code"], "ABCD-1234");
+    assert_eq!(out[0]["verification_url"], "https://example.com/device");
+    assert_eq!(out[1], json!({ "authenticated": true, "email": "ada@example.com", "tier": "premium" }));
+
+    assert_eq!(server.seen("/api/cli/device-code")[0].body, json!({ "client_id": "jfp-cli" }));
+    assert_eq!(
+        server.seen("/api/cli/device-token")[0].body,
+        json!({ "device_code": "device-1", "client_id": "jfp-cli" })
+    );
+
+    let stored = stored_credentials(&home);
+    assert_eq!(stored["access_token"], "new-token");
+    assert_eq!(stored["refresh_token"], "refresh-1");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(credentials_path(&home)).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let (ok, out, _) = run(jfp(&home, &server, &["whoami"]));
+    assert!(ok);
+    assert_eq!(out[0]["email"], "ada@example.com");
+    assert_eq!(out[0]["tier"], "premium");
+    assert_eq!(out[0]["source"], "credentials_file");
+    assert_eq!(out[0]["refreshed"], false);
+
+    // Logging in again needs a logout first
+    let (ok, _, err) = run(jfp(&home, &server, &["login", "--remote"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "already_logged_in");
+}
+
+#[test]
+fn device_code_login_stops_when_access_is_denied() {
+    let home = TestHome::new();
+    let server = PremiumServer::start(|path, nth| match (path, nth) {
+        ("/api/cli/device-code", _) => (
+            200,
+            json!({ "device_code": "device-1", "user_code": "WXYZ-0000", "verification_url": "https://example.com/device" }),
+        ),
+        ("/api/cli/device-token", 0) => (400, json!({ "error": "authorization_pending" })),
+        _ => (400, json!({ "error": "access_denied" })),
+    });
+
+    let (ok, _, err) = run(jfp(&home, &server, &["login", "--remote"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "access_denied");
+    assert_eq!(server.seen("/api/cli/device-token").len(), 2);
+    assert!(!credentials_path(&home).exists());
+}
+
+#[test]
+fn browser_login_receives_the_callback() {
+    let home = TestHome::new();
+    let server = PremiumServer::start(|_, _| (404, json!({})));
+    let mut command = jfp(&home, &server, &["login", "--no-browser", "--timeout", "30"]);
+    let mut child = command.stdout(Stdio::piped()).spawn().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    let mut pending = String::new();
+    stdout.read_line(&mut pending).unwrap();
+    let pending: Value = serde_json::from_str(&pending).unwrap();
+    let port = pending["port"].as_u64().unwrap();
+    let auth_url = pending["auth_url"].as_str().unwrap();
+    let (url, state) = auth_url.split_once("&state=").unwrap();
+    assert_eq!(url, format!("{}/cli/auth?port={}&redirect=local", server.url(), port));
+    assert_eq!(state.len(), 32);
+
+    let callback = |query: &str| {
+        let mut stream = TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+        let request = format!("GET /callback?{} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", query);
+        std::io::Write::write_all(&mut stream, request.as_bytes()).unwrap();
+        let mut page = String::new();
+        stream.read_to_string(&mut page).unwrap();
+        page
+    };
+
+    // A callback some other page forged, without the nonce, is turned away
+    let forged = "token=attacker-token&email=mallory%40example.com&tier=free&user_id=user-666";
+    assert!(callback(forged).starts_with("HTTP/1.1 403"));
+    assert!(callback(&format!("{}&state=0123", forged)).starts_with("HTTP/1.1 403"));
+
+    let granted = "token=browser-token&email=grace%40example.com&tier=free&user_id=user-2";
+    let page = callback(&format!("{}&state={}", granted, state));
+    assert!(page.starts_with("HTTP/1.1 200"));
+    assert!(page.contains("Signed in as grace@example.com"));
+
+    let mut rest = String::new();
+    stdout.read_to_string(&mut rest).unwrap();
+    assert!(child.wait().unwrap().success());
+    let done: Value = serde_json::from_str(&rest).unwrap();
+    assert_eq!(done, json!({ "authenticated": true, "email": "grace@example.com", "tier": "free" }));
+
+    let stored = stored_credentials(&home);
+    assert_eq!(stored["access_token"], "browser-token");
+    assert!(stored.get("refresh_token").is_none());
+    // No expires_at in the callback: a day from now
+    assert!(stored["expires_at"].as_str().unwrap() > Utc::now().to_rfc3339().as_str());
+}
+
+#[test]
+fn expired_token_is_refreshed_automatically() {
+    let home = TestHome::new();
+    let server = PremiumServer::start(|path, _| match path {
+        "/api/cli/token/refresh" => (200, token_response("fresh-token", None)),
+        _ => (404, json!({})),
+    });
+    write_credentials(&home, Duration::minutes(-10), Some("refresh-1"));
+
+    let (ok, out, err) = run(jfp(&home, &server, &["whoami"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["refreshed"], true);
+    assert_eq!(out[0]["expired"], false);
+    assert_eq!(
+        server.seen("/api/cli/token/refresh")[0].body,
+        json!({ "refresh_token": "refresh-1", "client_id": "jfp-cli" })
+    );
+
+    // The new token is saved; the refresh token is kept when not rotated
+    let stored = stored_credentials(&home);
+    assert_eq!(stored["access_token"], "fresh-token");
+    assert_eq!(stored["refresh_token"], "refresh-1");
+
+    // Within the 5-minute buffer counts as expired too
+    write_credentials(&home, Duration::minutes(2), None);
+    let (ok, _, err) = run(jfp(&home, &server, &["whoami"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "session_expired");
+    assert_eq!(err["email"], "ada@example.com");
+}
+
+#[test]
+fn rejected_refresh_ends_the_session() {
+    let home = TestHome::new();
+    let server = PremiumServer::start(|_, _| (401, json!({ "error": "invalid_grant" })));
+    write_credentials(&home, Duration::minutes(-10), Some("revoked"));
+
+    let (ok, _, err) = run(jfp(&home, &server, &["whoami"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "session_expired");
+    assert_eq!(err["authenticated"], false);
+    assert_eq!(stored_credentials(&home)["access_token"], "old-token");
+}
+
+#[test]
+fn logout_revokes_and_removes_credentials() {
+    let home = TestHome::new();
+    let server = PremiumServer::start(|path, _| match path {
+        "/api/cli/revoke" => (200, json!({ "revoked": true })),
+        _ => (404, json!({})),
+    });
+    write_credentials(&home, Duration::hours(1), Some("refresh-1"));
+    let search = home.json(&["search", "ideas"]);
+    assert_eq!(search["authenticated"], true);
+
+    let (ok, out, err) = run(jfp(&home, &server, &["logout", "--revoke"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["logged_out"], true);
+    assert_eq!(out[0]["revoked"], true);
+    assert_eq!(out[0]["email"], "ada@example.com");
+    assert_eq!(
+        server.seen("/api/cli/revoke")[0].authorization.as_deref(),
+        Some("Bearer old-token")
+    );
+    assert!(!credentials_path(&home).exists());
+
+    let (ok, out, _) = run(jfp(&home, &server, &["logout"]));
+    assert!(ok);
+    assert_eq!(out[0]["message"], "Not logged in (nothing to do)");
+
+    let (ok, _, err) = run(jfp(&home, &server, &["whoami"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "not_authenticated");
+    assert_eq!(home.json(&["search", "ideas"])["authenticated"], false);
+}
+
+#[test]
+fn environment_token_bypasses_credentials() {
+    let home = TestHome::new();
+    let server = PremiumServer::start(|_, _| (404, json!({})));
+    std::fs::create_dir_all(credentials_path(&home).parent().unwrap()).unwrap();
+    std::fs::write(credentials_path(&home), "{ not json").unwrap();
+
+    let (ok, _, err) = run(jfp(&home, &server, &["whoami"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "not_authenticated");
+
+    let mut whoami = jfp(&home, &server, &["whoami"]);
+    whoami.env("JFP_TOKEN", "ci-token");
+    let (ok, out, _) = run(whoami);
+    assert!(ok);
+    assert_eq!(out[0]["source"], "environment");
+
+    let mut logout = jfp(&home, &server, &["logout"]);
+    logout.env("JFP_TOKEN", "ci-token");
+    let (ok, _, err) = run(logout);
+    assert!(!ok);
+    assert_eq!(err["error"], "env_token");
+}
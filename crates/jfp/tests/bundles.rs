@@ -0,0 +1,103 @@
+//! Bundles: listing, details and bundle skill export
+
+mod common;
+
+use std::fs;
+
+use common::TestHome;
+use serde_json::{Value, json};
+
+#[test]
+fn bundles_lists_featured_first_with_prompt_counts() {
+    let home = TestHome::new();
+    let out = home.json(&["bundles"]);
+    assert_eq!(out["count"], 2);
+    assert_eq!(out["bundles"][0]["id"], "getting-started");
+    assert_eq!(out["bundles"][0]["featured"], true);
+    assert_eq!(out["bundles"][0]["prompt_count"], 3);
+    assert_eq!(out["bundles"][1]["id"], "jeffrey-essentials");
+}
+
+#[test]
+fn bundle_resolves_members_in_order_and_reports_missing_ones() {
+    let home = TestHome::new();
+    let out = home.json(&["bundle", "getting-started"]);
+    assert_eq!(out["promptIds"], json!(["idea-wizard", "readme-reviser", "robot-mode-maker"]));
+    assert_eq!(out["icon"], "rocket");
+    let ids: Vec<&str> = out["prompts"].as_array().unwrap().iter().map(|p| p["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["idea-wizard", "readme-reviser", "robot-mode-maker"]);
+    assert!(out.get("missing").is_none());
+
+    let conn = rusqlite::Connection::open(home.db_path()).unwrap();
+    conn.execute("DELETE FROM prompts WHERE id = 'readme-reviser'", []).unwrap();
+    let out = home.json(&["bundle", "getting-started"]);
+    assert_eq!(out["prompts"].as_array().unwrap().len(), 2);
+    assert_eq!(out["missing"], json!(["readme-reviser"]));
+}
+
+#[test]
+fn unknown_bundle_lists_available_ids() {
+    let home = TestHome::new();
+    let output = home.run(&["--json", "bundle", "nope"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_found");
+    assert_eq!(err["available"], json!(["getting-started", "jeffrey-essentials"]));
+}
+
+#[test]
+fn bundle_exports_as_one_skill_directory() {
+    let home = TestHome::new();
+    let out = home.path().join("skills");
+    let manifest = home.json(&[
+        "export", "--bundle", "getting-started", "--format", "skill", "--output-dir", out.to_str().unwrap(),
+    ]);
+
+    let file = out.join("getting-started").join("SKILL.md");
+    assert_eq!(manifest["exported"][0]["file"], file.to_str().unwrap());
+    assert_eq!(manifest["exported"][0]["ids"], json!(["idea-wizard", "readme-reviser", "robot-mode-maker"]));
+    assert_eq!(fs::read_dir(&out).unwrap().count(), 1);
+
+    let skill = fs::read_to_string(file).unwrap();
+    assert!(skill.starts_with(
+        "---\nname: getting-started\n\
+         description: \"Essential prompts for any project - ideation, documentation, and automation\"\n\
+         version: 1.0.0\nauthor: Jeffrey Emanuel\ntype: bundle\n\
+         prompts: [\"idea-wizard\", \"readme-reviser\", \"robot-mode-maker\"]\n\
+         source: https://jeffreysprompts.com/bundles/getting-started\nx_jfp_generated: true\n---\n\
+         # Getting Started\n"
+    ));
+    assert!(skill.contains("## Workflow\n\n1. **Start with Idea Wizard**"));
+    assert!(skill.contains("## When to Use This Bundle\n\n- When starting a new project\n"));
+    let positions: Vec<usize> = ["### The Idea Wizard", "### The README Reviser", "### The Robot-Mode Maker"]
+        .iter()
+        .map(|heading| skill.find(heading).expect("member prompt included"))
+        .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    assert!(skill.contains("Come up with your very best ideas for improving this project."));
+    assert!(skill.ends_with("*Bundle from [JeffreysPrompts.com](https://jeffreysprompts.com/bundles/getting-started)*\n"));
+}
+
+#[test]
+fn bundle_export_in_other_formats_writes_member_files() {
+    let home = TestHome::new();
+    let out = home.path().join("md");
+    let manifest = home.json(&["export", "--bundle", "jeffrey-essentials", "--output-dir", out.to_str().unwrap()]);
+    assert_eq!(manifest["exported"].as_array().unwrap().len(), 3);
+    assert!(out.join("robot-mode-maker.md").is_file());
+
+    let output = home.run(&["--json", "export", "--bundle", "nope"]);
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_found");
+}
+
+#[test]
+fn registry_export_includes_bundles() {
+    let home = TestHome::new();
+    let output = home.run(&["export", "all", "--format", "registry", "--stdout"]);
+    assert!(output.status.success());
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<&str> = payload["bundles"].as_array().unwrap().iter().map(|b| b["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["getting-started", "jeffrey-essentials"]);
+    assert_eq!(payload["bundles"][0]["promptIds"][0], "idea-wizard");
+}
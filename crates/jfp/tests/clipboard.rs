@@ -0,0 +1,195 @@
+//! Clipboard backends for `jfp copy` and `jfp random --copy`
+//!
+//! Clipboard tools are replaced by shell stubs on a private `PATH` that
+//! record what they were given, so no real clipboard is touched.
+
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::time::{Duration, Instant};
+
+use common::TestHome;
+use serde_json::{Value, json};
+
+/// Directory of fake clipboard tools
+struct Stubs {
+    dir: PathBuf,
+}
+
+impl Stubs {
+    fn new(home: &TestHome) -> Self {
+        let dir = home.path().join("bin");
+        fs::create_dir_all(&dir).unwrap();
+        Self { dir }
+    }
+
+    /// A tool that stores its stdin in `<name>.out`
+    fn recorder(&self, name: &str) -> &Self {
+        let out = self.output_path(name);
+        self.script(name, &format!("/bin/cat > '{}'", out.display()))
+    }
+
+    /// A tool that exits with an error
+    fn broken(&self, name: &str) -> &Self {
+        self.script(name, "exit 1")
+    }
+
+    fn script(&self, name: &str, body: &str) -> &Self {
+        let path = self.dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        self
+    }
+
+    fn output_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.out", name))
+    }
+
+    fn copied(&self, name: &str) -> Option<String> {
+        fs::read_to_string(self.output_path(name)).ok()
+    }
+
+    fn path(&self) -> &Path {
+        &self.dir
+    }
+}
+
+fn run(home: &TestHome, stubs: &Stubs, args: &[&str]) -> Output {
+    home.command()
+        .env("PATH", stubs.path())
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn add_template(home: &TestHome) {
+    home.add_prompt(json!({
+        "id": "lang-review",
+        "title": "Language Review",
+        "category": "testing",
+        "tags": ["code-language"],
+        "content": "Review this {{LANGUAGE}} code.",
+        "variables": [{ "name": "LANGUAGE", "label": "Language", "type": "text", "required": true }],
+    }));
+}
+
+#[test]
+fn copy_uses_the_first_available_tool() {
+    let home = TestHome::new();
+    add_template(&home);
+    let stubs = Stubs::new(&home);
+    stubs.recorder("xclip").recorder("xsel");
+
+    let output = run(&home, &stubs, &["--json", "copy", "lang-review", "--LANGUAGE=Rust"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(out["success"], true);
+    assert_eq!(out["backend"], "xclip");
+    assert_eq!(out["characters"], 22);
+    assert!(out.get("content").is_none());
+    assert_eq!(stubs.copied("xclip").as_deref(), Some("Review this Rust code."));
+    assert_eq!(stubs.copied("xsel"), None);
+}
+
+#[test]
+fn copy_falls_through_a_failing_tool() {
+    let home = TestHome::new();
+    let stubs = Stubs::new(&home);
+    stubs.broken("wl-copy").recorder("xclip");
+
+    let out: Value =
+        serde_json::from_slice(&run(&home, &stubs, &["--json", "copy", "idea-wizard"]).stdout).unwrap();
+    assert_eq!(out["backend"], "xclip");
+    assert!(stubs.copied("xclip").unwrap().contains("Come up with your very best ideas"));
+
+    stubs.recorder("wl-copy");
+    let out: Value =
+        serde_json::from_slice(&run(&home, &stubs, &["--json", "copy", "idea-wizard"]).stdout).unwrap();
+    assert_eq!(out["backend"], "wl-copy");
+}
+
+#[test]
+fn copy_gives_up_on_a_tool_that_stops_reading() {
+    let home = TestHome::new();
+    // More than a pipe buffer, so writing blocks until the tool reads
+    home.add_prompt(json!({
+        "id": "huge",
+        "title": "Huge",
+        "category": "testing",
+        "tags": [],
+        "content": "x".repeat(200_000),
+    }));
+    let stubs = Stubs::new(&home);
+    stubs.script("wl-copy", "exec /bin/sleep 30").recorder("xclip");
+
+    let started = Instant::now();
+    let output = run(&home, &stubs, &["--json", "copy", "huge"]);
+    assert!(started.elapsed() < Duration::from_secs(15), "{:?}", started.elapsed());
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(out["backend"], "xclip");
+    assert_eq!(stubs.copied("xclip").unwrap().len(), 200_000);
+}
+
+#[test]
+fn copy_without_a_clipboard_fails_unless_printing() {
+    let home = TestHome::new();
+    let stubs = Stubs::new(&home);
+
+    let output = run(&home, &stubs, &["--json", "copy", "idea-wizard"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "clipboard_failed");
+    assert!(err["fallback"].as_str().unwrap().contains("Come up with your very best ideas"));
+
+    let output = run(&home, &stubs, &["--json", "copy", "idea-wizard", "--print"]);
+    assert!(output.status.success());
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(out["backend"], "stdout");
+    assert_eq!(out["content"], err["fallback"]);
+}
+
+#[test]
+fn copy_reports_missing_variables_before_copying() {
+    let home = TestHome::new();
+    add_template(&home);
+    let stubs = Stubs::new(&home);
+    stubs.recorder("xclip");
+
+    let output = run(&home, &stubs, &["--json", "copy", "lang-review"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "missing_variables");
+    assert_eq!(stubs.copied("xclip"), None);
+}
+
+#[test]
+fn random_copy_reports_the_backend() {
+    let home = TestHome::new();
+    add_template(&home);
+    let stubs = Stubs::new(&home);
+    stubs.recorder("xsel");
+
+    let output = run(&home, &stubs, &["--json", "random", "--tag", "code-language", "--copy"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(out["prompt"]["id"], "lang-review");
+    assert_eq!(out["copied"], true);
+    assert_eq!(out["backend"], "xsel");
+    assert_eq!(stubs.copied("xsel").as_deref(), Some("Review this {{LANGUAGE}} code."));
+}
+
+#[test]
+fn random_with_no_matches_is_an_error() {
+    let home = TestHome::new();
+    let output = home.run(&["--json", "random", "--category", "no-such-category"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "no_prompts");
+    assert_eq!(err["message"], "No prompts found in category: no-such-category");
+}
@@ -0,0 +1,162 @@
+//! Local collections: editing, filtering, export and push to premium
+
+mod common;
+
+use std::fs;
+
+use chrono::Duration;
+use common::TestHome;
+use common::premium::{PremiumServer, jfp, run, write_credentials};
+use serde_json::{Value, json};
+
+fn ids(values: &Value) -> Vec<&str> {
+    values.as_array().unwrap().iter().map(|v| v["id"].as_str().unwrap()).collect()
+}
+
+fn error(home: &TestHome, args: &[&str]) -> Value {
+    let output = home.run(&[&["--json"], args].concat());
+    assert!(!output.status.success());
+    serde_json::from_slice(&output.stderr).unwrap()
+}
+
+#[test]
+fn collections_work_offline_and_keep_their_order() {
+    let home = TestHome::new();
+
+    let created = home.json(&["collections", "create", "Favorites", "--description", "  Daily drivers "]);
+    assert_eq!(created["created"], true);
+    assert_eq!(created["collection"]["name"], "Favorites");
+    assert_eq!(created["collection"]["description"], "Daily drivers");
+    assert_eq!(created["collection"]["prompt_count"], 0);
+
+    let added = home.json(&["collections", "add", "favorites", "readme-reviser", "idea-wizard"]);
+    assert_eq!(added["collection"], "Favorites");
+    assert_eq!(added["added"], json!(["readme-reviser", "idea-wizard"]));
+    assert_eq!(added["count"], 2);
+
+    let again = home.json(&["collections", "add", "Favorites", "idea-wizard", "bug-hunter"]);
+    assert_eq!(again["added"], json!(["bug-hunter"]));
+    assert_eq!(again["already_present"], json!(["idea-wizard"]));
+    assert_eq!(again["count"], 3);
+
+    let shown = home.json(&["collections", "show", "Favorites"]);
+    assert_eq!(ids(&shown["items"]), ["readme-reviser", "idea-wizard", "bug-hunter"]);
+    assert_eq!(shown["count"], 3);
+    assert!(shown["items"][0]["title"].is_string());
+    assert!(shown.get("missing").is_none());
+
+    let removed = home.json(&["collections", "remove", "Favorites", "idea-wizard", "not-a-member"]);
+    assert_eq!(removed["removed"], json!(["idea-wizard"]));
+    assert_eq!(removed["not_in_collection"], json!(["not-a-member"]));
+    assert_eq!(removed["count"], 2);
+
+    let listed = home.json(&["collections"]);
+    assert_eq!(listed["count"], 1);
+    assert_eq!(listed["collections"][0]["name"], "Favorites");
+    assert_eq!(listed["collections"][0]["prompt_count"], 2);
+    assert!(listed["collections"][0].get("synced_at").is_none());
+}
+
+#[test]
+fn list_and_search_filter_by_collection() {
+    let home = TestHome::new();
+    home.json(&["collections", "create", "review"]);
+    home.json(&["collections", "add", "review", "readme-reviser", "bug-hunter"]);
+
+    let listed = home.json(&["list", "--collection", "review"]);
+    assert_eq!(ids(&listed["prompts"]), ["readme-reviser", "bug-hunter"]);
+    assert_eq!(listed["count"], 2);
+
+    let everywhere = home.json(&["search", "ideas"]);
+    assert!(ids(&everywhere["results"]).contains(&"idea-wizard"));
+    let within = home.json(&["search", "ideas", "--collection", "review"]);
+    assert!(!ids(&within["results"]).contains(&"idea-wizard"));
+    let found = home.json(&["search", "bugs", "--collection", "review"]);
+    assert_eq!(ids(&found["results"]), ["bug-hunter"]);
+
+    let err = error(&home, &["list", "--collection", "nope"]);
+    assert_eq!(err["error"], "not_found");
+    assert_eq!(err["available"], json!(["review"]));
+    assert_eq!(error(&home, &["search", "ideas", "--collection", "nope"])["error"], "not_found");
+}
+
+#[test]
+fn export_reuses_the_markdown_and_skill_writers() {
+    let home = TestHome::new();
+    home.json(&["collections", "create", "kit"]);
+    home.json(&["collections", "add", "kit", "idea-wizard", "readme-reviser"]);
+    let out = home.path().join("out");
+    let dir = out.to_str().unwrap();
+
+    let manifest = home.json(&["collections", "export", "kit", "--output-dir", dir]);
+    assert_eq!(manifest["success"], true);
+    assert_eq!(manifest["format"], "md");
+    assert_eq!(manifest["exported"].as_array().unwrap().len(), 2);
+    let direct = home.path().join("direct");
+    home.json(&["export", "idea-wizard", "--output-dir", direct.to_str().unwrap()]);
+    assert_eq!(
+        fs::read_to_string(out.join("idea-wizard.md")).unwrap(),
+        fs::read_to_string(direct.join("idea-wizard.md")).unwrap()
+    );
+
+    home.json(&["collections", "export", "kit", "--format", "skill", "--output-dir", dir]);
+    assert!(out.join("readme-reviser/SKILL.md").is_file());
+
+    let output = home.run(&["--json", "collections", "export", "kit", "--stdout"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&fs::read_to_string(out.join("idea-wizard.md")).unwrap()));
+
+    let err = error(&home, &["collections", "export", "kit", "--format", "json"]);
+    assert_eq!(err["error"], "invalid_format");
+}
+
+#[test]
+fn collection_errors_are_reported() {
+    let home = TestHome::new();
+    assert_eq!(error(&home, &["collections", "create", "  "])["error"], "missing_argument");
+
+    home.json(&["collections", "create", "mine"]);
+    assert_eq!(error(&home, &["collections", "create", "MINE"])["error"], "already_exists");
+    assert_eq!(error(&home, &["collections", "show", "other"])["error"], "not_found");
+
+    let err = error(&home, &["collections", "add", "mine", "idea-wizard", "no-such-prompt"]);
+    assert_eq!(err["error"], "not_found");
+    assert_eq!(err["missing"], json!(["no-such-prompt"]));
+    // Nothing is added when any id is unknown
+    assert_eq!(home.json(&["collections", "show", "mine"])["count"], 0);
+
+    // Signing in is only needed to push to premium
+    assert_eq!(error(&home, &["collections", "sync"])["error"], "not_authenticated");
+}
+
+#[test]
+fn sync_pushes_collections_to_premium() {
+    let home = TestHome::new();
+    home.json(&["collections", "create", "My Kit", "--description", "Shared"]);
+    home.json(&["collections", "add", "My Kit", "idea-wizard", "bug-hunter"]);
+    write_credentials(&home, Duration::hours(1), None);
+
+    let members = "/api/cli/collections/My%20Kit/prompts";
+    let server = PremiumServer::start(move |path, nth| match path {
+        "/api/cli/collections" => (409, json!({ "error": "already_exists" })),
+        p if p == members && nth == 0 => (200, json!({ "success": true })),
+        p if p == members => (409, json!({ "error": "already_in_collection" })),
+        _ => (404, json!({})),
+    });
+
+    let (ok, out, err) = run(jfp(&home, &server, &["collections", "sync"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["synced"], json!(["My Kit"]));
+    assert_eq!(out[0]["failed"], json!([]));
+
+    let created = &server.seen("/api/cli/collections")[0];
+    assert_eq!(created.method, "POST");
+    assert_eq!(created.body, json!({ "name": "My Kit", "description": "Shared" }));
+    assert_eq!(created.authorization.as_deref(), Some("Bearer old-token"));
+    let added: Vec<Value> = server.seen(members).into_iter().map(|s| s.body).collect();
+    assert_eq!(added, [json!({ "prompt_id": "idea-wizard" }), json!({ "prompt_id": "bug-hunter" })]);
+
+    let shown = home.json(&["collections", "show", "my kit"]);
+    assert!(shown["collection"]["synced_at"].is_string());
+}
@@ -0,0 +1,104 @@
+//! Shared helpers for CLI integration tests
+//!
+//! Every test runs the real `jfp` binary against its own temporary
+//! `JFP_HOME`, so nothing touches the developer's config or database.
+
+#![allow(dead_code)]
+
+pub mod premium;
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use tempfile::TempDir;
+
+/// Isolated jfp home directory for a single test
+pub struct TestHome {
+    dir: TempDir,
+}
+
+impl TestHome {
+    pub fn new() -> Self {
+        Self {
+            dir: TempDir::new().expect("create temp JFP_HOME"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn db_path(&self) -> PathBuf {
+        self.dir.path().join(".config").join("jfp").join("jfp.db")
+    }
+
+    /// Build a `jfp` command bound to this home
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_jfp"));
+        cmd.env("JFP_HOME", self.dir.path())
+            .env_remove("NO_COLOR")
+            .env_remove("JFP_NO_COLOR")
+            // Never reach the real registry or premium site
+            .env_remove("JFP_TOKEN")
+            .env_remove("JFP_REGISTRY_URL")
+            .env_remove("JFP_PREMIUM_URL")
+            .env_remove("JFP_PREMIUM_API_URL")
+            .env_remove("JFP_BUSY_TIMEOUT")
+            // Keep clipboard probing off the OSC 52 path in CI shells
+            .env_remove("SSH_TTY")
+            .env_remove("SSH_CONNECTION")
+            .env_remove("SSH_CLIENT")
+            .env_remove("TMUX");
+        cmd
+    }
+
+    /// Run `jfp` with the given arguments
+    pub fn run(&self, args: &[&str]) -> Output {
+        self.command().args(args).output().expect("run jfp")
+    }
+
+    /// Store an extra prompt (registry JSON shape) in the library
+    ///
+    /// Seeds the database first so the bundled prompts are still present.
+    pub fn add_prompt(&self, prompt: serde_json::Value) {
+        self.json(&["list"]);
+        let conn = rusqlite::Connection::open(self.db_path()).expect("open test database");
+        let tags: Vec<&str> = prompt["tags"]
+            .as_array()
+            .map(|tags| tags.iter().filter_map(|t| t.as_str()).collect())
+            .unwrap_or_default();
+        conn.execute(
+            "INSERT INTO prompts (id, title, description, category, tags_text, featured, content, data, stored_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?7, datetime('now'))",
+            rusqlite::params![
+                prompt["id"].as_str(),
+                prompt["title"].as_str(),
+                prompt["description"].as_str(),
+                prompt["category"].as_str(),
+                tags.join(" "),
+                prompt["content"].as_str(),
+                prompt.to_string(),
+            ],
+        )
+        .expect("insert test prompt");
+        for tag in tags {
+            conn.execute(
+                "INSERT OR IGNORE INTO prompt_tags (prompt_id, tag) VALUES (?1, ?2)",
+                rusqlite::params![prompt["id"].as_str(), tag],
+            )
+            .expect("insert test prompt tag");
+        }
+    }
+
+    /// Run `jfp --json ...` and parse stdout, asserting success
+    pub fn json(&self, args: &[&str]) -> serde_json::Value {
+        let output = self.run(&[&["--json"], args].concat());
+        assert!(
+            output.status.success(),
+            "jfp {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).expect("stdout is JSON")
+    }
+}
@@ -0,0 +1,150 @@
+//! Stand-in for the premium site and API
+//!
+//! Serves canned JSON so auth, sync and other premium commands can be
+//! tested without the network, and records every request it receives.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use chrono::{Duration, Utc};
+use serde_json::{Value, json};
+use tiny_http::{Header, Response, Server};
+
+use super::TestHome;
+
+/// A request the stand-in received
+#[derive(Clone, Debug)]
+pub struct Seen {
+    pub method: String,
+    pub path: String,
+    /// Raw query string, without the `?`
+    pub query: String,
+    pub authorization: Option<String>,
+    pub body: Value,
+}
+
+type Route = Box<dyn Fn(&str, usize) -> (u16, Value) + Send>;
+
+/// Endpoints answered by `route(path, nth call to that path)`
+pub struct PremiumServer {
+    server: Arc<Server>,
+    seen: Arc<Mutex<Vec<Seen>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PremiumServer {
+    pub fn start(route: impl Fn(&str, usize) -> (u16, Value) + Send + 'static) -> Self {
+        let route: Route = Box::new(route);
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let seen = Arc::new(Mutex::new(Vec::<Seen>::new()));
+        let thread = {
+            let server = Arc::clone(&server);
+            let seen = Arc::clone(&seen);
+            std::thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let mut body = String::new();
+                    request.as_reader().read_to_string(&mut body).unwrap();
+                    let url = request.url().to_string();
+                    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+                    let authorization = request
+                        .headers()
+                        .iter()
+                        .find(|h| h.field.equiv("Authorization"))
+                        .map(|h| h.value.to_string());
+
+                    let nth = {
+                        let mut seen = seen.lock().unwrap();
+                        let nth = seen.iter().filter(|s| s.path == path).count();
+                        seen.push(Seen {
+                            method: request.method().to_string(),
+                            path: path.to_string(),
+                            query: query.to_string(),
+                            authorization,
+                            body: serde_json::from_str(&body).unwrap_or(Value::Null),
+                        });
+                        nth
+                    };
+                    let (status, reply) = route(path, nth);
+                    let response = Response::from_string(reply.to_string())
+                        .with_status_code(status)
+                        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+                    let _ = request.respond(response);
+                }
+            })
+        };
+        Self {
+            server,
+            seen,
+            thread: Some(thread),
+        }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.server.server_addr().to_ip().unwrap().port())
+    }
+
+    /// Requests to `path`, oldest first
+    pub fn seen(&self, path: &str) -> Vec<Seen> {
+        self.seen.lock().unwrap().iter().filter(|s| s.path == path).cloned().collect()
+    }
+}
+
+impl Drop for PremiumServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+pub fn token_response(access_token: &str, refresh_token: Option<&str>) -> Value {
+    let mut response = json!({
+        "access_token": access_token,
+        "expires_at": (Utc::now() + Duration::hours(1)).to_rfc3339(),
+        "email": "ada@example.com",
+        "tier": "premium",
+        "user_id": "user-1"
+    });
+    if let Some(refresh_token) = refresh_token {
+        response["refresh_token"] = json!(refresh_token);
+    }
+    response
+}
+
+pub fn credentials_path(home: &TestHome) -> PathBuf {
+    home.path().join(".config").join("jfp").join("credentials.json")
+}
+
+/// Sign `home` in as the premium user `old-token`, expiring in `expires_in`
+pub fn write_credentials(home: &TestHome, expires_in: Duration, refresh_token: Option<&str>) {
+    let mut credentials = token_response("old-token", refresh_token);
+    credentials["expires_at"] = json!((Utc::now() + expires_in).to_rfc3339());
+    let path = credentials_path(home);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, credentials.to_string()).unwrap();
+}
+
+pub fn stored_credentials(home: &TestHome) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(credentials_path(home)).unwrap()).unwrap()
+}
+
+/// `jfp --json <args>` against `server`
+pub fn jfp(home: &TestHome, server: &PremiumServer, args: &[&str]) -> Command {
+    let mut command = home.command();
+    command.env("JFP_PREMIUM_URL", server.url()).arg("--json").args(args);
+    command
+}
+
+/// Every JSON document the command printed on stdout, and its stderr JSON on failure
+pub fn run(mut command: Command) -> (bool, Vec<Value>, Value) {
+    let output = command.output().unwrap();
+    let stdout = serde_json::Deserializer::from_slice(&output.stdout)
+        .into_iter::<Value>()
+        .collect::<Result<_, _>>()
+        .expect("stdout is JSON");
+    let stderr = serde_json::from_slice(&output.stderr).unwrap_or(Value::Null);
+    (output.status.success(), stdout, stderr)
+}
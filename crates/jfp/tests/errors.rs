@@ -0,0 +1,170 @@
+//! The error contract: every failure is one JSON object on stderr with a
+//! stable `error` code, nothing on stdout, and an exit code per category
+
+mod common;
+
+use std::fs;
+use std::net::TcpListener;
+use std::process::Output;
+
+use chrono::Duration;
+use common::TestHome;
+use common::premium::write_credentials;
+use serde_json::{Value, json};
+
+const NOT_FOUND: i32 = 1;
+const INVALID_ARGUMENT: i32 = 2;
+const NETWORK: i32 = 4;
+const PERMISSION_DENIED: i32 = 5;
+const DATABASE: i32 = 6;
+const CONFLICT: i32 = 7;
+
+/// Assert `output` is a failure reported by the contract; returns the payload
+fn contract(output: Output, code: &str, exit: i32) -> Value {
+    assert_eq!(output.status.code(), Some(exit), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.stdout.is_empty(), "stdout: {}", String::from_utf8_lossy(&output.stdout));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.lines().count(), 1, "one JSON line: {}", stderr);
+    let err: Value = serde_json::from_str(&stderr).unwrap();
+    assert_eq!(err["error"], code);
+    err
+}
+
+fn fails(home: &TestHome, args: &[&str], code: &str, exit: i32) -> Value {
+    contract(home.run(&[&["--json"], args].concat()), code, exit)
+}
+
+#[test]
+fn show_errors_match_the_spec_exactly() {
+    let home = TestHome::new();
+    assert_eq!(fails(&home, &["show", "no-such-prompt"], "not_found", NOT_FOUND), json!({ "error": "not_found" }));
+    assert_eq!(fails(&home, &["show", " "], "invalid_id", INVALID_ARGUMENT), json!({ "error": "invalid_id" }));
+}
+
+#[test]
+fn messages_with_quotes_stay_valid_json() {
+    let home = TestHome::new();
+    let id = r#"say "hi" \ bye"#;
+    let err = fails(&home, &["render", id], "not_found", NOT_FOUND);
+    assert_eq!(err["message"], format!("Prompt not found: {}", id));
+
+    let err = fails(&home, &["collections", "show", r#"my "kit""#], "not_found", NOT_FOUND);
+    assert_eq!(err["message"], r#"Collection not found: my "kit""#);
+}
+
+#[test]
+fn lookups_report_not_found() {
+    let home = TestHome::new();
+    fails(&home, &["render", "no-such-prompt"], "not_found", NOT_FOUND);
+    fails(&home, &["copy", "no-such-prompt"], "not_found", NOT_FOUND);
+    fails(&home, &["open", "no-such-prompt"], "not_found", NOT_FOUND);
+    fails(&home, &["notes", "no-such-prompt"], "not_found", NOT_FOUND);
+    fails(&home, &["notes", "idea-wizard", "--delete", "note-missing"], "note_not_found", NOT_FOUND);
+    fails(&home, &["random", "--category", "no-such-category"], "no_prompts", NOT_FOUND);
+    assert!(fails(&home, &["bundle", "nope"], "not_found", NOT_FOUND)["available"].is_array());
+    assert!(fails(&home, &["workflow", "run", "nope"], "not_found", NOT_FOUND)["available"].is_array());
+    fails(&home, &["list", "--collection", "nope"], "not_found", NOT_FOUND);
+    let err = fails(&home, &["export", "no-such-prompt"], "not_found", NOT_FOUND);
+    assert_eq!(err["missing"], json!(["no-such-prompt"]));
+
+    assert!(fails(&home, &["workflow", "show", "nope"], "not_found", NOT_FOUND)["available"].is_array());
+    let err = fails(&home, &["workflow", "run", "new-feature", "--resume"], "no_run_to_resume", NOT_FOUND);
+    assert!(err["hint"].is_string());
+    fails(&home, &["notes", "no-such-prompt", "--add", "hi"], "not_found", NOT_FOUND);
+    assert!(fails(&home, &["schema", "doctor"], "not_found", NOT_FOUND)["available"].is_array());
+
+    home.json(&["collections", "create", "kit"]);
+    assert_eq!(
+        fails(&home, &["collections", "add", "nope", "idea-wizard"], "not_found", NOT_FOUND)["available"],
+        json!(["kit"])
+    );
+    fails(&home, &["collections", "remove", "nope", "idea-wizard"], "not_found", NOT_FOUND);
+    fails(&home, &["collections", "export", "nope"], "not_found", NOT_FOUND);
+    let err = fails(&home, &["collections", "add", "kit", "no-such-prompt"], "not_found", NOT_FOUND);
+    assert_eq!(err["missing"], json!(["no-such-prompt"]));
+}
+
+#[test]
+fn bad_arguments_are_invalid() {
+    let home = TestHome::new();
+    fails(&home, &["search", " "], "empty_query", INVALID_ARGUMENT);
+    fails(&home, &["search", "ideas", "--limit", "0"], "invalid_limit", INVALID_ARGUMENT);
+    fails(&home, &["suggest", " "], "empty_task", INVALID_ARGUMENT);
+    fails(&home, &["suggest", "docs", "--limit", "0"], "invalid_limit", INVALID_ARGUMENT);
+    fails(&home, &["export"], "missing_ids", INVALID_ARGUMENT);
+    fails(&home, &["export", "idea-wizard", "--format", "pdf"], "invalid_format", INVALID_ARGUMENT);
+    fails(&home, &["export", "idea-wizard", "--on-conflict", "maybe"], "invalid_argument", INVALID_ARGUMENT);
+    fails(&home, &["notes", "idea-wizard", "--add", " "], "empty_note", INVALID_ARGUMENT);
+    fails(&home, &["collections", "create", " "], "missing_argument", INVALID_ARGUMENT);
+    home.json(&["collections", "create", "kit"]);
+    fails(&home, &["collections", "export", "kit", "--format", "pdf"], "invalid_format", INVALID_ARGUMENT);
+    fails(&home, &["workflow", "run", "new-feature", "--var", "NOEQUALS"], "invalid_variable", INVALID_ARGUMENT);
+    fails(&home, &["render", "idea-wizard", "--var", "NOEQUALS"], "invalid_variable", INVALID_ARGUMENT);
+    fails(&home, &["render", "idea-wizard", "--fill"], "not_a_tty", INVALID_ARGUMENT);
+    fails(&home, &["i"], "not_a_tty", INVALID_ARGUMENT);
+    fails(&home, &["completion", "--shell", "tcsh"], "invalid_shell", INVALID_ARGUMENT);
+}
+
+#[test]
+fn existing_state_is_a_conflict() {
+    let home = TestHome::new();
+    home.json(&["collections", "create", "mine"]);
+    fails(&home, &["collections", "create", "mine"], "already_exists", CONFLICT);
+
+    write_credentials(&home, Duration::hours(1), None);
+    let err = fails(&home, &["login", "--remote"], "already_logged_in", CONFLICT);
+    assert!(err["email"].is_string());
+}
+
+#[test]
+fn premium_commands_need_a_session() {
+    let home = TestHome::new();
+    fails(&home, &["whoami"], "not_authenticated", PERMISSION_DENIED);
+    fails(&home, &["sync"], "not_authenticated", PERMISSION_DENIED);
+    fails(&home, &["collections", "sync"], "not_authenticated", PERMISSION_DENIED);
+
+    let output = home.command().env("JFP_TOKEN", "env-token").args(["--json", "logout"]).output().unwrap();
+    assert!(contract(output, "env_token", INVALID_ARGUMENT)["hint"].is_string());
+}
+
+#[test]
+fn unreachable_servers_are_network_errors() {
+    let home = TestHome::new();
+    let unreachable = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/api/prompts", listener.local_addr().unwrap())
+    };
+    let run = |args: &[&str]| {
+        home.command()
+            .env("JFP_REGISTRY_URL", &unreachable)
+            .env("JFP_PREMIUM_URL", unreachable.trim_end_matches("/api/prompts"))
+            .arg("--json")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    contract(run(&["refresh"]), "network_error", NETWORK);
+    contract(run(&["login", "--remote"]), "network_error", NETWORK);
+
+    write_credentials(&home, Duration::hours(1), None);
+    contract(run(&["sync"]), "network_error", NETWORK);
+
+    // Partial failure is part of the regular output, with the exit code
+    home.json(&["collections", "create", "kit"]);
+    let output = run(&["collections", "sync"]);
+    assert_eq!(output.status.code(), Some(NETWORK));
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(out["failed"][0]["name"], "kit");
+}
+
+#[test]
+fn a_broken_database_is_a_database_error() {
+    let home = TestHome::new();
+    fs::create_dir_all(home.db_path().parent().unwrap()).unwrap();
+    fs::write(home.db_path(), "this is not a sqlite database, \"quoted\" all the same").unwrap();
+
+    fails(&home, &["list"], "database_error", DATABASE);
+    fails(&home, &["show", "idea-wizard"], "database_error", DATABASE);
+    fails(&home, &["search", "ideas"], "database_error", DATABASE);
+    fails(&home, &["tags"], "database_error", DATABASE);
+}
@@ -0,0 +1,196 @@
+//! `jfp export`: formats, file naming and the manifest
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::TestHome;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+/// Files in the repository root produced by the TypeScript exporter
+fn golden(name: &str) -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    fs::read_to_string(root.join(name)).unwrap()
+}
+
+fn export(home: &TestHome, dir: &Path, args: &[&str]) -> Value {
+    let dir = dir.to_str().unwrap();
+    home.json(&[&["export", "--output-dir", dir], args].concat())
+}
+
+fn entry_files(manifest: &Value) -> Vec<PathBuf> {
+    manifest["exported"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| PathBuf::from(e["file"].as_str().unwrap()))
+        .collect()
+}
+
+#[test]
+fn markdown_and_skill_match_the_typescript_exporter() {
+    let home = TestHome::new();
+    let out = home.path().join("out");
+
+    export(&home, &out, &["idea-wizard"]);
+    assert_eq!(fs::read_to_string(out.join("idea-wizard.md")).unwrap(), golden("idea-wizard.md"));
+
+    export(&home, &out, &["idea-wizard", "readme-reviser", "--format", "skill"]);
+    assert_eq!(
+        fs::read_to_string(out.join("idea-wizard/SKILL.md")).unwrap(),
+        golden("idea-wizard-SKILL.md")
+    );
+    assert_eq!(
+        fs::read_to_string(out.join("readme-reviser/SKILL.md")).unwrap(),
+        golden("readme-reviser-SKILL.md")
+    );
+}
+
+#[test]
+fn manifest_lists_size_and_hash_of_each_file() {
+    let home = TestHome::new();
+    let out = home.path().join("out");
+    let manifest = export(&home, &out, &["idea-wizard", "bug-hunter", "idea-wizard", "--format", "json"]);
+
+    assert_eq!(manifest["success"], true);
+    assert_eq!(manifest["format"], "json");
+    let entries = manifest["exported"].as_array().unwrap();
+    assert_eq!(entries.len(), 2, "duplicate ids are exported once");
+    for entry in entries {
+        let content = fs::read(entry["file"].as_str().unwrap()).unwrap();
+        assert_eq!(entry["bytes"], content.len());
+        assert_eq!(entry["sha256"], format!("{:x}", Sha256::digest(&content)));
+    }
+
+    let prompt: Value = serde_json::from_slice(&fs::read(out.join("bug-hunter.json")).unwrap()).unwrap();
+    assert_eq!(prompt["id"], "bug-hunter");
+    assert_eq!(entries[1]["ids"], json!(["bug-hunter"]));
+}
+
+#[test]
+fn all_exports_the_whole_library_to_combined_files() {
+    let home = TestHome::new();
+    let out = home.path().join("out");
+    let count = home.json(&["list"])["count"].as_u64().unwrap() as usize;
+
+    let manifest = export(&home, &out, &["all", "--format", "jsonl"]);
+    assert_eq!(entry_files(&manifest), vec![out.join("prompts.jsonl")]);
+    assert_eq!(manifest["exported"][0]["ids"].as_array().unwrap().len(), count);
+    let jsonl = fs::read_to_string(out.join("prompts.jsonl")).unwrap();
+    assert_eq!(jsonl.lines().count(), count);
+    for line in jsonl.lines() {
+        let prompt: Value = serde_json::from_str(line).unwrap();
+        assert!(prompt["content"].is_string());
+    }
+
+    export(&home, &out, &["all", "--format", "registry"]);
+    let payload: Value = serde_json::from_slice(&fs::read(out.join("registry.json")).unwrap()).unwrap();
+    assert_eq!(payload["schemaVersion"], 1);
+    assert_eq!(payload["meta"]["promptCount"], count);
+    assert_eq!(payload["prompts"].as_array().unwrap().len(), count);
+    let categories = payload["meta"]["categories"].as_array().unwrap();
+    assert!(categories.contains(&json!("ideation")));
+    assert!(categories.windows(2).all(|w| w[0].as_str() < w[1].as_str()));
+}
+
+#[test]
+fn yaml_quotes_values_that_would_change_type() {
+    let home = TestHome::new();
+    home.add_prompt(json!({
+        "id": "yaml-edge",
+        "title": "Edge: cases",
+        "category": "testing",
+        "tags": ["yes", "42", "plain"],
+        "created": "2025-01-09",
+        "content": "First line\n\n  indented\n",
+    }));
+
+    let output = home.run(&["export", "yaml-edge", "--format", "yaml", "--stdout"]);
+    assert!(output.status.success());
+    let yaml = String::from_utf8(output.stdout).unwrap();
+    assert!(yaml.starts_with("---\n"));
+    assert!(yaml.contains("content: |\n  First line\n\n    indented\n"));
+    assert!(yaml.contains("created: \"2025-01-09\"\n"));
+    assert!(yaml.contains("tags:\n  - \"yes\"\n  - \"42\"\n  - plain\n"));
+    assert!(yaml.contains("title: \"Edge: cases\"\n"));
+}
+
+#[test]
+fn file_names_are_safe_and_unique() {
+    let home = TestHome::new();
+    for id in ["../escape", "Mixed-Case", "mixed-case"] {
+        home.add_prompt(json!({ "id": id, "title": id, "tags": [], "content": format!("Prompt {}", id) }));
+    }
+    let out = home.path().join("out");
+
+    let manifest = export(&home, &out, &["../escape", "Mixed-Case", "mixed-case"]);
+    assert_eq!(
+        entry_files(&manifest),
+        vec![out.join("-escape.md"), out.join("Mixed-Case.md"), out.join("mixed-case-2.md")]
+    );
+    assert!(!home.path().join("escape.md").exists());
+    assert!(fs::read_to_string(out.join("mixed-case-2.md")).unwrap().contains("Prompt mixed-case"));
+}
+
+#[test]
+fn existing_files_follow_on_conflict() {
+    let home = TestHome::new();
+    let out = home.path().join("out");
+    fs::create_dir_all(&out).unwrap();
+    fs::write(out.join("idea-wizard.md"), "hand edited").unwrap();
+
+    let manifest = export(&home, &out, &["idea-wizard", "--on-conflict", "skip"]);
+    assert_eq!(manifest["exported"], json!([]));
+    assert_eq!(manifest["skipped"][0]["reason"], "exists");
+    assert_eq!(fs::read_to_string(out.join("idea-wizard.md")).unwrap(), "hand edited");
+
+    let manifest = export(&home, &out, &["idea-wizard", "--on-conflict", "rename"]);
+    assert_eq!(entry_files(&manifest), vec![out.join("idea-wizard-2.md")]);
+    assert_eq!(manifest["exported"][0]["renamed_from"], out.join("idea-wizard.md").to_str().unwrap());
+    assert_eq!(fs::read_to_string(out.join("idea-wizard.md")).unwrap(), "hand edited");
+
+    export(&home, &out, &["idea-wizard"]);
+    assert_eq!(fs::read_to_string(out.join("idea-wizard.md")).unwrap(), golden("idea-wizard.md"));
+
+    // Atomic writes leave no temporary files behind
+    let mut names: Vec<String> = fs::read_dir(&out)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["idea-wizard-2.md", "idea-wizard.md"]);
+}
+
+#[test]
+fn stdout_prints_content_without_a_manifest() {
+    let home = TestHome::new();
+    let output = home.run(&["--json", "export", "idea-wizard", "bug-hunter", "--format", "json", "--stdout"]);
+    assert!(output.status.success());
+    let prompts: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(prompts[0]["id"], "idea-wizard");
+    assert_eq!(prompts[1]["id"], "bug-hunter");
+
+    let output = home.run(&["export", "idea-wizard", "--stdout"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), golden("idea-wizard.md"));
+}
+
+#[test]
+fn unknown_ids_abort_before_writing() {
+    let home = TestHome::new();
+    let out = home.path().join("out");
+    let output = home.run(&["--json", "export", "idea-wizard", "nope", "--output-dir", out.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_found");
+    assert_eq!(err["missing"], json!(["nope"]));
+    assert!(!out.exists());
+
+    let err: Value = serde_json::from_slice(&home.run(&["--json", "export"]).stderr).unwrap();
+    assert_eq!(err["error"], "missing_ids");
+
+    let err: Value =
+        serde_json::from_slice(&home.run(&["--json", "export", "all", "--format", "toml"]).stderr).unwrap();
+    assert_eq!(err["error"], "invalid_format");
+}
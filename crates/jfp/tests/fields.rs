@@ -0,0 +1,95 @@
+//! `--fields`: projection of JSON payloads and NDJSON records
+
+mod common;
+
+use common::TestHome;
+use serde_json::{Value, json};
+
+fn fails(home: &TestHome, args: &[&str]) -> Value {
+    let output = home.run(&[&["--json"], args].concat());
+    assert_eq!(output.status.code(), Some(2), "{}", String::from_utf8_lossy(&output.stdout));
+    assert!(output.stdout.is_empty());
+    serde_json::from_slice(&output.stderr).unwrap()
+}
+
+#[test]
+fn fields_keep_only_the_named_paths_in_order() {
+    let home = TestHome::new();
+    let prompt = home.json(&["show", "idea-wizard", "--fields", "title,id,tags"]);
+    assert_eq!(prompt.as_object().unwrap().keys().collect::<Vec<_>>(), ["id", "tags", "title"]);
+
+    // Key order follows the request
+    let output = home.run(&["--json", "show", "idea-wizard", "--fields", "title,id"]);
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.find("\"title\"").unwrap() < text.find("\"id\"").unwrap(), "{}", text);
+
+    // Arrays are transparent
+    let listed = home.json(&["list", "--tag", "review", "--fields", "prompts.id,count"]);
+    assert_eq!(listed["count"], 3);
+    assert!(listed["prompts"].as_array().unwrap().iter().all(|p| p.as_object().unwrap().len() == 1));
+    assert_eq!(listed["prompts"][0], json!({ "id": "bug-hunter" }));
+
+    let found = home.json(&["search", "ideas", "--limit", "1", "--fields", "results.snippet.field,query"]);
+    assert_eq!(found, json!({ "results": [{ "snippet": { "field": "description" } }], "query": "ideas" }));
+
+    let wrapped = home.json(&["--envelope", "show", "idea-wizard", "--fields", "title"]);
+    assert_eq!(wrapped, json!({ "schema_version": 1, "command": "show", "title": "The Idea Wizard" }));
+}
+
+#[test]
+fn ndjson_records_are_projected_one_by_one() {
+    let home = TestHome::new();
+    let output = home.run(&["--format", "ndjson", "list", "--tag", "review", "--fields", "id"]);
+    assert!(output.status.success());
+    let ids: Vec<Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(ids[0], json!({ "id": "bug-hunter" }));
+    assert_eq!(ids.len(), 3);
+}
+
+#[test]
+fn optional_fields_from_the_schema_are_not_unknown() {
+    let home = TestHome::new();
+    home.add_prompt(json!({ "id": "bare", "title": "Bare", "tags": [], "content": "No description" }));
+    assert_eq!(home.json(&["show", "bare", "--fields", "id,description"]), json!({ "id": "bare" }));
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    let home = TestHome::new();
+    let err = fails(&home, &["list", "--fields", "id,bogus"]);
+    assert_eq!(err["error"], "unknown_field");
+    assert_eq!(err["fields"], json!(["id", "bogus"]));
+    assert_eq!(err["hint"], "Did you mean prompts.id?");
+    assert!(err["available"].as_array().unwrap().iter().any(|p| p == "prompts.title"));
+
+    assert_eq!(fails(&home, &["show", "idea-wizard", "--fields", "tags.name"])["error"], "unknown_field");
+    assert_eq!(fails(&home, &["show", "idea-wizard", "--fields", "id,,title"])["error"], "invalid_fields");
+}
+
+#[test]
+fn unknown_fields_are_rejected_before_the_command_runs() {
+    let home = TestHome::new();
+    let out = home.path().join("out");
+    let dir = out.to_str().unwrap();
+    let err = fails(&home, &["--fields", "bogus", "export", "idea-wizard", "--output-dir", dir]);
+    assert_eq!(err["error"], "unknown_field");
+    assert!(!out.exists());
+
+    fails(&home, &["--fields", "bogus", "collections", "create", "foo"]);
+    assert_eq!(home.json(&["collections"])["collections"], json!([]));
+}
+
+#[test]
+fn envelope_fields_are_only_available_with_envelope() {
+    let home = TestHome::new();
+    let err = fails(&home, &["show", "idea-wizard", "--fields", "command"]);
+    let available = err["available"].as_array().unwrap();
+    assert!(!available.iter().any(|p| p == "command" || p == "schema_version"), "{:?}", available);
+
+    let wrapped = home.json(&["--envelope", "show", "idea-wizard", "--fields", "command"]);
+    assert_eq!(wrapped, json!({ "schema_version": 1, "command": "show" }));
+}
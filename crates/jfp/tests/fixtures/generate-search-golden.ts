@@ -0,0 +1,37 @@
+#!/usr/bin/env bun
+// Regenerates search_golden.json from the TypeScript search engine.
+// Run from the repo root:
+//   bun crates/jfp/tests/fixtures/generate-search-golden.ts > crates/jfp/tests/fixtures/search_golden.json
+
+import { prompts, promptsById } from "../../../../packages/core/src/prompts/registry";
+import { buildIndex, expandQuery, searchPrompts, tokenize } from "../../../../packages/core/src/search";
+
+const QUERIES = [
+  "bug",
+  "fix bug",
+  "docs",
+  "readme",
+  "refactor",
+  "performance",
+  "test",
+  "agent",
+  "brainstorm ideas",
+  "cli",
+  "debug",
+  "review code",
+  "deploy",
+  "documentation",
+  "planning",
+  "ui design",
+  "git commit",
+];
+
+const index = buildIndex(prompts);
+
+const cases = QUERIES.map((query) => ({
+  query,
+  expanded: expandQuery(tokenize(query)),
+  results: searchPrompts(query, { limit: 100, index, promptsMap: promptsById }).map((r) => r.prompt.id),
+}));
+
+console.log(JSON.stringify({ source: "packages/core/src/search", cases }, null, 2));
@@ -0,0 +1,132 @@
+//! `--format`: NDJSON streaming, plain text, and `--json` as an alias
+
+mod common;
+
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+
+use common::TestHome;
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+/// stdout of a successful run, one JSON value per line
+fn ndjson(home: &TestHome, args: &[&str]) -> Vec<Value> {
+    let output = home.run(&[&["--format", "ndjson"], args].concat());
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn plain(home: &TestHome, args: &[&str]) -> String {
+    let output = home.run(&[&["--format", "plain"], args].concat());
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn ndjson_streams_the_records_of_the_json_payload() {
+    let home = TestHome::new();
+    let cases = [
+        (vec!["list", "--tag", "review"], "prompts"),
+        (vec!["search", "ideas", "--limit", "3"], "results"),
+        (vec!["tags"], "tags"),
+        (vec!["categories"], "categories"),
+    ];
+    for (args, key) in cases {
+        let records = ndjson(&home, &args);
+        assert!(!records.is_empty(), "{:?}", args);
+        assert_eq!(Value::from(records), home.json(&args)[key], "{:?}", args);
+    }
+
+    // The flag works after the subcommand too, and envelope fields go on every line
+    let records = ndjson(&home, &["--envelope", "tags"]);
+    assert!(records.iter().all(|r| r["command"] == "tags" && r["schema_version"].is_u64()));
+    assert!(ndjson(&home, &["search", "zzzznothing"]).is_empty());
+}
+
+#[test]
+fn other_commands_print_their_payload_on_one_line() {
+    let home = TestHome::new();
+    let records = ndjson(&home, &["show", "idea-wizard"]);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["id"], "idea-wizard");
+}
+
+#[test]
+fn ndjson_export_reports_each_file_as_it_is_written() {
+    let home = TestHome::new();
+    let out = TempDir::new().unwrap();
+    let dir = out.path().to_str().unwrap();
+
+    let records = ndjson(&home, &["export", "idea-wizard", "bug-hunter", "-o", dir]);
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|r| r["status"] == "exported" && r["sha256"].is_string()));
+
+    // `--format` is the file format for export; the output format has its own flag
+    let output = home.run(&["export", "idea-wizard", "--output-format", "ndjson", "-o", dir, "--on-conflict", "skip"]);
+    assert!(output.status.success());
+    let line: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(line["status"], "skipped");
+
+    let err: Value = serde_json::from_slice(&home.run(&["export", "all", "--format", "ndjson"]).stderr).unwrap();
+    assert_eq!(err["error"], "invalid_format");
+    assert!(err["hint"].as_str().unwrap().contains("--output-format ndjson"));
+}
+
+#[test]
+fn plain_prints_tab_separated_fields() {
+    let home = TestHome::new();
+    let tags = plain(&home, &["tags"]);
+    let (name, count) = tags.lines().next().unwrap().split_once('\t').unwrap();
+    assert!(!name.is_empty());
+    assert!(count.parse::<usize>().unwrap() > 0);
+
+    let list = plain(&home, &["list", "--tag", "review"]);
+    assert!(list.lines().any(|line| line.starts_with("bug-hunter\t")));
+}
+
+#[test]
+fn json_is_an_alias_for_format_json() {
+    let home = TestHome::new();
+    let output = home.run(&["--format", "json", "tags"]);
+    assert_eq!(serde_json::from_slice::<Value>(&output.stdout).unwrap(), home.json(&["tags"]));
+
+    // An explicit --format wins over --json
+    assert_eq!(ndjson(&home, &["--json", "tags"]).len(), home.json(&["tags"])["total"]);
+}
+
+#[test]
+fn ndjson_stops_cleanly_when_the_reader_goes_away() {
+    let home = TestHome::new();
+    // Records bigger than a pipe buffer, so jfp is still writing when the
+    // reader closes
+    for id in ["big-one", "big-two"] {
+        home.add_prompt(json!({
+            "id": id,
+            "title": id,
+            "description": "x".repeat(100_000),
+            "category": "testing",
+            "tags": [],
+            "content": "Body",
+        }));
+    }
+
+    let mut child = home
+        .command()
+        .args(["--format", "ndjson", "list"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut first = String::new();
+    BufReader::new(child.stdout.take().unwrap()).read_line(&mut first).unwrap();
+    assert!(serde_json::from_str::<Value>(&first).is_ok(), "{}", first);
+
+    let output = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{:?}: {}", output.status, stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+}
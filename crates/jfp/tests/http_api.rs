@@ -0,0 +1,218 @@
+//! Local HTTP API (`jfp serve --http`) on an ephemeral port
+
+mod common;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Stdio};
+
+use common::TestHome;
+use serde_json::{Value, json};
+
+const TOKEN: &str = "test-token-123";
+
+/// A running `jfp serve --http`, killed on drop
+struct ApiServer {
+    child: Child,
+    port: u16,
+}
+
+impl ApiServer {
+    fn start(home: &TestHome) -> Self {
+        let token_file = home.path().join("token");
+        std::fs::write(&token_file, format!("{}\n", TOKEN)).unwrap();
+        let mut child = home
+            .command()
+            .args(["--json", "serve", "--http", "--port", "0", "--token-file"])
+            .arg(&token_file)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut ready = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut ready).unwrap();
+        let ready: Value = serde_json::from_str(&ready).expect("ready line is JSON");
+        assert_eq!(ready["token_file"], token_file.to_str().unwrap());
+        let port = ready["port"].as_u64().unwrap() as u16;
+        assert_eq!(ready["listening"], format!("http://127.0.0.1:{}", port));
+        Self { child, port }
+    }
+
+    fn request(&self, method: &str, path: &str, token: Option<&str>, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(("127.0.0.1", self.port)).unwrap();
+        let auth = token.map(|t| format!("Authorization: Bearer {}\r\n", t)).unwrap_or_default();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            auth,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).expect("body is JSON"))
+    }
+
+    fn get(&self, path: &str) -> (u16, Value) {
+        self.request("GET", path, Some(TOKEN), "")
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn requests_need_the_bearer_token() {
+    let home = TestHome::new();
+    let server = ApiServer::start(&home);
+
+    let (status, body) = server.request("GET", "/prompts", None, "");
+    assert_eq!(status, 401);
+    assert_eq!(body["error"], "unauthorized");
+
+    let (status, _) = server.request("GET", "/prompts", Some("wrong-token-12"), "");
+    assert_eq!(status, 401);
+
+    let (status, _) = server.get("/prompts");
+    assert_eq!(status, 200);
+}
+
+#[test]
+fn prompts_and_bundles_match_cli_json() {
+    let home = TestHome::new();
+    let server = ApiServer::start(&home);
+
+    let (status, list) = server.get("/prompts?category=ideation");
+    assert_eq!(status, 200);
+    assert_eq!(list, home.json(&["list", "--category", "ideation"]));
+
+    let (status, show) = server.get("/prompts/idea-wizard");
+    assert_eq!(status, 200);
+    assert_eq!(show, home.json(&["show", "idea-wizard"]));
+
+    let (status, bundles) = server.get("/bundles");
+    assert_eq!(status, 200);
+    assert_eq!(bundles, home.json(&["bundles"]));
+
+    let (status, missing) = server.get("/prompts/nope");
+    assert_eq!(status, 404);
+    assert_eq!(missing["error"], "not_found");
+}
+
+#[test]
+fn search_matches_cli_json_and_validates_input() {
+    let home = TestHome::new();
+    let server = ApiServer::start(&home);
+
+    let (status, results) = server.get("/search?q=readme%20docs&limit=3");
+    assert_eq!(status, 200);
+    assert_eq!(results, home.json(&["search", "readme docs", "--limit", "3"]));
+    assert_eq!(results["query"], "readme docs");
+
+    let (status, body) = server.get("/search?q=");
+    assert_eq!(status, 400);
+    assert_eq!(body["error"], "empty_query");
+
+    let (status, body) = server.get("/search?q=docs&limit=500");
+    assert_eq!(status, 400);
+    assert_eq!(body["error"], "invalid_limit");
+}
+
+/// The server keeps its search index between requests, but not past a change
+#[test]
+fn search_sees_library_changes_while_serving() {
+    let home = TestHome::new();
+    let server = ApiServer::start(&home);
+    let (_, before) = server.get("/search?q=zebra%20ideas");
+    assert!(before["results"].as_array().unwrap().iter().all(|r| r["id"] != "zebra"));
+
+    home.add_prompt(json!({
+        "id": "zebra",
+        "title": "Zebra ideas",
+        "category": "ideation",
+        "tags": ["zebra"],
+        "content": "Stripes",
+    }));
+    let (status, after) = server.get("/search?q=zebra%20ideas");
+    assert_eq!(status, 200);
+    assert_eq!(after["results"][0]["id"], "zebra");
+    assert_eq!(after, home.json(&["search", "zebra ideas"]));
+}
+
+#[test]
+fn render_takes_variables_from_query_or_body() {
+    let home = TestHome::new();
+    home.add_prompt(json!({
+        "id": "greeting",
+        "title": "Greeting",
+        "description": "Say hello",
+        "category": "automation",
+        "tags": [],
+        "author": "Test",
+        "content": "Hello {{NAME}}!",
+        "variables": [{ "name": "NAME", "label": "Name", "type": "text", "required": true }]
+    }));
+    let server = ApiServer::start(&home);
+
+    let (status, out) = server.get("/render/greeting?NAME=Ada+Lovelace");
+    assert_eq!(status, 200);
+    assert_eq!(out["id"], "greeting");
+    assert_eq!(out["rendered"], "Hello Ada Lovelace!");
+
+    let (status, out) = server.request("POST", "/render/greeting", Some(TOKEN), r#"{"variables": {"NAME": "Grace"}}"#);
+    assert_eq!(status, 200);
+    assert_eq!(out["rendered"], "Hello Grace!");
+
+    let (status, err) = server.get("/render/greeting");
+    assert_eq!(status, 422);
+    assert_eq!(err["error"], "missing_variables");
+    assert_eq!(err["missing"], json!(["NAME"]));
+}
+
+#[test]
+fn unknown_routes_and_methods_are_rejected() {
+    let home = TestHome::new();
+    let server = ApiServer::start(&home);
+
+    let (status, body) = server.get("/admin");
+    assert_eq!(status, 404);
+    assert_eq!(body["error"], "not_found");
+
+    let (status, body) = server.request("DELETE", "/prompts/idea-wizard", Some(TOKEN), "");
+    assert_eq!(status, 405);
+    assert_eq!(body["error"], "method_not_allowed");
+}
+
+#[cfg(unix)]
+#[test]
+fn missing_token_file_is_created_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = TestHome::new();
+    let token_file = home.path().join("secrets").join("token");
+    let mut child = home
+        .command()
+        .args(["--json", "serve", "--http", "--port", "0", "--token-file"])
+        .arg(&token_file)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut ready = String::new();
+    BufReader::new(child.stdout.take().unwrap()).read_line(&mut ready).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let token = std::fs::read_to_string(&token_file).unwrap();
+    assert_eq!(token.trim().len(), 64);
+    assert_eq!(std::fs::metadata(&token_file).unwrap().permissions().mode() & 0o777, 0o600);
+}
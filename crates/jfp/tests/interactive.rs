@@ -0,0 +1,40 @@
+//! Interactive picker and the commands it dispatches to
+
+mod common;
+
+use common::TestHome;
+use serde_json::Value;
+
+#[test]
+fn picker_refuses_without_a_terminal() {
+    let home = TestHome::new();
+    let output = home.run(&["i"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_a_tty");
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn open_reports_the_url_when_no_browser_is_available() {
+    let home = TestHome::new();
+    let output = home
+        .command()
+        .env("PATH", home.path())
+        .args(["--json", "open", "idea-wizard"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(out["url"], "https://jeffreysprompts.com/prompts/idea-wizard");
+    assert_eq!(out["opened"], false);
+}
+
+#[test]
+fn open_unknown_prompt_is_not_found() {
+    let home = TestHome::new();
+    let output = home.run(&["--json", "open", "no-such-prompt"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_found");
+}
@@ -0,0 +1,137 @@
+//! Personal notes: offline storage, search, and upload to a stand-in API
+
+mod common;
+
+use chrono::Duration;
+use common::TestHome;
+use common::premium::{PremiumServer, jfp, run, write_credentials};
+use serde_json::json;
+
+const NOTES_PATH: &str = "/api/cli/notes/idea-wizard";
+
+#[test]
+fn notes_work_offline_and_are_searchable() {
+    let home = TestHome::new();
+
+    let added = home.json(&["notes", "idea-wizard", "--add", "  Pairs well with zanzibarian brainstorms  "]);
+    assert_eq!(added["added"], true);
+    assert_eq!(added["queued"], true);
+    assert_eq!(added["note"]["content"], "Pairs well with zanzibarian brainstorms");
+    assert_eq!(added["note"]["synced"], false);
+    let note_id = added["note"]["id"].as_str().unwrap().to_string();
+
+    let listed = home.json(&["notes", "idea-wizard"]);
+    assert_eq!(listed["count"], 1);
+    assert_eq!(listed["pending"], 1);
+    assert_eq!(listed["online"], false);
+    assert_eq!(listed["notes"][0]["id"], note_id.as_str());
+
+    let shown = home.json(&["show", "idea-wizard"]);
+    assert_eq!(shown["notes"][0]["content"], "Pairs well with zanzibarian brainstorms");
+
+    let found = home.json(&["search", "zanzibarian"]);
+    assert_eq!(found["results"][0]["id"], "idea-wizard");
+    assert_eq!(found["results"][0]["matched_fields"], json!(["notes"]));
+    assert_eq!(found["results"][0]["snippet"]["field"], "notes");
+
+    let deleted = home.json(&["notes", "idea-wizard", "--delete", &note_id]);
+    assert_eq!(deleted["deleted"], true);
+    // Never uploaded, so there is nothing left to tell the server
+    assert_eq!(deleted["queued"], false);
+    assert_eq!(home.json(&["notes", "idea-wizard"])["count"], 0);
+    assert!(home.json(&["show", "idea-wizard"]).get("notes").is_none());
+    assert_eq!(home.json(&["search", "zanzibarian"])["results"], json!([]));
+}
+
+#[test]
+fn notes_reject_unknown_prompts_notes_and_empty_text() {
+    let home = TestHome::new();
+
+    let output = home.run(&["--json", "notes", "no-such-prompt"]);
+    assert!(!output.status.success());
+    let err: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_found");
+
+    let output = home.run(&["--json", "notes", "idea-wizard", "--add", "   "]);
+    let err: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "empty_note");
+
+    let output = home.run(&["--json", "notes", "idea-wizard", "--delete", "note-missing"]);
+    let err: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "note_not_found");
+}
+
+#[test]
+fn notes_upload_and_merge_with_the_server() {
+    let home = TestHome::new();
+    write_credentials(&home, Duration::hours(1), None);
+    let server = PremiumServer::start(|path, _| match path {
+        NOTES_PATH => (
+            200,
+            json!({
+                "success": true,
+                "note": { "id": "srv-1" },
+                "prompt_id": "idea-wizard",
+                "notes": [
+                    {
+                        "id": "srv-1", "prompt_id": "idea-wizard", "content": "Uploaded",
+                        "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z"
+                    },
+                    {
+                        "id": "srv-2", "prompt_id": "idea-wizard", "content": "From the web app",
+                        "created_at": "2026-01-02T00:00:00Z", "updated_at": "2026-01-02T00:00:00Z"
+                    }
+                ]
+            }),
+        ),
+        "/api/cli/notes/idea-wizard/srv-2" => (200, json!({ "success": true })),
+        _ => (404, json!({})),
+    });
+
+    let (ok, out, err) = run(jfp(&home, &server, &["notes", "idea-wizard", "--add", "Uploaded"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["queued"], false);
+    assert_eq!(out[0]["note"]["synced"], true);
+    let posted = &server.seen(NOTES_PATH)[0];
+    assert_eq!(posted.method, "POST");
+    assert_eq!(posted.body, json!({ "content": "Uploaded" }));
+    assert_eq!(posted.authorization.as_deref(), Some("Bearer old-token"));
+
+    // Listing pulls notes written elsewhere, without duplicating ours
+    let (ok, out, _) = run(jfp(&home, &server, &["notes", "idea-wizard"]));
+    assert!(ok);
+    assert_eq!(out[0]["online"], true);
+    assert_eq!(out[0]["count"], 2);
+    // Oldest first: the web app note predates ours
+    assert_eq!(out[0]["notes"][0]["id"], "srv-2");
+    assert_eq!(out[0]["notes"][0]["content"], "From the web app");
+    assert_eq!(out[0]["notes"][1]["content"], "Uploaded");
+
+    let (ok, out, _) = run(jfp(&home, &server, &["notes", "idea-wizard", "--delete", "srv-2"]));
+    assert!(ok);
+    assert_eq!(out[0]["queued"], false);
+    assert_eq!(server.seen("/api/cli/notes/idea-wizard/srv-2")[0].method, "DELETE");
+}
+
+#[test]
+fn queued_notes_upload_on_sync() {
+    let home = TestHome::new();
+    home.json(&["notes", "idea-wizard", "--add", "Written offline"]);
+
+    let server = PremiumServer::start(|path, _| match path {
+        NOTES_PATH => (200, json!({ "success": true, "note": { "id": "srv-9" } })),
+        "/api/cli/sync" => (200, json!({ "prompts": [] })),
+        _ => (404, json!({})),
+    });
+    write_credentials(&home, Duration::hours(1), None);
+
+    let (ok, out, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["notes_uploaded"], 1);
+    assert_eq!(out[0]["notes_pending"], 0);
+    assert_eq!(server.seen(NOTES_PATH)[0].body, json!({ "content": "Written offline" }));
+
+    let listed = home.json(&["notes", "idea-wizard"]);
+    assert_eq!(listed["notes"][0]["synced"], true);
+    assert_eq!(listed["pending"], 0);
+}
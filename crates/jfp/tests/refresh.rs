@@ -0,0 +1,320 @@
+//! Remote registry refresh and status against a stand-in registry server
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use common::TestHome;
+use serde_json::{Value, json};
+use tiny_http::{Header, Response, Server};
+
+const LAST_MODIFIED: &str = "Wed, 14 Oct 2026 09:00:00 GMT";
+
+/// What the stand-in serves, and the conditional headers it was sent
+struct State {
+    status: u16,
+    body: String,
+    etag: String,
+    seen: Vec<(Option<String>, Option<String>)>,
+}
+
+/// A registry on an ephemeral loopback port, stopped on drop
+struct Registry {
+    server: Arc<Server>,
+    state: Arc<Mutex<State>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Registry {
+    fn start(payload: Value) -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let state = Arc::new(Mutex::new(State {
+            status: 200,
+            body: payload.to_string(),
+            etag: "\"v1\"".to_string(),
+            seen: Vec::new(),
+        }));
+
+        let thread = {
+            let server = Arc::clone(&server);
+            let state = Arc::clone(&state);
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    let header = |name: &'static str| {
+                        request
+                            .headers()
+                            .iter()
+                            .find(|h| h.field.equiv(name))
+                            .map(|h| h.value.to_string())
+                    };
+                    let mut state = state.lock().unwrap();
+                    let if_none_match = header("If-None-Match");
+                    state.seen.push((if_none_match.clone(), header("If-Modified-Since")));
+
+                    let response = if if_none_match.as_deref() == Some(state.etag.as_str()) {
+                        Response::from_string("").with_status_code(304)
+                    } else {
+                        Response::from_string(state.body.clone())
+                            .with_status_code(state.status)
+                            .with_header(Header::from_bytes("ETag", state.etag.as_bytes()).unwrap())
+                            .with_header(Header::from_bytes("Last-Modified", LAST_MODIFIED).unwrap())
+                    };
+                    let _ = request.respond(response);
+                }
+            })
+        };
+
+        Self {
+            server,
+            state,
+            thread: Some(thread),
+        }
+    }
+
+    fn url(&self) -> String {
+        format!("http://127.0.0.1:{}/api/prompts", self.server.server_addr().to_ip().unwrap().port())
+    }
+
+    /// Serve a new registry version
+    fn publish(&self, payload: Value, etag: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.body = payload.to_string();
+        state.etag = etag.to_string();
+    }
+
+    fn fail_with(&self, status: u16, body: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.status = status;
+        state.body = body.to_string();
+        state.etag = "\"broken\"".to_string();
+    }
+
+    fn seen(&self) -> Vec<(Option<String>, Option<String>)> {
+        self.state.lock().unwrap().seen.clone()
+    }
+}
+
+impl Drop for Registry {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn prompt(id: &str, content: &str) -> Value {
+    json!({
+        "id": id,
+        "title": format!("Remote {}", id),
+        "description": "Served by the stand-in registry",
+        "category": "automation",
+        "tags": ["remote"],
+        "author": "Test",
+        "content": content
+    })
+}
+
+/// Run `jfp --json <args>` against `registry`, returning (success, stdout or stderr JSON)
+fn jfp(home: &TestHome, registry_url: &str, args: &[&str]) -> (bool, Value) {
+    let output = home
+        .command()
+        .env("JFP_REGISTRY_URL", registry_url)
+        .env("JFP_CACHE_TTL", "3600")
+        .arg("--json")
+        .args(args)
+        .output()
+        .unwrap();
+    let stream = if output.status.success() { &output.stdout } else { &output.stderr };
+    (output.status.success(), serde_json::from_slice(stream).expect("output is JSON"))
+}
+
+#[test]
+fn refresh_downloads_then_revalidates_with_validators() {
+    let home = TestHome::new();
+    let registry = Registry::start(json!({ "prompts": [prompt("remote-one", "One"), prompt("remote-two", "Two")] }));
+
+    let (ok, first) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(ok, "{}", first);
+    assert_eq!(first["source"], "remote");
+    assert_eq!(first["status"], "updated");
+    assert_eq!(first["url"], registry.url());
+    assert_eq!(first["prompt_count"], 2);
+    assert_eq!(first["added"], 2);
+    assert_eq!(first["etag"], "\"v1\"");
+    assert_eq!(first["last_modified"], LAST_MODIFIED);
+    assert_eq!(home.json(&["show", "remote-one"])["content"], "One");
+
+    let (ok, second) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(ok, "{}", second);
+    assert_eq!(second["source"], "cache");
+    assert_eq!(second["status"], "not_modified");
+    assert_eq!(second["prompt_count"], 2);
+    assert_eq!(second["fetched_at"], first["fetched_at"]);
+
+    // --force downloads again without validators
+    let (ok, forced) = jfp(&home, &registry.url(), &["refresh", "--force"]);
+    assert!(ok, "{}", forced);
+    assert_eq!(forced["status"], "updated");
+    assert_eq!(forced["added"], 0);
+    assert_eq!(forced["updated"], 0);
+
+    let seen = registry.seen();
+    assert_eq!(seen[0], (None, None));
+    assert_eq!(seen[1], (Some("\"v1\"".to_string()), Some(LAST_MODIFIED.to_string())));
+    assert_eq!(seen[2], (None, None));
+}
+
+#[test]
+fn remote_registry_replaces_bundled_and_keeps_local_prompts() {
+    let home = TestHome::new();
+    home.add_prompt(json!({
+        "id": "greeting",
+        "title": "Greeting",
+        "description": "Say hello",
+        "category": "automation",
+        "tags": [],
+        "author": "Test",
+        "content": "Hello from my machine"
+    }));
+    let registry = Registry::start(json!([
+        prompt("idea-wizard", "Remote idea wizard"),
+        prompt("remote-one", "One"),
+        prompt("greeting", "Hello from the registry"),
+    ]));
+
+    let (ok, out) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(ok, "{}", out);
+    assert_eq!(out["added"], 1);
+    assert_eq!(out["updated"], 1);
+    assert_eq!(out["kept_local"], json!(["greeting"]));
+    assert_eq!(home.json(&["show", "idea-wizard"])["content"], "Remote idea wizard");
+    assert_eq!(home.json(&["show", "greeting"])["content"], "Hello from my machine");
+
+    // The next version drops a prompt; it goes, and search forgets it
+    registry.publish(json!([prompt("idea-wizard", "Remote idea wizard")]), "\"v2\"");
+    let (ok, out) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(ok, "{}", out);
+    assert_eq!(out["status"], "updated");
+    assert_eq!(out["removed"], 1);
+    assert!(!home.run(&["--json", "show", "remote-one"]).status.success());
+    let results = home.json(&["search", "One"]);
+    assert!(results["results"].as_array().unwrap().iter().all(|r| r["id"] != "remote-one"));
+    assert_eq!(home.json(&["show", "greeting"])["content"], "Hello from my machine");
+}
+
+#[test]
+fn reseeding_leaves_remote_prompts_alone() {
+    let home = TestHome::new();
+    let registry = Registry::start(json!([prompt("idea-wizard", "Remote idea wizard")]));
+    let (ok, out) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(ok, "{}", out);
+
+    // A newer snapshot that bundles a prompt the remote registry already serves
+    let conn = rusqlite::Connection::open(home.db_path()).unwrap();
+    conn.execute("UPDATE sync_meta SET bundled_sha256 = 'older' WHERE id = 1", []).unwrap();
+    conn.execute("DELETE FROM seeded_prompts WHERE id = 'idea-wizard'", []).unwrap();
+
+    assert_eq!(home.json(&["show", "idea-wizard"])["content"], "Remote idea wizard");
+    let source: String = conn
+        .query_row("SELECT source FROM prompts WHERE id = 'idea-wizard'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(source, "remote");
+}
+
+#[test]
+fn invalid_registry_leaves_library_untouched() {
+    let home = TestHome::new();
+    let before = home.json(&["list"]);
+    let registry = Registry::start(json!([prompt("remote-one", "One"), { "id": "no-content", "title": "Broken" }]));
+
+    let (ok, err) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(!ok);
+    assert_eq!(err["error"], "invalid_registry");
+    assert!(err["message"].as_str().unwrap().contains("prompt #1 (no-content)"));
+
+    registry.publish(json!([prompt("remote-one", "One"), prompt("remote-one", "Again")]), "\"v2\"");
+    let (ok, err) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(!ok);
+    assert!(err["message"].as_str().unwrap().contains("duplicate id"));
+
+    registry.publish(json!([prompt("Not Kebab", "One")]), "\"v3\"");
+    let (ok, err) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(!ok);
+    assert!(err["message"].as_str().unwrap().contains("kebab-case"));
+
+    assert_eq!(home.json(&["list"]), before);
+    let (_, status) = jfp(&home, &registry.url(), &["status"]);
+    assert_eq!(status["cache"], Value::Null);
+}
+
+#[test]
+fn http_and_network_failures_are_reported() {
+    let home = TestHome::new();
+    let registry = Registry::start(json!([prompt("remote-one", "One")]));
+    registry.fail_with(503, "maintenance");
+
+    let (ok, err) = jfp(&home, &registry.url(), &["refresh"]);
+    assert!(!ok);
+    assert_eq!(err["error"], "http_error");
+    assert_eq!(err["status"], 503);
+
+    let unreachable = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/api/prompts", listener.local_addr().unwrap())
+    };
+    let (ok, err) = jfp(&home, &unreachable, &["refresh"]);
+    assert!(!ok);
+    assert_eq!(err["error"], "network_error");
+}
+
+#[test]
+fn status_reports_cache_age_sources_and_staleness() {
+    let home = TestHome::new();
+    home.add_prompt(json!({
+        "id": "greeting",
+        "title": "Greeting",
+        "description": "Say hello",
+        "category": "automation",
+        "tags": [],
+        "author": "Test",
+        "content": "Hello"
+    }));
+    let registry = Registry::start(json!([prompt("idea-wizard", "Remote"), prompt("remote-one", "One")]));
+
+    let (ok, before) = jfp(&home, &registry.url(), &["status"]);
+    assert!(ok);
+    assert_eq!(before["registry"], json!({ "url": registry.url(), "ttl_seconds": 3600 }));
+    assert_eq!(before["cache"], Value::Null);
+    assert_eq!(before["stale"], true);
+    let bundled = before["prompts"]["bundled"].as_u64().unwrap();
+    assert!(bundled > 0);
+    assert_eq!(before["prompts"]["remote"], 0);
+    assert_eq!(before["prompts"]["local"], 1);
+    assert_eq!(before["prompts"]["total"], bundled + 1);
+
+    jfp(&home, &registry.url(), &["refresh"]);
+    let (_, after) = jfp(&home, &registry.url(), &["status"]);
+    assert_eq!(after["stale"], false);
+    assert_eq!(after["cache"]["url"], registry.url());
+    assert_eq!(after["cache"]["etag"], "\"v1\"");
+    assert_eq!(after["cache"]["prompt_count"], 2);
+    assert!(after["cache"]["age_seconds"].as_i64().unwrap() < 60);
+    assert_eq!(after["prompts"]["bundled"], bundled - 1);
+    assert_eq!(after["prompts"]["remote"], 2);
+    assert_eq!(after["prompts"]["local"], 1);
+
+    // A zero TTL makes any cache stale, as does pointing at another registry
+    let output = home
+        .command()
+        .env("JFP_REGISTRY_URL", registry.url())
+        .env("JFP_CACHE_TTL", "0")
+        .args(["--json", "status"])
+        .output()
+        .unwrap();
+    let expired: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(expired["stale"], true);
+    let (_, moved) = jfp(&home, "http://127.0.0.1:9/elsewhere", &["status"]);
+    assert_eq!(moved["stale"], true);
+}
@@ -0,0 +1,130 @@
+//! Embedded registry snapshot: seeding and upgrades
+
+mod common;
+
+use common::TestHome;
+use rusqlite::{Connection, params};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn bundled_hash(conn: &Connection) -> Option<String> {
+    conn.query_row("SELECT bundled_sha256 FROM sync_meta WHERE id = 1", [], |row| row.get(0))
+        .unwrap()
+}
+
+/// Rewrite a stored prompt's content, as another writer would
+fn edit_content(conn: &Connection, id: &str, content: &str) {
+    conn.execute(
+        "UPDATE prompts SET content = ?2, data = json_set(data, '$.content', ?2) WHERE id = ?1",
+        params![id, content],
+    )
+    .unwrap();
+}
+
+/// Pretend the stored copy of `id` is what the snapshot last wrote
+fn mark_seeded(conn: &Connection, id: &str) {
+    let data: String = conn
+        .query_row("SELECT data FROM prompts WHERE id = ?1", [id], |row| row.get(0))
+        .unwrap();
+    conn.execute(
+        "UPDATE seeded_prompts SET sha256 = ?2 WHERE id = ?1",
+        params![id, sha256_hex(&data)],
+    )
+    .unwrap();
+}
+
+fn content(home: &TestHome, id: &str) -> Value {
+    home.json(&["show", id])["content"].clone()
+}
+
+#[test]
+fn fresh_library_records_snapshot_and_prompt_hashes() {
+    let home = TestHome::new();
+    let count = home.json(&["list"])["count"].as_u64().unwrap();
+
+    let conn = Connection::open(home.db_path()).unwrap();
+    let hash = bundled_hash(&conn).expect("snapshot hash recorded");
+    assert_eq!(hash.len(), 64);
+    assert!(hash.bytes().all(|b| b.is_ascii_hexdigit()));
+
+    let mut stmt = conn
+        .prepare("SELECT s.sha256, p.data FROM seeded_prompts s JOIN prompts p ON p.id = s.id")
+        .unwrap();
+    let rows: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows.len() as u64, count);
+    assert!(rows.iter().all(|(seeded, data)| *seeded == sha256_hex(data)));
+}
+
+#[test]
+fn applied_snapshot_is_not_reapplied() {
+    let home = TestHome::new();
+    let original = content(&home, "idea-wizard");
+
+    let conn = Connection::open(home.db_path()).unwrap();
+    edit_content(&conn, "idea-wizard", "Older wording");
+    mark_seeded(&conn, "idea-wizard");
+
+    assert_eq!(content(&home, "idea-wizard"), "Older wording");
+    assert_ne!(original, "Older wording");
+}
+
+#[test]
+fn new_snapshot_upgrades_only_prompts_nobody_changed() {
+    let home = TestHome::new();
+    let bundled = content(&home, "idea-wizard");
+    let conn = Connection::open(home.db_path()).unwrap();
+    let current = bundled_hash(&conn);
+
+    // As if an older snapshot had been applied...
+    conn.execute("UPDATE sync_meta SET bundled_sha256 = 'older' WHERE id = 1", [])
+        .unwrap();
+    // ...with different wording for one prompt
+    edit_content(&conn, "idea-wizard", "Older wording");
+    mark_seeded(&conn, "idea-wizard");
+    // A prompt edited locally since it was seeded
+    edit_content(&conn, "readme-reviser", "My own wording");
+    // A prompt removed after seeding
+    conn.execute("DELETE FROM prompts WHERE id = 'robot-mode-maker'", []).unwrap();
+    // A prompt the older snapshot did not have
+    conn.execute("DELETE FROM prompts WHERE id = 'bug-hunter'", []).unwrap();
+    conn.execute("DELETE FROM seeded_prompts WHERE id = 'bug-hunter'", []).unwrap();
+
+    assert_eq!(content(&home, "idea-wizard"), bundled);
+    assert_eq!(content(&home, "readme-reviser"), "My own wording");
+    let output = home.run(&["--json", "show", "robot-mode-maker"]);
+    assert!(!output.status.success());
+    assert!(content(&home, "bug-hunter").is_string());
+    assert_eq!(bundled_hash(&conn), current);
+
+    // The upgraded prompt is searchable under its new text again
+    let results = home.json(&["search", "Older wording"]);
+    assert!(results["results"].as_array().unwrap().iter().all(|r| r["id"] != "idea-wizard"));
+}
+
+#[test]
+fn new_snapshot_upgrades_bundles_and_workflows() {
+    let home = TestHome::new();
+    let bundled = home.json(&["bundle", "getting-started"])["title"].clone();
+    let conn = Connection::open(home.db_path()).unwrap();
+
+    // As if an older snapshot had titled a bundle differently and had no workflows
+    conn.execute("UPDATE sync_meta SET bundled_sha256 = 'older' WHERE id = 1", [])
+        .unwrap();
+    conn.execute(
+        "UPDATE bundles SET title = 'Old', data = json_set(data, '$.title', 'Old') WHERE id = 'getting-started'",
+        [],
+    )
+    .unwrap();
+    conn.execute("DELETE FROM workflows", []).unwrap();
+
+    assert_eq!(home.json(&["bundle", "getting-started"])["title"], bundled);
+    assert_eq!(home.json(&["workflows"])["count"], 1);
+}
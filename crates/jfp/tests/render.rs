@@ -0,0 +1,314 @@
+//! Template rendering with typed variables
+
+mod common;
+
+use common::TestHome;
+use serde_json::{Value, json};
+
+fn release_notes() -> Value {
+    json!({
+        "id": "release-notes",
+        "title": "Release Notes",
+        "description": "Draft release notes",
+        "category": "documentation",
+        "tags": ["release"],
+        "author": "Test",
+        "content": "Write {{TONE}} notes for {{ PROJECT_NAME }} on {{GIT_BRANCH}}.\n\n{{CHANGES}}\n{{FOOTER}} {{UNKNOWN}}",
+        "variables": [
+            { "name": "TONE", "label": "Tone", "type": "select", "options": ["formal", "casual"], "required": true },
+            { "name": "CHANGES", "label": "Changes", "type": "file", "required": true },
+            { "name": "FOOTER", "label": "Footer", "type": "text", "default": "Thanks!" }
+        ]
+    })
+}
+
+/// A project directory on a known branch with a changes file
+fn project(home: &TestHome) -> std::path::PathBuf {
+    let dir = home.path().join("acme");
+    std::fs::create_dir_all(dir.join(".git")).unwrap();
+    std::fs::write(dir.join(".git/HEAD"), "ref: refs/heads/feature/render\n").unwrap();
+    std::fs::write(dir.join("CHANGES.md"), "- Added rendering").unwrap();
+    dir
+}
+
+fn render(home: &TestHome, cwd: &std::path::Path, args: &[&str]) -> std::process::Output {
+    home.command()
+        .current_dir(cwd)
+        .args([&["--json", "render", "release-notes"], args].concat())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn renders_typed_variables_with_dynamic_defaults() {
+    let home = TestHome::new();
+    home.add_prompt(release_notes());
+    let cwd = project(&home);
+
+    let output = render(&home, &cwd, &["--TONE=casual", "--var", "CHANGES=CHANGES.md"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(
+        out["rendered"],
+        "Write casual notes for acme on feature/render.\n\n- Added rendering\nThanks! {{UNKNOWN}}"
+    );
+    assert_eq!(out["variables"]["GIT_BRANCH"], "feature/render");
+    assert_eq!(out["variables"]["CWD"], cwd.display().to_string());
+    assert_eq!(out["unresolved"], json!(["UNKNOWN"]));
+}
+
+#[test]
+fn missing_required_variables_are_listed() {
+    let home = TestHome::new();
+    home.add_prompt(release_notes());
+    let cwd = project(&home);
+
+    let output = render(&home, &cwd, &[]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "missing_variables");
+    assert_eq!(err["missing"], json!(["TONE", "CHANGES"]));
+}
+
+#[test]
+fn invalid_select_value_is_rejected() {
+    let home = TestHome::new();
+    home.add_prompt(release_notes());
+    let cwd = project(&home);
+
+    let output = render(&home, &cwd, &["--TONE=angry", "--CHANGES=CHANGES.md"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "invalid_option");
+    assert_eq!(err["variable"], "TONE");
+    assert_eq!(err["options"], json!(["formal", "casual"]));
+}
+
+#[test]
+fn missing_file_variable_is_an_error() {
+    let home = TestHome::new();
+    home.add_prompt(release_notes());
+    let cwd = project(&home);
+
+    let output = render(&home, &cwd, &["--TONE=formal", "--CHANGES=nope.md"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "variable_error");
+    assert_eq!(err["variable"], "CHANGES");
+}
+
+#[test]
+fn unknown_prompt_is_not_found() {
+    let home = TestHome::new();
+    let output = home.run(&["--json", "render", "no-such-prompt"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_found");
+}
+
+/// A source tree to feed in as context
+fn sources(home: &TestHome) -> std::path::PathBuf {
+    let dir = home.path().join("work");
+    std::fs::create_dir_all(dir.join("src/nested")).unwrap();
+    std::fs::create_dir_all(dir.join("src/.cache")).unwrap();
+    std::fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(dir.join("src/nested/lib.rs"), "pub fn lib() {}\n").unwrap();
+    std::fs::write(dir.join("src/.cache/blob"), "hidden").unwrap();
+    std::fs::write(dir.join("notes.md"), "ünïcödé notes").unwrap();
+    std::fs::write(dir.join("logo.png"), b"\x89PNG\x00\x00").unwrap();
+    dir
+}
+
+fn render_context(home: &TestHome, cwd: &std::path::Path, args: &[&str]) -> Value {
+    let output = home
+        .command()
+        .current_dir(cwd)
+        .args([&["--json", "render", "readme-reviser"], args].concat())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn sources_of(out: &Value) -> Vec<&str> {
+    out["context"]["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["source"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn context_expands_directories_and_globs_in_order() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+
+    let out = render_context(&home, &cwd, &["--context", "src", "--context", "*.md", "--context", "src/main.rs"]);
+    assert_eq!(sources_of(&out), ["src/main.rs", "src/nested/lib.rs", "notes.md"]);
+    assert_eq!(out["context"]["truncated"], false);
+
+    let rendered = out["rendered"].as_str().unwrap();
+    let main = rendered.find("### src/main.rs").unwrap();
+    let lib = rendered.find("### src/nested/lib.rs").unwrap();
+    assert!(rendered.contains("## Context"));
+    assert!(main < lib);
+    assert!(!rendered.contains("hidden"));
+}
+
+#[test]
+fn context_budget_truncates_deterministically() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+    let args = ["--context", "src", "--context", "notes.md", "--max-context", "30"];
+
+    let first = render_context(&home, &cwd, &args);
+    let second = render_context(&home, &cwd, &args);
+    assert_eq!(first, second);
+
+    let context = &first["context"];
+    assert_eq!(context["budget_bytes"], 30);
+    assert_eq!(context["included_bytes"], 29);
+    assert_eq!(context["truncated"], true);
+
+    // 13 + 16 bytes of source, then the budget ends inside "ü" (2 bytes)
+    let sources = context["sources"].as_array().unwrap();
+    assert_eq!(sources[1]["truncated"], false);
+    assert_eq!(sources[2]["included_bytes"], 0);
+    assert_eq!(sources[2]["truncated"], true);
+    assert!(first["rendered"].as_str().unwrap().contains("[Context truncated to 29 of 46 bytes; 1 source(s) omitted]"));
+}
+
+#[test]
+fn token_budget_tightens_byte_budget() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+
+    let out = render_context(&home, &cwd, &["--context", "src", "--max-context-tokens", "5"]);
+    assert_eq!(out["context"]["budget_bytes"], 20);
+    assert_eq!(out["context"]["estimated_tokens"], 5);
+}
+
+#[test]
+fn binary_files_are_skipped() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+
+    let out = render_context(&home, &cwd, &["--context", "logo.png", "--context", "notes.md"]);
+    assert_eq!(out["context"]["sources"][0]["skipped"], "binary");
+    assert!(!out["rendered"].as_str().unwrap().contains("PNG"));
+}
+
+#[test]
+fn stdin_context_comes_first() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+
+    let mut child = home
+        .command()
+        .current_dir(&cwd)
+        .args(["--json", "render", "readme-reviser", "--stdin", "--context", "notes.md"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    {
+        use std::io::Write;
+        child.stdin.take().unwrap().write_all(b"git diff output").unwrap();
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(sources_of(&out), ["stdin", "notes.md"]);
+    assert!(out["rendered"].as_str().unwrap().contains("### stdin\n\n```\ngit diff output\n```"));
+}
+
+#[test]
+fn large_stdin_reports_its_real_size() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+
+    let mut child = home
+        .command()
+        .current_dir(&cwd)
+        .args(["--json", "render", "readme-reviser", "--stdin", "--max-context", "100"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    {
+        use std::io::Write;
+        child.stdin.take().unwrap().write_all(&[b'x'; 300_000]).unwrap();
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let out: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let stdin = &out["context"]["sources"][0];
+    assert_eq!(stdin["bytes"], 300_000);
+    assert_eq!(stdin["included_bytes"], 100);
+    assert_eq!(stdin["truncated"], true);
+    assert_eq!(out["context"]["total_bytes"], 300_000);
+}
+
+#[test]
+fn binary_files_past_the_budget_are_still_skipped() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+
+    let out = render_context(&home, &cwd, &["--context", "notes.md", "--context", "logo.png", "--max-context", "5"]);
+    let logo = &out["context"]["sources"][1];
+    assert_eq!(logo["skipped"], "binary");
+    assert_eq!(logo["truncated"], false);
+}
+
+#[test]
+fn invalid_utf8_counts_the_text_included() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+    std::fs::write(cwd.join("latin1.txt"), b"caf\xe9 au lait").unwrap();
+
+    let out = render_context(&home, &cwd, &["--context", "latin1.txt"]);
+    let part = &out["context"]["sources"][0];
+    assert_eq!(part["bytes"], 12);
+    // The invalid byte becomes U+FFFD, three bytes of text
+    assert_eq!(part["included_bytes"], "caf\u{FFFD} au lait".len());
+    assert_eq!(part["truncated"], false);
+    assert!(out["rendered"].as_str().unwrap().contains("caf\u{FFFD} au lait"));
+
+    // The budget holds for the replaced text too
+    let out = render_context(&home, &cwd, &["--context", "latin1.txt", "--max-context", "5"]);
+    assert_eq!(out["context"]["included_bytes"], 3);
+    assert_eq!(out["context"]["truncated"], true);
+}
+
+#[test]
+fn missing_context_path_is_an_error() {
+    let home = TestHome::new();
+    let cwd = sources(&home);
+
+    let output = home
+        .command()
+        .current_dir(&cwd)
+        .args(["--json", "render", "readme-reviser", "--context", "missing/*.rs"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "file_not_found");
+}
+
+#[test]
+fn fill_refuses_without_a_terminal() {
+    let home = TestHome::new();
+    home.add_prompt(release_notes());
+
+    // Test processes have piped stdio, exactly like an agent
+    let output = home.run(&["render", "release-notes", "--fill"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_a_tty");
+    assert!(output.stdout.is_empty());
+}
@@ -0,0 +1,336 @@
+//! Published output schemas: the `--envelope` fields, `jfp schema`, and
+//! snapshots that catch shape changes made without a version bump
+//!
+//! Snapshots live in `tests/fixtures/schemas`, one file per command, with
+//! descriptions stripped so doc comment edits don't count as changes. After
+//! bumping `SCHEMA_VERSION`, rerun with `JFP_UPDATE_SCHEMAS=1` to rewrite them.
+
+mod common;
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+use common::TestHome;
+use common::premium::{PremiumServer, token_response};
+use serde_json::{Value, json};
+
+fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/schemas")
+}
+
+fn commands(home: &TestHome) -> (u64, Vec<String>) {
+    let listing = home.json(&["schema"]);
+    let names = listing["commands"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_str().unwrap().to_string())
+        .collect();
+    (listing["schema_version"].as_u64().unwrap(), names)
+}
+
+fn schema(home: &TestHome, command: &str) -> Value {
+    let args: Vec<&str> = ["schema"].into_iter().chain(command.split(' ')).collect();
+    home.json(&args)
+}
+
+/// The schema without `description` annotations
+fn shape(mut schema: Value) -> Value {
+    fn strip(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                map.retain(|key, v| !(key == "description" && v.is_string()));
+                map.values_mut().for_each(strip);
+            }
+            Value::Array(items) => items.iter_mut().for_each(strip),
+            _ => {}
+        }
+    }
+    strip(&mut schema);
+    schema
+}
+
+#[test]
+fn envelope_fields_are_opt_in() {
+    let home = TestHome::new();
+    let (version, _) = commands(&home);
+
+    let plain = home.json(&["list"]);
+    assert!(plain.get("schema_version").is_none());
+    assert!(plain.get("command").is_none());
+
+    let wrapped = home.json(&["--envelope", "list"]);
+    assert_eq!(wrapped["schema_version"], version);
+    assert_eq!(wrapped["command"], "list");
+    assert_eq!(wrapped["prompts"], plain["prompts"]);
+
+    // Aliases report the command's name; nested commands the full path
+    assert_eq!(home.json(&["--envelope", "ls"])["command"], "list");
+    assert_eq!(home.json(&["--envelope", "workflow", "show", "new-feature"])["command"], "workflow show");
+}
+
+/// Where `value` breaks `schema`, for the keywords the generated schemas
+/// use: `$ref`, `type`, `const`, `enum`, `anyOf`/`oneOf`/`allOf`,
+/// `properties`, `required`, `additionalProperties`, `items` and `minimum`
+fn violations(value: &Value, schema: &Value, defs: &Value, at: &str, out: &mut Vec<String>) {
+    if schema == &Value::Bool(false) {
+        out.push(format!("{}: not allowed", at));
+    }
+    if let Some(name) = schema.get("$ref").and_then(Value::as_str).and_then(|r| r.strip_prefix("#/$defs/")) {
+        violations(value, &defs[name], defs, at, out);
+    }
+    let matches = |branch: &Value| {
+        let mut problems = Vec::new();
+        violations(value, branch, defs, at, &mut problems);
+        problems.is_empty()
+    };
+    if let Some(branches) = schema.get("anyOf").and_then(Value::as_array)
+        && !branches.iter().any(matches)
+    {
+        out.push(format!("{}: matches no anyOf branch: {}", at, value));
+    }
+    if let Some(branches) = schema.get("oneOf").and_then(Value::as_array)
+        && branches.iter().filter(|b| matches(b)).count() != 1
+    {
+        out.push(format!("{}: does not match exactly one oneOf branch: {}", at, value));
+    }
+    for branch in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+        violations(value, branch, defs, at, out);
+    }
+    if let Some(expected) = schema.get("const")
+        && value != expected
+    {
+        out.push(format!("{}: {} is not {}", at, value, expected));
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array)
+        && !options.contains(value)
+    {
+        out.push(format!("{}: {} is not one of {:?}", at, value, options));
+    }
+    if let Some(types) = schema.get("type") {
+        let types: Vec<&str> = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            single => single.as_str().into_iter().collect(),
+        };
+        let fits = |kind: &str| match kind {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => false,
+        };
+        if !types.iter().any(|kind| fits(kind)) {
+            out.push(format!("{}: {} is not {:?}", at, value, types));
+        }
+    }
+    if let (Some(minimum), Some(number)) = (schema.get("minimum").and_then(Value::as_f64), value.as_f64())
+        && number < minimum
+    {
+        out.push(format!("{}: {} is below {}", at, number, minimum));
+    }
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for key in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+            if !object.contains_key(key.as_str().unwrap()) {
+                out.push(format!("{}: missing required {}", at, key));
+            }
+        }
+        for (key, item) in object {
+            let path = format!("{}.{}", at, key);
+            match (properties.and_then(|p| p.get(key)), schema.get("additionalProperties")) {
+                (Some(property), _) => violations(item, property, defs, &path, out),
+                (None, Some(extra)) => violations(item, extra, defs, &path, out),
+                // Only object schemas list their properties
+                (None, None) if properties.is_some() => out.push(format!("{}: not in the schema", path)),
+                (None, None) => {}
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            violations(item, items, defs, &format!("{}[{}]", at, i), out);
+        }
+    }
+}
+
+/// Check an enveloped payload against the schema published as `name`
+fn conforms(home: &TestHome, name: &str, output: &Value) {
+    let schema = schema(home, name);
+    assert_eq!(schema["title"], format!("jfp {}", name));
+    assert_eq!(schema["properties"]["command"]["const"], name);
+    assert_eq!(output["command"], name);
+
+    let mut problems = Vec::new();
+    violations(output, &schema, schema.get("$defs").unwrap_or(&Value::Null), name, &mut problems);
+    assert!(problems.is_empty(), "{}", problems.join("\n"));
+}
+
+/// Premium and registry endpoints for the commands that go online
+fn stand_in_server() -> PremiumServer {
+    PremiumServer::start(|path, _| match path {
+        "/api/cli/device-code" => (
+            200,
+            json!({
+                "device_code": "device-1",
+                "user_code": "ABCD1234",
+                "verification_url": "https://example.com/device",
+                "expires_in": 600,
+                "interval": 1
+            }),
+        ),
+        "/api/cli/device-token" => (200, token_response("new-token", Some("refresh-1"))),
+        "/api/cli/sync" => (
+            200,
+            json!({
+                "prompts": [{
+                    "id": "saved",
+                    "title": "Saved",
+                    "content": "Saved body",
+                    "tags": ["saved"],
+                    "saved_at": "2026-01-02T03:04:05Z",
+                    "note_count": 0
+                }],
+                "total": 1,
+                "last_modified": "2026-02-01T00:00:00Z"
+            }),
+        ),
+        "/api/cli/collections" | "/api/cli/collections/favorites/prompts" => (200, json!({ "success": true })),
+        "/api/prompts" => (
+            200,
+            json!({ "prompts": [{
+                "id": "remote-one",
+                "title": "Remote one",
+                "category": "automation",
+                "tags": ["remote"],
+                "content": "Served by the stand-in registry"
+            }] }),
+        ),
+        _ => (404, json!({})),
+    })
+}
+
+/// Every published schema against its command's real `--envelope` output
+#[test]
+fn schemas_describe_the_payloads() {
+    let home = TestHome::new();
+    let server = stand_in_server();
+    // No browser or clipboard tools to reach for
+    let bin = home.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let out = home.path().join("out");
+
+    let envelope = |args: &[&str]| -> Value {
+        let output = home
+            .command()
+            .env("PATH", &bin)
+            .env("JFP_PREMIUM_URL", server.url())
+            .env("JFP_REGISTRY_URL", format!("{}/api/prompts", server.url()))
+            .args(["--json", "--envelope"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        // `login` prints a progress line before its payload
+        let documents: Vec<Value> =
+            serde_json::Deserializer::from_slice(&output.stdout).into_iter().collect::<Result<_, _>>().unwrap();
+        documents.into_iter().last().expect("a payload")
+    };
+    let mut checked = BTreeSet::new();
+    let mut check = |name: &str, args: &[&str]| -> Value {
+        let output = envelope(args);
+        conforms(&home, name, &output);
+        checked.insert(name.to_string());
+        output
+    };
+
+    let dir = out.to_str().unwrap();
+    check("about", &["about"]);
+    check("list", &["list"]);
+    check("search", &["search", "ideas"]);
+    check("show", &["show", "idea-wizard"]);
+    check("categories", &["categories"]);
+    check("tags", &["tags"]);
+    check("random", &["random"]);
+    check("open", &["open", "idea-wizard"]);
+    check("status", &["status"]);
+    check("copy", &["copy", "idea-wizard", "--print"]);
+    check("export", &["export", "idea-wizard", "--output-dir", dir]);
+    check("render", &["render", "idea-wizard"]);
+    check("suggest", &["suggest", "write docs"]);
+    check("bundles", &["bundles"]);
+    check("bundle", &["bundle", "getting-started"]);
+    check("workflows", &["workflows"]);
+    check("workflow show", &["workflow", "show", "new-feature"]);
+    check("workflow run", &["workflow", "run", "new-feature"]);
+    check("serve config", &["serve", "--config"]);
+    check("sync status", &["sync", "--status"]);
+
+    check("collections create", &["collections", "create", "favorites"]);
+    check("collections add", &["collections", "add", "favorites", "idea-wizard", "bug-hunter"]);
+    check("collections remove", &["collections", "remove", "favorites", "bug-hunter"]);
+    check("collections show", &["collections", "show", "favorites"]);
+    check("collections", &["collections"]);
+    check("collections export", &["collections", "export", "favorites", "--output-dir", dir, "--on-conflict", "rename"]);
+
+    check("notes add", &["notes", "idea-wizard", "--add", "Works well"]);
+    let notes = check("notes", &["notes", "idea-wizard"]);
+    let note = notes["notes"][0]["id"].as_str().unwrap();
+    check("notes delete", &["notes", "idea-wizard", "--delete", note]);
+
+    check("login", &["login", "--remote"]);
+    check("whoami", &["whoami"]);
+    check("sync", &["sync"]);
+    check("collections sync", &["collections", "sync"]);
+    check("refresh", &["refresh"]);
+    check("logout", &["logout"]);
+
+    let (_, names) = commands(&home);
+    assert_eq!(checked, names.into_iter().collect::<BTreeSet<_>>());
+
+    let output = home.run(&["--json", "schema", "doctor"]);
+    assert_eq!(output.status.code(), Some(1));
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_found");
+    assert!(err["available"].as_array().unwrap().iter().any(|c| c == "list"));
+}
+
+#[test]
+fn schema_changes_come_with_a_version_bump() {
+    let home = TestHome::new();
+    let (version, names) = commands(&home);
+    let update = std::env::var_os("JFP_UPDATE_SCHEMAS").is_some();
+    let dir = snapshot_dir();
+
+    let mut problems = Vec::new();
+    for name in &names {
+        let current = shape(schema(&home, name));
+        let path = dir.join(format!("{}.json", name.replace(' ', "-")));
+        let snapshot: Option<Value> = fs::read(&path).ok().map(|bytes| serde_json::from_slice(&bytes).unwrap());
+        if snapshot.as_ref() == Some(&current) {
+            continue;
+        }
+        let snapshot_version = snapshot.as_ref().and_then(|s| s["properties"]["schema_version"]["const"].as_u64());
+        if snapshot_version == Some(version) {
+            problems.push(format!("{}: output shape changed without bumping SCHEMA_VERSION", name));
+        } else if update {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(&path, serde_json::to_string_pretty(&current).unwrap() + "\n").unwrap();
+        } else {
+            problems.push(format!("{}: snapshot is stale; rerun with JFP_UPDATE_SCHEMAS=1", name));
+        }
+    }
+
+    // Every snapshot belongs to a published schema
+    for entry in fs::read_dir(&dir).unwrap() {
+        let file = entry.unwrap().file_name().into_string().unwrap();
+        let name = file.trim_end_matches(".json").replace('-', " ");
+        if !names.contains(&name) {
+            problems.push(format!("{}: snapshot for a command without a schema", file));
+        }
+    }
+    assert!(problems.is_empty(), "{}", problems.join("\n"));
+}
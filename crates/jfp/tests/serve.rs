@@ -0,0 +1,193 @@
+//! MCP server over stdio
+
+mod common;
+
+use std::io::Write;
+use std::process::Stdio;
+
+use common::TestHome;
+use serde_json::{Value, json};
+
+/// Send `messages` to `jfp serve`, one per line, and collect its responses
+fn exchange(home: &TestHome, messages: &[Value]) -> Vec<Value> {
+    let mut child = home
+        .command()
+        .arg("serve")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    {
+        let mut stdin = child.stdin.take().unwrap();
+        for message in messages {
+            writeln!(stdin, "{}", message).unwrap();
+        }
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is one JSON-RPC message"))
+        .collect()
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+#[test]
+fn initialize_negotiates_and_notifications_get_no_reply() {
+    let home = TestHome::new();
+    let responses = exchange(
+        &home,
+        &[
+            request(1, "initialize", json!({ "protocolVersion": "2024-11-05", "capabilities": {} })),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            request(2, "ping", json!({})),
+        ],
+    );
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "jeffreysprompts");
+    assert!(responses[0]["result"]["capabilities"]["prompts"].is_object());
+    assert_eq!(responses[1], json!({ "jsonrpc": "2.0", "id": 2, "result": {} }));
+}
+
+#[test]
+fn resources_list_and_read_prompt_uris() {
+    let home = TestHome::new();
+    let responses = exchange(
+        &home,
+        &[
+            request(1, "resources/list", json!({})),
+            request(2, "resources/read", json!({ "uri": "prompt://idea-wizard" })),
+            request(3, "resources/read", json!({ "uri": "prompt://nope" })),
+            request(4, "resources/read", json!({ "uri": "file:///etc/passwd" })),
+        ],
+    );
+    let resources = responses[0]["result"]["resources"].as_array().unwrap();
+    assert!(resources.iter().any(|r| r["uri"] == "prompt://idea-wizard" && r["mimeType"] == "text/plain"));
+
+    let contents = &responses[1]["result"]["contents"][0];
+    assert_eq!(contents["uri"], "prompt://idea-wizard");
+    assert!(contents["text"].as_str().unwrap().starts_with("Come up with your very best ideas"));
+
+    assert_eq!(responses[2]["error"]["code"], -32002);
+    assert_eq!(responses[3]["error"]["code"], -32602);
+}
+
+#[test]
+fn tools_search_and_render() {
+    let home = TestHome::new();
+    let responses = exchange(
+        &home,
+        &[
+            request(1, "tools/list", json!({})),
+            request(
+                2,
+                "tools/call",
+                json!({ "name": "search_prompts", "arguments": { "query": "ideas", "category": "ideation", "limit": 1 } }),
+            ),
+            request(
+                3,
+                "tools/call",
+                json!({ "name": "render_prompt", "arguments": { "id": "idea-wizard", "context": "src/main.rs" } }),
+            ),
+            request(4, "tools/call", json!({ "name": "render_prompt", "arguments": { "id": "nope" } })),
+            request(5, "tools/call", json!({ "name": "delete_everything", "arguments": {} })),
+        ],
+    );
+    let names: Vec<&str> = responses[0]["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["search_prompts", "render_prompt"]);
+
+    let search: Value = serde_json::from_str(responses[1]["result"]["content"][0]["text"].as_str().unwrap()).unwrap();
+    assert_eq!(search["total"], 1);
+    assert_eq!(search["results"][0]["category"], "ideation");
+
+    let rendered = responses[2]["result"]["content"][0]["text"].as_str().unwrap();
+    assert!(rendered.starts_with("Come up with your very best ideas"));
+    assert!(rendered.ends_with("\n\n---\n\n**Context:**\nsrc/main.rs"));
+
+    assert_eq!(responses[3]["result"]["isError"], true);
+    assert_eq!(responses[3]["result"]["content"][0]["text"], "Error: Prompt not found: nope");
+    assert_eq!(responses[4]["result"]["isError"], true);
+}
+
+#[test]
+fn prompts_expose_variables_as_arguments() {
+    let home = TestHome::new();
+    home.add_prompt(json!({
+        "id": "greeting",
+        "title": "Greeting",
+        "description": "Say hello",
+        "category": "automation",
+        "tags": [],
+        "author": "Test",
+        "content": "Hello {{NAME}}, {{MOOD}} day!",
+        "variables": [
+            { "name": "NAME", "label": "Name", "type": "text", "required": true },
+            { "name": "MOOD", "label": "Mood", "type": "text", "default": "nice" }
+        ]
+    }));
+    let responses = exchange(
+        &home,
+        &[
+            request(1, "prompts/list", json!({})),
+            request(2, "prompts/get", json!({ "name": "greeting", "arguments": { "NAME": "Ada" } })),
+            request(3, "prompts/get", json!({ "name": "greeting" })),
+        ],
+    );
+    let prompts = responses[0]["result"]["prompts"].as_array().unwrap();
+    let greeting = prompts.iter().find(|p| p["name"] == "greeting").unwrap();
+    assert_eq!(
+        greeting["arguments"],
+        json!([
+            { "name": "NAME", "description": "Name", "required": true },
+            { "name": "MOOD", "description": "Mood", "required": false }
+        ])
+    );
+
+    let message = &responses[1]["result"]["messages"][0];
+    assert_eq!(message["role"], "user");
+    assert_eq!(message["content"], json!({ "type": "text", "text": "Hello Ada, nice day!" }));
+
+    assert_eq!(responses[2]["error"]["code"], -32602);
+    assert!(responses[2]["error"]["message"].as_str().unwrap().contains("NAME"));
+}
+
+#[test]
+fn malformed_messages_and_unknown_methods_are_errors() {
+    let home = TestHome::new();
+    let mut child = home
+        .command()
+        .arg("serve")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    writeln!(child.stdin.take().unwrap(), "not json\n{}", request(7, "bogus/method", json!({}))).unwrap();
+    let output = child.wait_with_output().unwrap();
+    let responses: Vec<Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses[0]["id"], Value::Null);
+    assert_eq!(responses[0]["error"]["code"], -32700);
+    assert_eq!(responses[1]["id"], 7);
+    assert_eq!(responses[1]["error"]["code"], -32601);
+}
+
+#[test]
+fn config_prints_client_snippet() {
+    let home = TestHome::new();
+    let out = home.json(&["serve", "--config"]);
+    assert_eq!(out, json!({ "mcpServers": { "jeffreysprompts": { "command": "jfp", "args": ["serve"] } } }));
+}
@@ -0,0 +1,76 @@
+//! Storage and migration behavior
+
+mod common;
+
+use common::TestHome;
+use rusqlite::Connection;
+
+#[test]
+fn fresh_database_is_migrated_and_seeded() {
+    let home = TestHome::new();
+    let list = home.json(&["list"]);
+    assert!(list["count"].as_u64().unwrap() > 0);
+
+    let conn = Connection::open(home.db_path()).unwrap();
+    let (schema_version, source): (u32, String) = conn
+        .query_row(
+            "SELECT schema_version, source_of_truth FROM sync_meta WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    let applied: u32 = conn
+        .query_row("SELECT MAX(version) FROM schema_migrations", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(schema_version, applied);
+    assert_eq!(source, "sqlite");
+}
+
+#[test]
+fn reopening_keeps_existing_rows_and_migrations() {
+    let home = TestHome::new();
+    home.json(&["list"]);
+
+    let conn = Connection::open(home.db_path()).unwrap();
+    conn.execute("DELETE FROM prompts WHERE id <> 'idea-wizard'", [])
+        .unwrap();
+    let migrations_before: u32 = conn
+        .query_row("SELECT COUNT(*) FROM schema_migrations", [], |row| row.get(0))
+        .unwrap();
+
+    // A non-empty library must not be reseeded or rebuilt
+    let list = home.json(&["list"]);
+    assert_eq!(list["count"], 1);
+    assert_eq!(list["prompts"][0]["id"], "idea-wizard");
+
+    let migrations_after: u32 = conn
+        .query_row("SELECT COUNT(*) FROM schema_migrations", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(migrations_before, migrations_after);
+}
+
+#[test]
+fn newer_schema_is_refused_without_touching_data() {
+    let home = TestHome::new();
+    home.json(&["list"]);
+
+    let conn = Connection::open(home.db_path()).unwrap();
+    conn.execute(
+        "INSERT INTO schema_migrations (version, name, applied_at) VALUES (9999, 'future', 'now')",
+        [],
+    )
+    .unwrap();
+    let count: u32 = conn
+        .query_row("SELECT COUNT(*) FROM prompts", [], |row| row.get(0))
+        .unwrap();
+
+    let output = home.run(&["--json", "list"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("newer than this jfp supports"), "{stderr}");
+
+    let count_after: u32 = conn
+        .query_row("SELECT COUNT(*) FROM prompts", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, count_after);
+}
@@ -0,0 +1,81 @@
+//! Suggest command and semantic reranking
+
+mod common;
+
+use common::TestHome;
+use rusqlite::Connection;
+
+#[test]
+fn suggests_prompts_for_a_task() {
+    let home = TestHome::new();
+    let out = home.json(&["suggest", "find bugs in my code"]);
+    assert_eq!(out["suggestions"][0]["id"], "bug-hunter");
+    assert!(out["semantic"].is_null());
+    assert!(out["suggestions"][0]["blend"].is_null());
+}
+
+#[test]
+fn semantic_rerank_explains_the_blend() {
+    let home = TestHome::new();
+    let out = home.json(&["suggest", "improve documentation for my API", "--semantic"]);
+
+    assert_eq!(out["semantic"]["embedder"], "hash-v1");
+    assert_eq!(out["semantic"]["bm25_weight"], 0.4);
+    assert_eq!(out["semantic"]["semantic_weight"], 0.6);
+
+    let suggestions = out["suggestions"].as_array().unwrap();
+    assert!(!suggestions.is_empty());
+    let combined: Vec<f64> = suggestions
+        .iter()
+        .map(|s| {
+            let blend = &s["blend"];
+            let expected = 0.4 * blend["bm25_normalized"].as_f64().unwrap()
+                + 0.6 * blend["semantic"].as_f64().unwrap();
+            let combined = blend["combined"].as_f64().unwrap();
+            assert!((combined - expected).abs() < 1e-9);
+            combined
+        })
+        .collect();
+    assert!(combined.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[test]
+fn semantic_rerank_is_deterministic_and_caches_embeddings() {
+    let home = TestHome::new();
+    let first = home.json(&["suggest", "write tests", "--semantic"]);
+    let second = home.json(&["suggest", "write tests", "--semantic"]);
+    assert_eq!(first, second);
+
+    let conn = Connection::open(home.db_path()).unwrap();
+    let (rows, dims): (u32, u32) = conn
+        .query_row(
+            "SELECT COUNT(*), MIN(dimensions) FROM prompt_embeddings WHERE embedder = 'hash-v1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert!(rows > 0);
+    assert_eq!(dims, 128);
+}
+
+#[test]
+fn semantic_progress_goes_to_stderr() {
+    let home = TestHome::new();
+    let output = home.run(&["--format", "table", "suggest", "write tests", "--semantic"]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Applying semantic reranking"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Applying semantic reranking"));
+}
+
+#[test]
+fn rejects_empty_task_and_zero_limit() {
+    let home = TestHome::new();
+
+    let output = home.run(&["--json", "suggest", "   "]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("empty_task"));
+
+    let output = home.run(&["--json", "suggest", "docs", "--limit", "0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid_limit"));
+}
@@ -0,0 +1,224 @@
+//! Premium library sync against a stand-in sync endpoint
+
+mod common;
+
+use std::fs::File;
+
+use chrono::Duration;
+use common::TestHome;
+use common::premium::{PremiumServer, credentials_path, jfp, run, write_credentials};
+use serde_json::{Value, json};
+
+const SYNC_PATH: &str = "/api/cli/sync";
+
+fn saved(id: &str, title: &str) -> Value {
+    json!({
+        "id": id,
+        "title": title,
+        "content": format!("Content of {}", title),
+        "tags": ["saved"],
+        "saved_at": "2026-01-02T03:04:05Z",
+        "note_count": 1
+    })
+}
+
+fn library_dir(home: &TestHome) -> std::path::PathBuf {
+    home.path().join(".config").join("jfp").join("library")
+}
+
+fn mirror_lines(home: &TestHome) -> Vec<Value> {
+    std::fs::read_to_string(library_dir(home).join("library.jsonl"))
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn sync_stores_the_library_and_writes_a_jsonl_mirror() {
+    let home = TestHome::new();
+    write_credentials(&home, Duration::hours(1), None);
+    let server = PremiumServer::start(|path, nth| match (path, nth) {
+        (SYNC_PATH, 0) => (
+            200,
+            json!({
+                "prompts": [saved("zeta", "Zeta"), saved("alpha", "Alpha")],
+                "total": 2,
+                "last_modified": "2026-02-01T00:00:00Z"
+            }),
+        ),
+        (SYNC_PATH, _) => (
+            200,
+            json!({
+                "prompts": [saved("alpha", "Alpha v2"), saved("mid", "Mid")],
+                "total": 3,
+                "last_modified": "2026-03-01T00:00:00Z"
+            }),
+        ),
+        _ => (404, json!({})),
+    });
+
+    let (ok, out, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["synced"], true);
+    assert_eq!(out[0]["new_prompts"], 2);
+    assert_eq!(out[0]["total_prompts"], 2);
+    assert_eq!(out[0]["synced_at"], "2026-02-01T00:00:00Z");
+
+    let first = &server.seen(SYNC_PATH)[0];
+    assert_eq!(first.method, "GET");
+    assert_eq!(first.query, "");
+    assert_eq!(first.authorization.as_deref(), Some("Bearer old-token"));
+
+    // Sorted by id, unknown fields kept, nothing secret
+    let lines = mirror_lines(&home);
+    let ids: Vec<&str> = lines.iter().map(|p| p["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["alpha", "zeta"]);
+    assert_eq!(lines[0]["note_count"], 1);
+    let mirror = std::fs::read_to_string(library_dir(&home).join("library.jsonl")).unwrap();
+    assert!(mirror.ends_with("}\n") && !mirror.contains('\r'));
+    assert!(!mirror.contains("old-token"));
+
+    let meta: Value =
+        serde_json::from_str(&std::fs::read_to_string(library_dir(&home).join("library.meta.json")).unwrap()).unwrap();
+    assert_eq!(meta["source_of_truth"], "sqlite");
+    assert_eq!(meta["record_count"], 2);
+    assert_eq!(meta["last_synced_at"], "2026-02-01T00:00:00Z");
+    assert_eq!(meta["sqlite_sha256"], out[0]["jsonl_sha256"]);
+
+    // Incremental: only changes since the last sync, merged by id
+    let (ok, out, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(ok, "{}", err);
+    assert_eq!(server.seen(SYNC_PATH)[1].query, "since=2026-02-01T00%3A00%3A00Z");
+    assert_eq!(out[0]["new_prompts"], 2);
+    assert_eq!(out[0]["total_prompts"], 3);
+    let lines = mirror_lines(&home);
+    let titles: Vec<&str> = lines.iter().map(|p| p["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, ["Alpha v2", "Mid", "Zeta"]);
+
+    let (ok, out, _) = run(jfp(&home, &server, &["sync", "--status"]));
+    assert!(ok);
+    assert_eq!(out[0]["synced"], true);
+    assert_eq!(out[0]["last_sync"], "2026-03-01T00:00:00Z");
+    assert_eq!(out[0]["prompt_count"], 3);
+    assert_eq!(out[0]["mirror"]["in_sync"], true);
+    assert_eq!(out[0]["authenticated"], true);
+    assert_eq!(out[0]["user"], json!({ "email": "ada@example.com", "tier": "premium" }));
+}
+
+#[test]
+fn force_sync_replaces_the_library() {
+    let home = TestHome::new();
+    write_credentials(&home, Duration::hours(1), None);
+    let server = PremiumServer::start(|path, nth| match (path, nth) {
+        (SYNC_PATH, 0) => (200, json!({ "prompts": [saved("alpha", "Alpha"), saved("beta", "Beta")] })),
+        (SYNC_PATH, _) => (200, json!({ "prompts": [saved("gamma", "Gamma")], "last_modified": "2026-04-01T00:00:00Z" })),
+        _ => (404, json!({})),
+    });
+
+    let (ok, _, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(ok, "{}", err);
+    let (ok, out, err) = run(jfp(&home, &server, &["sync", "--force"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["force"], true);
+    assert_eq!(out[0]["total_prompts"], 1);
+    assert_eq!(server.seen(SYNC_PATH)[1].query, "");
+    let ids: Vec<Value> = mirror_lines(&home).iter().map(|p| p["id"].clone()).collect();
+    assert_eq!(ids, [json!("gamma")]);
+}
+
+#[test]
+fn unchanged_library_rewrites_an_identical_mirror() {
+    let home = TestHome::new();
+    write_credentials(&home, Duration::hours(1), None);
+    let server = PremiumServer::start(|path, nth| match (path, nth) {
+        (SYNC_PATH, 0) => (200, json!({ "prompts": [saved("alpha", "Alpha")], "last_modified": "2026-02-01T00:00:00Z" })),
+        (SYNC_PATH, _) => (200, json!({ "prompts": [], "last_modified": "2026-02-01T00:00:00Z" })),
+        _ => (404, json!({})),
+    });
+
+    let (_, first, _) = run(jfp(&home, &server, &["sync"]));
+    let before = std::fs::read(library_dir(&home).join("library.jsonl")).unwrap();
+    let (ok, second, _) = run(jfp(&home, &server, &["sync"]));
+    assert!(ok);
+    assert_eq!(second[0]["new_prompts"], 0);
+    assert_eq!(first[0]["jsonl_sha256"], second[0]["jsonl_sha256"]);
+    assert_eq!(std::fs::read(library_dir(&home).join("library.jsonl")).unwrap(), before);
+
+    // An edited mirror is reported as out of sync with the database
+    std::fs::write(library_dir(&home).join("library.jsonl"), "{}\n").unwrap();
+    let (_, status, _) = run(jfp(&home, &server, &["sync", "--status"]));
+    assert_eq!(status[0]["mirror"]["in_sync"], false);
+}
+
+#[test]
+fn sync_requires_a_premium_session() {
+    let home = TestHome::new();
+    let server = PremiumServer::start(|path, _| match path {
+        SYNC_PATH => (403, json!({ "error": "forbidden" })),
+        _ => (404, json!({})),
+    });
+
+    let (ok, _, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "not_authenticated");
+
+    let (ok, out, _) = run(jfp(&home, &server, &["sync", "--status"]));
+    assert!(ok);
+    assert_eq!(out[0]["synced"], false);
+    assert_eq!(out[0]["authenticated"], false);
+    assert_eq!(out[0]["user"], Value::Null);
+
+    // A free account is turned away before any request
+    write_credentials(&home, Duration::hours(1), None);
+    let mut credentials: Value = serde_json::from_str(&std::fs::read_to_string(credentials_path(&home)).unwrap()).unwrap();
+    credentials["tier"] = json!("free");
+    std::fs::write(credentials_path(&home), credentials.to_string()).unwrap();
+    let (ok, _, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "requires_premium");
+    assert_eq!(err["tier"], "free");
+    assert!(server.seen(SYNC_PATH).is_empty());
+
+    // The server has the last word on the tier
+    write_credentials(&home, Duration::hours(1), None);
+    let (ok, _, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "requires_premium");
+    assert!(!library_dir(&home).exists());
+}
+
+#[test]
+fn rejected_token_reports_an_expired_session() {
+    let home = TestHome::new();
+    write_credentials(&home, Duration::hours(1), None);
+    let server = PremiumServer::start(|_, _| (401, json!({ "error": "unauthorized" })));
+
+    let (ok, _, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(!ok);
+    assert_eq!(err["error"], "session_expired");
+}
+
+#[test]
+fn sync_waits_for_the_lock_then_gives_up() {
+    let home = TestHome::new();
+    write_credentials(&home, Duration::hours(1), None);
+    let server = PremiumServer::start(|_, _| (200, json!({ "prompts": [saved("alpha", "Alpha")] })));
+
+    let lock_path = home.path().join(".config").join("jfp").join("locks").join("sync.lock");
+    std::fs::create_dir_all(lock_path.parent().unwrap()).unwrap();
+    let held = File::create(&lock_path).unwrap();
+    held.lock().unwrap();
+
+    let mut command = jfp(&home, &server, &["sync"]);
+    command.env("JFP_BUSY_TIMEOUT", "0");
+    let (ok, _, err) = run(command);
+    assert!(!ok);
+    assert_eq!(err["error"], "sync_locked");
+    assert!(server.seen(SYNC_PATH).is_empty());
+
+    held.unlock().unwrap();
+    let (ok, out, err) = run(jfp(&home, &server, &["sync"]));
+    assert!(ok, "{}", err);
+    assert_eq!(out[0]["total_prompts"], 1);
+}
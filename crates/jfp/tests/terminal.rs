@@ -0,0 +1,94 @@
+//! Human output (`--format table`): width-aware layout, Unicode-safe
+//! truncation, tables, and no color codes off a terminal
+
+mod common;
+
+use std::process::Output;
+
+use common::TestHome;
+use serde_json::json;
+
+fn table(home: &TestHome, columns: usize, args: &[&str]) -> String {
+    let output = home
+        .command()
+        .env("COLUMNS", columns.to_string())
+        .args([&["--format", "table"], args].concat())
+        .output()
+        .unwrap();
+    succeeded(output)
+}
+
+fn succeeded(output: Output) -> String {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn long_multibyte_descriptions_are_truncated_to_the_terminal() {
+    let home = TestHome::new();
+    let description = "Crème brûlée façade naïve — résumé ".repeat(4) + "👩‍👩‍👧‍👦 family";
+    home.add_prompt(json!({
+        "id": "unicode",
+        "title": "Unicode",
+        "description": description,
+        "category": "testing",
+        "tags": ["emoji", "accents"],
+        "content": "Body",
+    }));
+
+    for columns in [40, 57, 61, 80] {
+        let out = table(&home, columns, &["list", "--category", "testing"]);
+        let line = out.lines().find(|l| l.trim_start().starts_with("Crème")).unwrap();
+        assert!(line.ends_with('…'), "{}", line);
+        assert!(line.chars().count() <= columns, "{} columns: {}", columns, line);
+    }
+
+    // Wrapped, not cut, where the whole description is shown
+    let shown = table(&home, 40, &["show", "unicode"]);
+    assert!(shown.contains("👩‍👩‍👧‍👦 family"));
+    assert!(shown.lines().filter(|l| l.contains("Crème")).count() > 1);
+}
+
+#[test]
+fn tags_fit_beside_the_category_on_narrow_terminals() {
+    let home = TestHome::new();
+    home.add_prompt(json!({
+        "id": "tagged",
+        "title": "Tagged",
+        "category": "testing",
+        "tags": ["regression", "integration", "snapshots"],
+        "content": "Body",
+    }));
+
+    for columns in [30, 36, 50] {
+        let out = table(&home, columns, &["list", "--category", "testing"]);
+        let line = out.lines().find(|l| l.contains("Tags:")).unwrap();
+        assert!(line.chars().count() <= columns, "{} columns: {}", columns, line);
+    }
+}
+
+#[test]
+fn categories_and_tags_are_tables() {
+    let home = TestHome::new();
+    let out = table(&home, 80, &["categories"]);
+    let mut lines = out.lines().skip_while(|l| !l.contains("CATEGORY"));
+    let header = lines.next().unwrap();
+    assert!(header.trim_start().starts_with("CATEGORY") && header.ends_with("PROMPTS"), "{}", header);
+    // Counts are right-aligned under the header
+    for row in lines.take_while(|l| !l.is_empty()) {
+        assert_eq!(row.len(), header.len(), "{}", row);
+        assert!(row.rsplit(' ').next().unwrap().parse::<usize>().is_ok(), "{}", row);
+    }
+
+    let out = table(&home, 80, &["tags"]);
+    assert!(out.lines().any(|l| l.trim_start().starts_with("TAG") && l.ends_with("PROMPTS")));
+}
+
+/// The opt-outs are unit-tested in cli/output.rs: stdout here is never a
+/// terminal
+#[test]
+fn no_color_codes_off_a_terminal() {
+    let home = TestHome::new();
+    let out = table(&home, 80, &["search", "ideas"]);
+    assert!(!out.contains('\x1b'), "{:?}", out);
+}
@@ -0,0 +1,171 @@
+//! Workflows: listing, running prompt chains and resuming saved runs
+
+mod common;
+
+use common::TestHome;
+use serde_json::{Value, json};
+
+/// A two-step workflow whose prompts share a required PRODUCT variable
+fn add_launch_workflow(home: &TestHome) {
+    home.add_prompt(json!({
+        "id": "launch-plan",
+        "title": "Launch Plan",
+        "description": "Plan a launch",
+        "category": "automation",
+        "tags": ["launch"],
+        "author": "Test",
+        "content": "Plan the launch of {{PRODUCT}}.",
+        "variables": [{ "name": "PRODUCT", "label": "Product", "type": "text", "required": true }]
+    }));
+    home.add_prompt(json!({
+        "id": "launch-post",
+        "title": "Launch Post",
+        "description": "Announce a launch",
+        "category": "documentation",
+        "tags": ["launch"],
+        "author": "Test",
+        "content": "Announce {{PRODUCT}} in a {{TONE}} tone.",
+        "variables": [
+            { "name": "PRODUCT", "label": "Product", "type": "text", "required": true },
+            { "name": "TONE", "label": "Tone", "type": "text", "required": true }
+        ]
+    }));
+
+    let workflow = json!({
+        "id": "launch",
+        "title": "Launch",
+        "description": "Plan and announce a launch",
+        "steps": [
+            { "id": "plan", "promptId": "launch-plan", "note": "Plan it" },
+            { "id": "announce", "promptId": "launch-post", "note": "Announce it" }
+        ],
+        "whenToUse": []
+    });
+    home.json(&["workflows"]);
+    let conn = rusqlite::Connection::open(home.db_path()).unwrap();
+    conn.execute(
+        "INSERT INTO workflows (id, title, data, stored_at) VALUES ('launch', 'Launch', ?1, datetime('now'))",
+        [workflow.to_string()],
+    )
+    .unwrap();
+}
+
+#[test]
+fn workflows_lists_bundled_chains() {
+    let home = TestHome::new();
+    let out = home.json(&["workflows"]);
+    assert_eq!(out["count"], 1);
+    assert_eq!(out["workflows"][0]["id"], "new-feature");
+    assert_eq!(out["workflows"][0]["step_count"], 2);
+
+    let out = home.json(&["workflow", "show", "new-feature"]);
+    assert_eq!(out["steps"][0]["promptId"], "idea-wizard");
+    let ids: Vec<&str> = out["prompts"].as_array().unwrap().iter().map(|p| p["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["idea-wizard", "readme-reviser"]);
+    assert!(out.get("unfinished_run").is_none());
+}
+
+#[test]
+fn run_renders_every_step_in_order() {
+    let home = TestHome::new();
+    let out = home.json(&["workflow", "run", "new-feature"]);
+    assert_eq!(out["workflow_id"], "new-feature");
+    assert_eq!(out["status"], "completed");
+    assert!(out["completed_at"].is_string());
+    assert!(out.get("next_step").is_none());
+
+    let steps = out["steps"].as_array().unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[0]["id"], "ideate");
+    assert_eq!(steps[0]["title"], "The Idea Wizard");
+    assert_eq!(steps[0]["status"], "done");
+    assert!(steps[0]["rendered"].as_str().unwrap().contains("Come up with your very best ideas"));
+    assert_eq!(steps[1]["prompt_id"], "readme-reviser");
+    assert_eq!(steps[1]["status"], "done");
+}
+
+#[test]
+fn variables_are_shared_across_steps() {
+    let home = TestHome::new();
+    add_launch_workflow(&home);
+    let out = home.json(&["workflow", "run", "launch", "--PRODUCT=Acme", "--var", "TONE=upbeat"]);
+    assert_eq!(out["variables"], json!({ "PRODUCT": "Acme", "TONE": "upbeat" }));
+    assert_eq!(out["steps"][0]["rendered"], "Plan the launch of Acme.");
+    assert_eq!(out["steps"][1]["rendered"], "Announce Acme in a upbeat tone.");
+}
+
+#[test]
+fn steps_limit_pauses_and_resume_continues_the_same_run() {
+    let home = TestHome::new();
+    let first = home.json(&["workflow", "run", "new-feature", "--steps", "1"]);
+    assert_eq!(first["status"], "in_progress");
+    assert_eq!(first["next_step"], "document");
+    assert_eq!(first["steps"][1]["status"], "pending");
+    assert!(first["steps"][1].get("rendered").is_none());
+
+    let show = home.json(&["workflow", "show", "new-feature"]);
+    assert_eq!(show["unfinished_run"], first["run_id"]);
+
+    let second = home.json(&["workflow", "run", "new-feature", "--resume"]);
+    assert_eq!(second["run_id"], first["run_id"]);
+    assert_eq!(second["status"], "completed");
+    assert_eq!(second["steps"][0]["rendered"], first["steps"][0]["rendered"]);
+    assert_eq!(second["steps"][0]["completed_at"], first["steps"][0]["completed_at"]);
+    assert_eq!(second["steps"][1]["status"], "done");
+
+    let output = home.run(&["--json", "workflow", "run", "new-feature", "--resume"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "no_run_to_resume");
+}
+
+#[test]
+fn missing_variable_stops_the_run_and_resume_accepts_it() {
+    let home = TestHome::new();
+    add_launch_workflow(&home);
+
+    let output = home.run(&["--json", "workflow", "run", "launch", "--PRODUCT=Acme"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "missing_variables");
+    assert_eq!(err["missing"], json!(["TONE"]));
+
+    let conn = rusqlite::Connection::open(home.db_path()).unwrap();
+    let (run_id, saved): (i64, i64) = conn
+        .query_row(
+            "SELECT r.id, COUNT(s.step_id) FROM workflow_runs r
+             LEFT JOIN workflow_run_steps s ON s.run_id = r.id GROUP BY r.id",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(saved, 1);
+
+    let run_arg = run_id.to_string();
+    let out = home.json(&["workflow", "run", "launch", "--resume", &run_arg, "--TONE=calm"]);
+    assert_eq!(out["run_id"], run_id);
+    assert_eq!(out["status"], "completed");
+    assert_eq!(out["variables"], json!({ "PRODUCT": "Acme", "TONE": "calm" }));
+    assert_eq!(out["steps"][1]["rendered"], "Announce Acme in a calm tone.");
+}
+
+#[test]
+fn unknown_workflow_and_missing_step_prompt_are_reported() {
+    let home = TestHome::new();
+    let output = home.run(&["--json", "workflow", "run", "nope"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "not_found");
+    assert_eq!(err["available"], json!(["new-feature"]));
+
+    let conn = rusqlite::Connection::open(home.db_path()).unwrap();
+    conn.execute("DELETE FROM prompts WHERE id = 'readme-reviser'", []).unwrap();
+    let show = home.json(&["workflow", "show", "new-feature"]);
+    assert_eq!(show["missing"], json!(["readme-reviser"]));
+
+    let output = home.run(&["--json", "workflow", "run", "new-feature"]);
+    assert!(!output.status.success());
+    let err: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["error"], "prompt_not_found");
+    assert_eq!(err["step"], "document");
+}